@@ -0,0 +1,94 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Times the parser on large generated programs
+//The lexer is timed on one generated program, the large programs repeat the tokens of its procedures so they are quick to make
+//Run with: cargo bench --bench parse
+
+//package imports
+use {
+    compiler::models::{lexer::{Lexer, Token}, parser::Parser},
+    compiler::tokenTypeEnum,
+    std::time::{Duration, Instant},
+};
+
+//How many times each program is parsed, the fastest run is reported
+const RUNS: usize = 5;
+
+//How many procedures are in the program that is lexed
+const LEXED_PROCS: usize = 20;
+
+//A program with the given number of procedures, each with a loop, an if and a long expression
+fn generateProgram(procCount: usize) -> String {
+    let mut source = String::from("program bench is\nvariable total : integer;\nvariable values : integer[100];\n");
+    for i in 0..procCount {
+        source.push_str(&format!("procedure proc{} : integer(variable x : integer, variable y : float)\n", i));
+        source.push_str("    variable i : integer;\n    variable acc : integer;\nbegin\n    acc := 0;\n");
+        source.push_str("    for (i := 0; i < x)\n        if (i < 50) then\n            acc := acc + values[i] * 2 - (i + 1) / 3;\n");
+        source.push_str("        else\n            acc := acc - 1;\n        end if;\n    end for;\n");
+        source.push_str(&format!("    acc := acc + {};\n", (0..20).map(|n| format!("(x + {})", n)).collect::<Vec<String>>().join(" + ")));
+        source.push_str("    return acc;\nend procedure;\n");
+    }
+    source.push_str("begin\n    total := 0;\n");
+    for i in 0..procCount {
+        source.push_str(&format!("    total := total + proc{}({}, 1.5);\n", i, i));
+    }
+    source.push_str("    putinteger(total);\nend program.\n");
+    return source;
+}
+
+//The fastest of a few runs
+fn fastest<F: FnMut()>(mut run: F) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        run();
+        best = best.min(start.elapsed());
+    }
+    return best;
+}
+
+//Splits the tokens into the program header, one procedure and the rest of the program
+fn splitProgram(tokens: &[Token]) -> (Vec<Token>, Vec<Token>, Vec<Token>) {
+    let procStart = tokens.iter().position(|token| token.tt == tokenTypeEnum::PROCEDURE).expect("The program has no procedure");
+    let procEnd = tokens.iter().position(|token| token.tt == tokenTypeEnum::END_PROCEDURE).expect("The procedure has no end") + 2;
+    let lastProcEnd = tokens.iter().rposition(|token| token.tt == tokenTypeEnum::END_PROCEDURE).expect("The procedure has no end") + 2;
+    return (tokens[..procStart].to_vec(), tokens[procStart..procEnd].to_vec(), tokens[lastProcEnd..].to_vec());
+}
+
+fn main() {
+    let source = generateProgram(LEXED_PROCS);
+    let lexTime = fastest(|| {
+        let mut lexer = Lexer::newFromString("bench.src", source.clone());
+        lexer.scanThrough();
+    });
+    let mut lexer = Lexer::newFromString("bench.src", source.clone());
+    lexer.scanThrough();
+    println!("lexing {} lines ({} tokens): {:?}\n", source.lines().count(), lexer.tokenList.len(), lexTime);
+    let (header, procedure, body) = splitProgram(&lexer.tokenList);
+
+    println!("{:>10} {:>10} {:>12} {:>14}", "procedures", "tokens", "parse", "tokens/second");
+    for procCount in [100, 1000, 10000, 50000] {
+        let mut tokens = header.clone();
+        for _ in 0..procCount {
+            tokens.extend(procedure.iter().cloned());
+        }
+        tokens.extend(body.iter().cloned());
+
+        let parseTime = fastest(|| {
+            let mut parser = Parser::fromTokens(&tokens);
+            if parser.startParse().is_err() {
+                panic!("The generated program did not parse");
+            }
+        });
+
+        let rate = tokens.len() as f64 / parseTime.as_secs_f64();
+        println!("{:>10} {:>10} {:>12?} {:>14.0}", procCount, tokens.len(), parseTime, rate);
+    }
+}
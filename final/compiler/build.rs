@@ -1,8 +1,33 @@
+//Rules
+#![allow(non_snake_case)]
+
+//Builds the runtime library (funcLib) that the compiler links into the executables it produces
+//funcLib is also a regular path dependency, that copy is linked into the compiler for the JIT and the interpreter
+//The archive is put next to the compiler executable, where the linker looks for it, so a plain cargo build is enough
+
+use std::{env, fs, path::PathBuf, process::Command};
+
 fn main() {
+    let manifestDir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let outDir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let libSource = manifestDir.join("funcLib").join("src").join("lib.rs");
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", libSource.display());
+
+    //funcLib has no dependencies, so rustc can build the staticlib without a nested cargo locking the target directory
+    let rustc = env::var("RUSTC").unwrap_or("rustc".to_string());
+    let archive = outDir.join("libfuncLib.a");
+    let status = Command::new(&rustc)
+        .args(["--edition", "2021", "--crate-type", "staticlib", "--crate-name", "funcLib", "-C", "opt-level=3"])
+        .arg("-o").arg(&archive)
+        .arg(&libSource)
+        .status()
+        .unwrap_or_else(|err| panic!("Failed to run {}: {}", rustc, err));
+    if !status.success() {
+        panic!("Building the runtime library {} failed ({})", archive.display(), status);
+    }
 
-    // Link the Rust library
-    println!("cargo:rustc-link-lib=funcLib");
-    // Specify the path if the library is not in a standard location
-    println!("cargo:rustc-link-search=native=./target/release/libFuncLib.d");
-}
\ No newline at end of file
+    //OUT_DIR is target/<profile>/build/compiler-<hash>/out, the compiler executable is in target/<profile>
+    let exeDir = outDir.ancestors().nth(3).unwrap_or_else(|| panic!("Unexpected OUT_DIR {}", outDir.display()));
+    fs::copy(&archive, exeDir.join("libfuncLib.a")).unwrap_or_else(|err| panic!("Failed to copy the runtime library to {}: {}", exeDir.display(), err));
+}
@@ -1,66 +1,613 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
 
-use std::{ffi::CString, io};
+use std::{cell::RefCell, ffi::{c_char, CStr}, io::{self, BufRead, BufWriter, Stdout, Write}, process, sync::atomic::{AtomicI32, AtomicU64, Ordering}, time::{SystemTime, UNIX_EPOCH}};
 
+//How much output is held before it is written, it is also written before a read and when the program ends
+const OUTPUT_BUFFER: usize = 64 * 1024;
+
+//The seed the random numbers start from when seedrandom is never called, so a run can be repeated
+const DEFAULT_SEED: u64 = 0x2545F4914F6CDD1D;
+
+thread_local! {
+    //What the put builtins printed that hasn't been written to stdout yet
+    static OUTPUT: RefCell<BufWriter<Stdout>> = RefCell::new(BufWriter::with_capacity(OUTPUT_BUFFER, io::stdout()));
+    //The line a get builtin reads into, kept so a read doesn't allocate a new one
+    static INPUT_LINE: RefCell<String> = RefCell::new(String::new());
+}
+
+//The state of the random number generator
+static RANDOM_STATE: AtomicU64 = AtomicU64::new(DEFAULT_SEED);
+
+//The exit code of a runtime error, 1 unless rt_setup changes it (--error-exit-code)
+static ERROR_EXIT_CODE: AtomicI32 = AtomicI32::new(1);
+
+//What a get builtin does with input it can't read (--bad-input), one of the BAD_INPUT_ codes
+static BAD_INPUT: AtomicI32 = AtomicI32::new(BAD_INPUT_ABORT);
+pub const BAD_INPUT_ABORT: i32 = 0;     //A runtime error
+pub const BAD_INPUT_REPROMPT: i32 = 1;  //Says what was wrong on stderr and reads the next line
+pub const BAD_INPUT_DEFAULT: i32 = 2;   //Returns 0, 0.0, false or an empty string
+
+//A string value of the compiled program points at its UTF-8 bytes, the 16 bytes before them hold its length
+//in bytes and its number of characters (two i64s). A NUL that isn't counted follows the bytes, so C can read a
+//string as a char*, it stops at the first NUL if the string has one. Literals are laid out the same way by the compiler
+const STRING_HEADER: usize = 2;
+
+//The byte length and the character count of a string value
+fn stringHeader(val: *const c_char) -> (i64, i64) {
+    if val.is_null() {
+        return (0, 0);
+    }
+    let header = unsafe { (val as *const i64).sub(STRING_HEADER) };
+    return unsafe { (*header, *header.add(1)) };
+}
+
+//Reads a string value of the compiled program, all of its bytes with any NULs in them
+fn readString(val: *const c_char) -> String {
+    let (byteLength, _) = stringHeader(val);
+    if byteLength == 0 {
+        return String::new();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(val as *const u8, byteLength as usize) };
+    return String::from_utf8_lossy(bytes).to_string();
+}
+
+//Reads a NUL terminated C string, the messages the generated code passes and a string C code passes to putstring
+fn readCString(val: *const c_char) -> String {
+    if val.is_null() {
+        return String::new();
+    }
+    let string = unsafe { CStr::from_ptr(val) };
+    return string.to_string_lossy().to_string();
+}
+
+//Gives a string result to the compiled program as a new string value, the header and the bytes in one block
+//The program never changes a string, it keeps the pointer, so the copy is never freed
+fn returnString(string: &str) -> *const c_char {
+    //The block is whole i64s so the header is aligned, it starts zeroed so the NUL after the bytes is there
+    let mut block: Vec<i64> = vec![0; STRING_HEADER + (string.len() + 1 + 7) / 8];
+    block[0] = string.len() as i64;
+    block[1] = stringLength(string);
+    let block = Box::leak(block.into_boxed_slice());
+    let chars = unsafe { block.as_mut_ptr().add(STRING_HEADER) as *mut u8 };
+    unsafe { std::ptr::copy_nonoverlapping(string.as_ptr(), chars, string.len()) };
+    return chars as *const c_char;
+}
+
+
+///////////////////////// STRING HELPERS /////////////////////////
+//The string functions on Rust strings, the interpreter calls these directly so both backends give the same results
+//Strings are UTF-8 of any length, a length or position counts characters
+
+pub fn stringLength(string: &str) -> i64 {
+    return string.chars().count() as i64;
+}
+
+pub fn concatStrings(first: &str, second: &str) -> String {
+    return format!("{}{}", first, second);
+}
+
+//The characters from start (counting from 0), a start or count past the end is cut to the end of the string
+pub fn substring(string: &str, start: i64, count: i64) -> String {
+    let start = start.max(0) as usize;
+    let count = count.max(0) as usize;
+    return string.chars().skip(start).take(count).collect::<String>();
+}
+
+//-1, 0 or 1 like strcmp
+pub fn compareStrings(first: &str, second: &str) -> i32 {
+    match first.cmp(second) {
+        std::cmp::Ordering::Less => return -1,
+        std::cmp::Ordering::Equal => return 0,
+        std::cmp::Ordering::Greater => return 1,
+    }
+}
+
+//A string that isn't a number is 0, like atoi
+pub fn stringToInt(string: &str) -> i32 {
+    return string.trim().parse::<i32>().unwrap_or(0);
+}
+
+pub fn stringToInt64(string: &str) -> i64 {
+    return string.trim().parse::<i64>().unwrap_or(0);
+}
+
+pub fn stringToFloat(string: &str) -> f32 {
+    return string.trim().parse::<f32>().unwrap_or(0.0);
+}
+
+pub fn stringToFloat64(string: &str) -> f64 {
+    return string.trim().parse::<f64>().unwrap_or(0.0);
+}
+
+///////////////////////// /STRING HELPERS /////////////////////////
+
+
+///////////////////////// RUNTIME ERRORS /////////////////////////
+//A runtime error prints "Runtime error on line N: <message>" on stderr, the same as the interpreter, and exits with ERROR_EXIT_CODE
+//What was printed before it is flushed first. Line 0 is a line the runtime doesn't know
+//The generated code calls rt_setline before a builtin, so an error in the builtin (bad input to a get builtin) has its line
+
+//The .src line of the builtin call being run, 0 until rt_setline is called
+static CALL_LINE: AtomicI32 = AtomicI32::new(0);
+
+//The text of a runtime error
+pub fn errorMessage(msg: &str, line: i32) -> String {
+    if line <= 0 {
+        return format!("Runtime error: {}", msg);
+    }
+    return format!("Runtime error on line {}: {}", line, msg);
+}
+
+fn runtimeError(msg: &str, line: i32) -> ! {
+    let _ = flushOutput();
+    eprintln!("{}", errorMessage(msg, line));
+    process::exit(ERROR_EXIT_CODE.load(Ordering::Relaxed));
+}
+
+//The value, or a runtime error with the message on the line of the builtin call
+fn orPanic<T>(result: Result<T, String>) -> T {
+    match result {
+        Ok(value) => return value,
+        Err(msg) => runtimeError(&msg, CALL_LINE.load(Ordering::Relaxed)),
+    }
+}
+
+//Reads a line for a get builtin and parses it, bad input is handled as BAD_INPUT says
+//Running out of input is an error unless bad input gets the default, reprompting would wait forever
+//The interpreter calls this too, it reports the Err as its own runtime error
+pub fn readInput<T>(builtin: &str, kind: &str, parse: impl Fn(&str) -> Option<T>, default: T) -> Result<T, String> {
+    //A prompt printed before the read has to be seen
+    flushOutput()?;
+    let badInput = BAD_INPUT.load(Ordering::Relaxed);
+    return INPUT_LINE.with(|readIn| {
+        let mut readIn = readIn.borrow_mut();
+        let mut stdin = io::stdin().lock();
+        loop {
+            readIn.clear();
+            match stdin.read_line(&mut readIn) {
+                Ok(0) | Err(_) => {
+                    if badInput == BAD_INPUT_DEFAULT {
+                        return Ok(default);
+                    }
+                    return Err(format!("{}: there is no more input", builtin));
+                }
+                Ok(_) => {}
+            }
+            //Only the newline is taken off, a string keeps its spaces
+            let line = readIn.trim_end_matches(&['\n', '\r'][..]);
+            if let Some(value) = parse(line) {
+                return Ok(value);
+            }
+            let msg = format!("{}: '{}' is not {}", builtin, line.trim(), kind);
+            match badInput {
+                BAD_INPUT_REPROMPT => {
+                    eprintln!("{}, enter it again", msg);
+                }
+                BAD_INPUT_DEFAULT => {
+                    return Ok(default);
+                }
+                _ => {
+                    return Err(msg);
+                }
+            }
+        }
+    });
+}
+
+///////////////////////// /RUNTIME ERRORS /////////////////////////
+
+
+///////////////////////// OUTPUT /////////////////////////
+//The put builtins write to a buffer instead of locking and flushing stdout on every line
+//It is flushed before a get builtin reads, before a runtime error, by exit and when main returns (rt_flush)
+//A put builtin returns false if its line couldn't be written, a failed flush at the end is a runtime error
+
+//Prints a line through the buffer, false if it couldn't be written
+pub fn writeLine(text: &str) -> bool {
+    return OUTPUT.with(|output| writeln!(output.borrow_mut(), "{}", text).is_ok());
+}
+
+//Writes what is in the buffer to stdout
+pub fn flushOutput() -> Result<(), String> {
+    return OUTPUT.with(|output| match output.borrow_mut().flush() {
+        Ok(()) => return Ok(()),
+        Err(err) => return Err(format!("The output couldn't be written: {}", err)),
+    });
+}
+
+//A float as the language prints it, the same whatever the locale:
+//The fewest digits that read back as the same float, with a '.' only if it has a fraction ("2", "0.1", "-3.25")
+//An exponent from -5 to 15 is written out in full, the others as "1.5e20" or "1e-7". NaN is "nan", the infinities "inf" and "-inf"
+pub fn formatFloat(val: f32) -> String {
+    if val.is_nan() || val.is_infinite() {
+        return formatFloat64(val as f64);
+    }
+    return formatDigits(&format!("{:e}", val));
+}
+
+pub fn formatFloat64(val: f64) -> String {
+    if val.is_nan() {
+        return "nan".to_string();
+    }
+    if val.is_infinite() {
+        return if val < 0.0 { "-inf".to_string() } else { "inf".to_string() };
+    }
+    return formatDigits(&format!("{:e}", val));
+}
+
+//Lays out the shortest digits Rust's {:e} finds, "-1.25e-3" is "-0.00125"
+fn formatDigits(scientific: &str) -> String {
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((scientific, "0"));
+    let exponent = exponent.parse::<i32>().unwrap_or(0);
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    if exponent < -5 || exponent > 15 {
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            return format!("{}{}e{}", sign, first, exponent);
+        }
+        return format!("{}{}.{}e{}", sign, first, rest, exponent);
+    }
+    //Where the decimal point goes in the digits
+    let point = exponent + 1;
+    if point <= 0 {
+        return format!("{}0.{}{}", sign, "0".repeat((-point) as usize), digits);
+    }
+    let point = point as usize;
+    if point >= digits.len() {
+        return format!("{}{}{}", sign, digits, "0".repeat(point - digits.len()));
+    }
+    return format!("{}{}.{}", sign, &digits[..point], &digits[point..]);
+}
+
+///////////////////////// /OUTPUT /////////////////////////
 
 
 #[no_mangle]
-pub extern fn putinteger(val: i32) -> bool {
-    println!("{}", val);
-    return true;
+pub extern "C" fn putinteger(val: i32) -> bool {
+    return writeLine(&val.to_string());
+}
+
+#[no_mangle]
+pub extern "C" fn putinteger64(val: i64) -> bool {
+    return writeLine(&val.to_string());
 }
 
 
 #[no_mangle]
-pub extern fn putfloat(val: f32) -> bool {
-    println!("{}", val);
+pub extern "C" fn putfloat(val: f32) -> bool {
+    return writeLine(&formatFloat(val));
+}
+
+#[no_mangle]
+pub extern "C" fn putfloat64(val: f64) -> bool {
+    return writeLine(&formatFloat64(val));
+}
+
+
+#[no_mangle]
+pub extern "C" fn putbool(val: bool) -> bool {
+    return writeLine(&val.to_string());
+}
+
+//The program's putstring, a NUL in the string is printed too
+#[no_mangle]
+pub extern "C" fn rt_putstring(val: *const c_char) -> bool {
+    return writeLine(&readString(val));
+}
+
+//putstring for C code linked with the program, the string is a NUL terminated char*
+#[no_mangle]
+pub extern "C" fn putstring(val: *const c_char) -> bool {
+    if val.is_null() {
+        return false;
+    }
+    return writeLine(&readCString(val));
+}
+
+#[no_mangle]
+pub extern "C" fn getinteger() -> i32 {
+    return orPanic(readInput("getinteger", "an integer", |text| text.trim().parse::<i32>().ok(), 0));
+}
+
+#[no_mangle]
+pub extern "C" fn getinteger64() -> i64 {
+    return orPanic(readInput("getinteger", "an integer", |text| text.trim().parse::<i64>().ok(), 0));
+}
+
+#[no_mangle]
+pub extern "C" fn getfloat() -> f32 {
+    return orPanic(readInput("getfloat", "a float", |text| text.trim().parse::<f32>().ok(), 0.0));
+}
+
+#[no_mangle]
+pub extern "C" fn getfloat64() -> f64 {
+    return orPanic(readInput("getfloat", "a float", |text| text.trim().parse::<f64>().ok(), 0.0));
+}
+
+#[no_mangle]
+pub extern "C" fn getbool() -> bool {
+    return orPanic(readInput("getbool", "a bool", |text| text.trim().parse::<bool>().ok(), false));
+}
+
+//Reads a line as a new string
+#[no_mangle]
+pub extern "C" fn getstring() -> *const c_char {
+    let line = orPanic(readInput("getstring", "a string", |text| Some(text.to_string()), String::new()));
+    return returnString(&line);
+}
+
+//The functions below are exported as rt_<name> so they don't replace the C library's functions of the same name
+//The ones ending in 64 take and return an i64 and an f64 where the others have an i32 and an f32, a program built with --wide-numbers calls them
+
+#[no_mangle]
+pub extern "C" fn rt_sqrt(input: i32) -> f32 {
+    let retval = f64::sqrt(input as f64);
+    return retval as f32;
+}
+
+#[no_mangle]
+pub extern "C" fn rt_sqrt64(input: i64) -> f64 {
+    return f64::sqrt(input as f64);
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringlength(val: *const c_char) -> i32 {
+    return rt_stringlength64(val) as i32;
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringlength64(val: *const c_char) -> i64 {
+    //The count is in the string's header, it isn't counted again
+    return stringHeader(val).1;
+}
+
+#[no_mangle]
+pub extern "C" fn rt_concat(first: *const c_char, second: *const c_char) -> *const c_char {
+    return returnString(&concatStrings(&readString(first), &readString(second)));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_substring(val: *const c_char, start: i32, count: i32) -> *const c_char {
+    return returnString(&substring(&readString(val), start as i64, count as i64));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_substring64(val: *const c_char, start: i64, count: i64) -> *const c_char {
+    return returnString(&substring(&readString(val), start, count));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringcompare(first: *const c_char, second: *const c_char) -> i32 {
+    return compareStrings(&readString(first), &readString(second));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringcompare64(first: *const c_char, second: *const c_char) -> i64 {
+    return compareStrings(&readString(first), &readString(second)) as i64;
+}
+
+#[no_mangle]
+pub extern "C" fn rt_inttostring(val: i32) -> *const c_char {
+    return returnString(&val.to_string());
+}
+
+#[no_mangle]
+pub extern "C" fn rt_inttostring64(val: i64) -> *const c_char {
+    return returnString(&val.to_string());
+}
+
+#[no_mangle]
+pub extern "C" fn rt_floattostring(val: f32) -> *const c_char {
+    return returnString(&formatFloat(val));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_floattostring64(val: f64) -> *const c_char {
+    return returnString(&formatFloat64(val));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringtoint(val: *const c_char) -> i32 {
+    return stringToInt(&readString(val));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringtoint64(val: *const c_char) -> i64 {
+    return stringToInt64(&readString(val));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringtofloat(val: *const c_char) -> f32 {
+    return stringToFloat(&readString(val));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_stringtofloat64(val: *const c_char) -> f64 {
+    return stringToFloat64(&readString(val));
+}
+
+#[no_mangle]
+pub extern "C" fn rt_abs(val: f32) -> f32 {
+    return val.abs();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_abs64(val: f64) -> f64 {
+    return val.abs();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_pow(base: f32, exponent: f32) -> f32 {
+    return base.powf(exponent);
+}
+
+#[no_mangle]
+pub extern "C" fn rt_pow64(base: f64, exponent: f64) -> f64 {
+    return base.powf(exponent);
+}
+
+#[no_mangle]
+pub extern "C" fn rt_floor(val: f32) -> f32 {
+    return val.floor();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_floor64(val: f64) -> f64 {
+    return val.floor();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_sin(val: f32) -> f32 {
+    return val.sin();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_sin64(val: f64) -> f64 {
+    return val.sin();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_cos(val: f32) -> f32 {
+    return val.cos();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_cos64(val: f64) -> f64 {
+    return val.cos();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_exp(val: f32) -> f32 {
+    return val.exp();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_exp64(val: f64) -> f64 {
+    return val.exp();
+}
+
+//The natural log, NaN for a negative number and -inf for 0
+#[no_mangle]
+pub extern "C" fn rt_log(val: f32) -> f32 {
+    return val.ln();
+}
+
+#[no_mangle]
+pub extern "C" fn rt_log64(val: f64) -> f64 {
+    return val.ln();
+}
+
+//A seed gives the same random numbers on every run and on both backends
+//A seed that fits in an i32 starts the same numbers whether the numbers are wide or not
+#[no_mangle]
+pub extern "C" fn rt_seedrandom(seed: i32) -> bool {
+    return rt_seedrandom64(seed as i64);
+}
+
+#[no_mangle]
+pub extern "C" fn rt_seedrandom64(seed: i64) -> bool {
+    let bits = if seed >= i32::MIN as i64 && seed <= i32::MAX as i64 { seed as i32 as u32 as u64 } else { seed as u64 };
+    RANDOM_STATE.store(bits ^ DEFAULT_SEED, Ordering::Relaxed);
     return true;
 }
 
+//A random number from 0 up to but not including limit (xorshift64*), 0 if limit isn't positive
+#[no_mangle]
+pub extern "C" fn rt_random(limit: i32) -> i32 {
+    return rt_random64(limit as i64) as i32;
+}
+
+//A limit that fits in 32 bits uses the high half of the output like rt_random, a bigger one uses all of it
+#[no_mangle]
+pub extern "C" fn rt_random64(limit: i64) -> i64 {
+    let mut state = RANDOM_STATE.load(Ordering::Relaxed);
+    if state == 0 {
+        state = DEFAULT_SEED;
+    }
+    state ^= state >> 12;
+    state ^= state << 25;
+    state ^= state >> 27;
+    RANDOM_STATE.store(state, Ordering::Relaxed);
+    if limit <= 0 {
+        return 0;
+    }
+    let mut value = state.wrapping_mul(0x2545F4914F6CDD1D);
+    if limit <= u32::MAX as i64 {
+        value >>= 32;
+    }
+    return (value % limit as u64) as i64;
+}
+
+//The wall clock time in seconds since 1970
+#[no_mangle]
+pub extern "C" fn rt_time() -> i32 {
+    return rt_time64() as i32;
+}
 
 #[no_mangle]
-pub extern fn bool(val: bool) -> bool {
-    println!("{}", val);
+pub extern "C" fn rt_time64() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => return elapsed.as_secs() as i64,
+        Err(_) => return 0,
+    }
+}
+
+//Sets how runtime errors and bad input are handled, the generated main calls it first
+#[no_mangle]
+pub extern "C" fn rt_setup(errorExitCode: i32, badInput: i32) -> bool {
+    ERROR_EXIT_CODE.store(errorExitCode, Ordering::Relaxed);
+    BAD_INPUT.store(badInput, Ordering::Relaxed);
     return true;
 }
 
+//Ends the program with a runtime error on the .src line, for generated code and C code linked with the program
 #[no_mangle]
-pub extern fn putstring(val: &CString) -> bool {
-    println!("{:?}", val);
+pub extern "C" fn rt_panic(msg: *const c_char, line: i32) -> bool {
+    runtimeError(&readCString(msg), line);
+}
+
+//Keeps the .src line of the builtin the generated code is about to call
+#[no_mangle]
+pub extern "C" fn rt_setline(line: i32) -> bool {
+    CALL_LINE.store(line, Ordering::Relaxed);
     return true;
 }
 
+//Writes the buffered output, the generated main calls it before it returns
 #[no_mangle]
-pub extern fn getinteger() -> i32 {
-    let mut readIn = String::new();
-    let stdIn = io::stdin();
-    stdIn.read_line(&mut readIn).expect("No stdin value found");
-    let intVal = readIn.trim().parse::<i32>().expect("Must provide an int for getInt");
-    return intVal;
+pub extern "C" fn rt_flush() -> bool {
+    orPanic(flushOutput());
+    return true;
 }
 
+//A failed --checked-arith check, the operands are passed as an i64 whatever the number width
 #[no_mangle]
-pub extern fn getfloat() -> f32 {
-    let mut readIn = String::new();
-    let stdIn = io::stdin();
-    stdIn.read_line(&mut readIn).expect("No stdin value found");
-    let intVal = readIn.trim().parse::<f32>().expect("Must provide an float for getInt");
-    return intVal;
+pub extern "C" fn rt_arithpanic(op: *const c_char, left: i64, right: i64, line: i32) -> bool {
+    runtimeError(&arithFailure(&readCString(op), left, right), line);
 }
 
+//What went wrong in a checked operation, the interpreter gives the same message
+pub fn arithFailure(op: &str, left: i64, right: i64) -> String {
+    if op == "/" && right == 0 {
+        return format!("Integer division by zero in {} / {}", left, right);
+    }
+    return format!("Integer overflow in {} {} {}", left, op, right);
+}
 
+//Ends the program with the exit code, what was printed is flushed first
 #[no_mangle]
-pub extern fn getbool() -> bool {
-    let mut readIn = String::new();
-    let stdIn = io::stdin();
-    stdIn.read_line(&mut readIn).expect("No stdin value found");
-    let intVal = readIn.trim().parse::<bool>().expect("Must provide an bool for getInt");
-    return intVal;
+pub extern "C" fn rt_exit(code: i32) -> bool {
+    orPanic(flushOutput());
+    process::exit(code);
 }
 
+//The exit code a process gets is 32 bits, a wider code is cut to them
 #[no_mangle]
-pub extern fn sqrt(input: i32) -> f64{
-    let retval = f64::sqrt(input as f64);
-    return retval;
-}
\ No newline at end of file
+pub extern "C" fn rt_exit64(code: i64) -> bool {
+    return rt_exit(code as i32);
+}
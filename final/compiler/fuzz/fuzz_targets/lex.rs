@@ -0,0 +1,13 @@
+#![no_main]
+
+//Fuzzes the lexer, no input may panic
+//The first byte picks raw bytes (even) or a program generated from the rest of the input (odd)
+
+use {
+    compiler::models::{frontend::Stage, fuzzing::fuzzStage},
+    libfuzzer_sys::fuzz_target,
+};
+
+fuzz_target!(|data: &[u8]| {
+    fuzzStage(data, Stage::Lex);
+});
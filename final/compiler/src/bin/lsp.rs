@@ -0,0 +1,76 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//The language server binary, speaks the protocol over stdin and stdout
+
+//package imports
+use {
+    compiler::models::lsp::LanguageServer,
+    std::{
+        fs::File,
+        io::{self, BufReader, BufWriter, Write},
+        process,
+    },
+};
+
+//The same stack size the compiler runs with, the front end recurses on deeply nested programs
+const SERVER_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+//The front end prints its progress to stdout, which would corrupt the protocol stream
+//So the protocol gets its own copy of stdout and the process stdout is pointed at stderr
+#[cfg(unix)]
+fn protocolOutput() -> Box<dyn Write + Send> {
+    use std::os::unix::io::FromRawFd;
+    extern "C" {
+        fn dup(fd: i32) -> i32;
+        fn dup2(oldFd: i32, newFd: i32) -> i32;
+    }
+    let _ = io::stdout().flush();
+    unsafe {
+        let protocolFd = dup(1);
+        if protocolFd < 0 || dup2(2, 1) < 0 {
+            eprintln!("Language server: could not separate the protocol from stdout, front end output may corrupt it");
+            return Box::new(io::stdout());
+        }
+        return Box::new(File::from_raw_fd(protocolFd));
+    }
+}
+
+#[cfg(not(unix))]
+fn protocolOutput() -> Box<dyn Write + Send> {
+    return Box::new(io::stdout());
+}
+
+fn main() {
+    let output = protocolOutput();
+    let serverThread = std::thread::Builder::new()
+        .name("lsp".to_string())
+        .stack_size(SERVER_STACK_SIZE)
+        .spawn(move || {
+            let stdin = io::stdin();
+            let mut server = LanguageServer::new();
+            return server.run(stdin.lock(), BufWriter::new(output));
+        });
+    match serverThread {
+        Ok(handle) => {
+            match handle.join() {
+                Ok(exitCode) => {
+                    process::exit(exitCode);
+                }
+                Err(_) => {
+                    process::exit(101);
+                }
+            }
+        }
+        Err(errMsg) => {
+            eprintln!("Error starting the language server thread: {}", errMsg);
+            process::exit(1);
+        }
+    }
+}
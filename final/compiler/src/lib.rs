@@ -0,0 +1,183 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//The compiler library, the models are shared by the compiler and the language server binaries
+
+//Crate imports
+extern crate anyhow;
+extern crate parse_display;
+extern crate utf8_chars;
+extern crate unicode_segmentation;
+extern crate serde;
+extern crate serde_json;
+
+pub mod models;
+
+//The llvm feature needs exactly one LLVM version feature to pick the inkwell binding
+#[cfg(all(feature = "llvm", not(any(feature = "llvm12", feature = "llvm13", feature = "llvm14"))))]
+compile_error!("The llvm feature needs an LLVM version, enable one of llvm12, llvm13 or llvm14");
+
+//package imports
+use std::fmt;
+use serde::Serialize;
+
+///////////////////////// Setup /////////////////////////
+
+//The enumeration for saving Token types, this is a list of every type of Token there is
+#[derive(Clone, PartialEq, Serialize)]
+pub enum tokenTypeEnum{
+    //Operators
+    PLUS, 
+    MINUS,
+    LESS,
+    GREATER,
+    LESS_EQUALS,
+    GREATER_EQUALS,
+    SET_EQUALS,
+    CHECK_EQUALS,
+    NOT_EQUALS,
+    MULTIPLY,
+    DIVIDE,
+    AND,
+    OR,
+    NOT,
+    // OPERATOR,
+    
+    
+    //Variable types
+    INT,
+    FLOAT, 
+    STRING,
+
+    //Word types
+    IDENTIFIER, 
+    
+    //Keywords
+    IF,
+    ELSE,
+    GLOBAL,
+    VARIABLE,
+    THEN,
+    END,
+    
+
+    IF_RW, 
+    LOOP_RW, 
+    END_RW, 
+    L_PAREN, 
+    R_PAREN,
+    L_BRACKET, 
+    R_BRACKET,
+    
+    EOF,
+    LETTER,
+    UNACCOUNTED,
+    WORD,
+    RETURN,
+    ERROR,
+    PROGRAM,
+    IS,
+    BEGIN,
+    PROCEDURE,
+    SEMICOLON,
+    COLON,
+    PERIOD,
+    END_PROGRAM,
+    END_PROCEDURE,
+    END_IF,
+    END_FOR,
+    MODULE,
+    IMPORT,
+    END_MODULE,
+    EXTERNAL,
+    EXTERNAL_PROCEDURE,
+    COMMA,
+    FOR,
+
+    PROCEDURE_CALL,
+    TRUE,
+    FALSE,
+    COMMENT,
+    WHITESPACE,
+
+    
+    
+}
+impl fmt::Display for tokenTypeEnum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variant_str = match self {
+            tokenTypeEnum::PLUS => "PLUS",
+            tokenTypeEnum::MINUS => "MINUS",
+            tokenTypeEnum::IF_RW => "IF_RW",
+            tokenTypeEnum::LOOP_RW => "LOOP_RW",
+            tokenTypeEnum::END_RW => "END_RW",
+            tokenTypeEnum::L_PAREN => "L_PAREN",
+            tokenTypeEnum::R_PAREN => "R_PAREN",
+            tokenTypeEnum::L_BRACKET => "L_BRACKET",
+            tokenTypeEnum::R_BRACKET => "R_BRACKET",
+            tokenTypeEnum::INT => "INT",
+            tokenTypeEnum::FLOAT => "FLOAT",
+            tokenTypeEnum::IDENTIFIER => "IDENTIFIER",
+            tokenTypeEnum::LESS => "LESS",
+            tokenTypeEnum::GREATER => "GREATER",
+            tokenTypeEnum::LESS_EQUALS => "LESS_EQUALS",
+            tokenTypeEnum::GREATER_EQUALS => "GREATER_EQUALS",
+            tokenTypeEnum::EOF => "EOF",
+            tokenTypeEnum::LETTER => "LETTER",
+            tokenTypeEnum::UNACCOUNTED => "UNACCOUNTED",
+            tokenTypeEnum::WORD => "WORD",
+            tokenTypeEnum::STRING => "STRING",
+            tokenTypeEnum::RETURN => "RETURN",
+            tokenTypeEnum::SET_EQUALS => "SET_EQUALS",
+            tokenTypeEnum::CHECK_EQUALS => "CHECK_EQUALS",
+            tokenTypeEnum::ERROR => "ERROR",
+            tokenTypeEnum::COMMENT => "COMMENT",
+            tokenTypeEnum::WHITESPACE => "WHITESPACE",
+            tokenTypeEnum::PROGRAM => "PROGRAM",
+            tokenTypeEnum::IS => "IS",
+            tokenTypeEnum::BEGIN => "BEGIN",
+            tokenTypeEnum::PROCEDURE => "PROCEDURE",
+            tokenTypeEnum::IF => "IF",
+            tokenTypeEnum::ELSE => "ELSE",
+            tokenTypeEnum::GLOBAL => "GLOBAL",
+            tokenTypeEnum::VARIABLE => "VARIABLE",
+            tokenTypeEnum::THEN => "THEN",
+            tokenTypeEnum::END => "END",
+            tokenTypeEnum::SEMICOLON => "SEMICOLON",
+            tokenTypeEnum::COLON => "COLON",
+            tokenTypeEnum::PERIOD => "PERIOD",
+            tokenTypeEnum::END_PROCEDURE => "END_PROCEDURE",
+            tokenTypeEnum::END_PROGRAM => "END_PROGRAM",
+            tokenTypeEnum::END_IF => "END_IF",
+            tokenTypeEnum::MULTIPLY => "MULTIPLY",
+            tokenTypeEnum::DIVIDE => "DIVIDE",
+            tokenTypeEnum::COMMA => "COMMA",
+            tokenTypeEnum::END_FOR => "END_FOR",
+            tokenTypeEnum::MODULE => "MODULE",
+            tokenTypeEnum::IMPORT => "IMPORT",
+            tokenTypeEnum::END_MODULE => "END_MODULE",
+            tokenTypeEnum::EXTERNAL => "EXTERNAL",
+            tokenTypeEnum::EXTERNAL_PROCEDURE => "EXTERNAL_PROCEDURE",
+            tokenTypeEnum::FOR => "FOR",
+            tokenTypeEnum::PROCEDURE_CALL => "PROCEDURE_CALL",
+            tokenTypeEnum::AND => "AND",
+            tokenTypeEnum::OR => "OR",
+            tokenTypeEnum::NOT => "NOT",
+            tokenTypeEnum::NOT_EQUALS => "NOT_EQUALS",
+            tokenTypeEnum::TRUE => "TRUE",
+            tokenTypeEnum::FALSE => "FALSE",
+            // tokenTypeEnum::OPERATOR => "OPERATOR",
+
+
+        };
+        write!(f, "{}", variant_str)
+    }
+}
+
+///////////////////////// /Setup /////////////////////////
@@ -12,19 +12,24 @@ extern crate anyhow;
 extern crate parse_display;
 extern crate utf8_chars;
 extern crate unicode_segmentation;
-// extern crate funcLib;
-
-mod models;
+extern crate funcLib;
 
 //package imports
 use {
-    crate::models::{lexer::Lexer, parser::{Expr, Parser, *}, typechecker::{
+    compiler::verboseln,
+    compiler::models::{lexer::Lexer, parser::{Expr, Parser, *}, reporting::setVerbose, typechecker::{
         SymbolTable, SyntaxChecker
-    }, compiler::*,
-    }, anyhow::Result, inkwell::{builder::Builder, OptimizationLevel, passes::PassManager, context::Context, module::Module, types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum}, values::*, AddressSpace, FloatPredicate, IntPredicate}, parse_display::Display, std::{
+    }, linker::Linker, resolver::{Resolver, Unit}, options::{Backend, CompilerCommand, CompilerOptions, EmitKind}, formatter::formatSource, emit::{astDot, astJson, tokensJson},
+    }, anyhow::Result, parse_display::Display, std::{
         collections::HashMap, env::{self, args}, ffi::CString, fmt, rc::Rc
     }
 };
+//The LLVM backend imports, only built with the llvm feature
+#[cfg(feature = "llvm")]
+use {
+    compiler::models::{compiler::*, jit::runJit, optimizer::Optimizer, emit::cfgDot},
+    inkwell::{builder::Builder, memory_buffer::MemoryBuffer, OptimizationLevel, context::Context, module::Module, types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum}, values::*, AddressSpace, FloatPredicate, IntPredicate},
+};
 
 ///////////////////////// Setup /////////////////////////
 
@@ -32,146 +37,11 @@ use std::fs::{self, File};
 //imports
 use std::{io::prelude::*, path::Path};
 use std::process::{self, Command};
+#[cfg(feature = "llvm")]
 use inkwell::targets::{CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple};
-// use llvm_sys::target_machine::LLVMTargetMachineOptionsSetRelocMode;
-
-
-//The enumeration for saving Token types, this is a list of every type of Token there is
-#[derive(Clone, PartialEq)]
-pub enum tokenTypeEnum{
-    //Operators
-    PLUS, 
-    MINUS,
-    LESS,
-    GREATER,
-    LESS_EQUALS,
-    GREATER_EQUALS,
-    SET_EQUALS,
-    CHECK_EQUALS,
-    NOT_EQUALS,
-    MULTIPLY,
-    DIVIDE,
-    AND,
-    OR,
-    NOT,
-    // OPERATOR,
-    
-    
-    //Variable types
-    INT,
-    FLOAT, 
-    STRING,
+#[cfg(feature = "interp")]
+use compiler::models::interpreter::Interpreter;
 
-    //Word types
-    IDENTIFIER, 
-    
-    //Keywords
-    IF,
-    ELSE,
-    GLOBAL,
-    VARIABLE,
-    THEN,
-    END,
-    
-
-    IF_RW, 
-    LOOP_RW, 
-    END_RW, 
-    L_PAREN, 
-    R_PAREN,
-    L_BRACKET, 
-    R_BRACKET,
-    
-    EOF,
-    LETTER,
-    UNACCOUNTED,
-    WORD,
-    RETURN,
-    ERROR,
-    PROGRAM,
-    IS,
-    BEGIN,
-    PROCEDURE,
-    SEMICOLON,
-    COLON,
-    PERIOD,
-    END_PROGRAM,
-    END_PROCEDURE,
-    END_IF,
-    END_FOR,
-    COMMA,
-    FOR,
-
-    PROCEDURE_CALL,
-    TRUE,
-    FALSE,
-
-    
-    
-}
-impl fmt::Display for tokenTypeEnum {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let variant_str = match self {
-            tokenTypeEnum::PLUS => "PLUS",
-            tokenTypeEnum::MINUS => "MINUS",
-            tokenTypeEnum::IF_RW => "IF_RW",
-            tokenTypeEnum::LOOP_RW => "LOOP_RW",
-            tokenTypeEnum::END_RW => "END_RW",
-            tokenTypeEnum::L_PAREN => "L_PAREN",
-            tokenTypeEnum::R_PAREN => "R_PAREN",
-            tokenTypeEnum::L_BRACKET => "L_BRACKET",
-            tokenTypeEnum::R_BRACKET => "R_BRACKET",
-            tokenTypeEnum::INT => "INT",
-            tokenTypeEnum::FLOAT => "FLOAT",
-            tokenTypeEnum::IDENTIFIER => "IDENTIFIER",
-            tokenTypeEnum::LESS => "LESS",
-            tokenTypeEnum::GREATER => "GREATER",
-            tokenTypeEnum::LESS_EQUALS => "LESS_EQUALS",
-            tokenTypeEnum::GREATER_EQUALS => "GREATER_EQUALS",
-            tokenTypeEnum::EOF => "EOF",
-            tokenTypeEnum::LETTER => "LETTER",
-            tokenTypeEnum::UNACCOUNTED => "UNACCOUNTED",
-            tokenTypeEnum::WORD => "WORD",
-            tokenTypeEnum::STRING => "STRING",
-            tokenTypeEnum::RETURN => "RETURN",
-            tokenTypeEnum::SET_EQUALS => "SET_EQUALS",
-            tokenTypeEnum::CHECK_EQUALS => "CHECK_EQUALS",
-            tokenTypeEnum::ERROR => "ERROR",
-            tokenTypeEnum::PROGRAM => "PROGRAM",
-            tokenTypeEnum::IS => "IS",
-            tokenTypeEnum::BEGIN => "BEGIN",
-            tokenTypeEnum::PROCEDURE => "PROCEDURE",
-            tokenTypeEnum::IF => "IF",
-            tokenTypeEnum::ELSE => "ELSE",
-            tokenTypeEnum::GLOBAL => "GLOBAL",
-            tokenTypeEnum::VARIABLE => "VARIABLE",
-            tokenTypeEnum::THEN => "THEN",
-            tokenTypeEnum::END => "END",
-            tokenTypeEnum::SEMICOLON => "SEMICOLON",
-            tokenTypeEnum::COLON => "COLON",
-            tokenTypeEnum::PERIOD => "PERIOD",
-            tokenTypeEnum::END_PROCEDURE => "END_PROCEDURE",
-            tokenTypeEnum::END_PROGRAM => "END_PROGRAM",
-            tokenTypeEnum::END_IF => "END_IF",
-            tokenTypeEnum::MULTIPLY => "MULTIPLY",
-            tokenTypeEnum::DIVIDE => "DIVIDE",
-            tokenTypeEnum::COMMA => "COMMA",
-            tokenTypeEnum::END_FOR => "END_FOR",
-            tokenTypeEnum::FOR => "FOR",
-            tokenTypeEnum::PROCEDURE_CALL => "PROCEDURE_CALL",
-            tokenTypeEnum::AND => "AND",
-            tokenTypeEnum::OR => "OR",
-            tokenTypeEnum::NOT => "NOT",
-            tokenTypeEnum::NOT_EQUALS => "NOT_EQUALS",
-            tokenTypeEnum::TRUE => "TRUE",
-            tokenTypeEnum::FALSE => "FALSE",
-            // tokenTypeEnum::OPERATOR => "OPERATOR",
-
-
-        };
-        write!(f, "{}", variant_str)
-    }
-}
 
 ///////////////////////// /Setup /////////////////////////
 
@@ -179,8 +49,39 @@ impl fmt::Display for tokenTypeEnum {
 
 
 
+//The stack size of the thread the compiler runs on, the parser and the interpreter recurse on the AST
+//so deep programs (and deep recursion in the interpreter) need more than the default main thread stack
+const COMPILER_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+//Starts the compiler on a thread with a larger stack
+fn main() {
+    let compilerThread = std::thread::Builder::new()
+        .name("compiler".to_string())
+        .stack_size(COMPILER_STACK_SIZE)
+        .spawn(compilerMain);
+    match compilerThread {
+        Ok(handle) => {
+            match handle.join() {
+                Ok(Ok(())) => {}
+                Ok(Err(errMsg)) => {
+                    eprintln!("{}", errMsg);
+                    process::exit(1);
+                }
+                Err(_) => {
+                    //The panic message has already been printed by the thread
+                    process::exit(101);
+                }
+            }
+        }
+        Err(errMsg) => {
+            eprintln!("Error starting the compiler thread: {}", errMsg);
+            process::exit(1);
+        }
+    }
+}
+
 //The main section of the code
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn compilerMain() -> Result<(), String> {
     // env::set_var("RUST_BACKTRACE", "full");
     // println!("cargo:rerun-if-changed=build.rs");
     // println!("cargo:rustc-link-lib=dylib=funcLib");
@@ -189,24 +90,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Link the Rust library
     // println!("cargo:rustc-link-lib=dylib=funcLib");
     
-    // Get the path from command line arguments
-    let path = env::args().nth(1).expect("Please specify an input file");
+    // Get the options and the path from command line arguments
+    let options: CompilerOptions;
+    match CompilerOptions::parseArgs(env::args().skip(1).collect()) {
+        Ok(opts) => {
+            options = opts;
+        }
+        Err(errMsg) => {
+            eprintln!("{}", errMsg);
+            process::exit(1);
+        }
+    }
+    setVerbose(options.verbose);
+
+    //The formatter only needs the front end, it doesn't compile the program
+    if options.command == CompilerCommand::Fmt {
+        return runFormatter(&options);
+    }
+
+    let path = options.inputPath.clone();
     let mut myLexer = Lexer::new(&path);
-    println!("Lexer filename: {} \nCharacter count: {}", myLexer.inputFile.fileName, myLexer.inputFile.numChars);
+    verboseln!("Lexer filename: {} \nCharacter count: {}", myLexer.inputFile.fileName, myLexer.inputFile.numChars);
 
     // Scan through the input
     myLexer.scanThrough();
 
     // println!("Lexer reporting: {:?}", myLexer.reports.clone());
     if (myLexer.reports.status) {
-        println!("Error in lexer: {:?}", myLexer.reports.clone());
-        return Ok(());
+        return Err(format!("Error in lexer:\n{}", myLexer.reports.errors.join("\n")));
     } else {
-        println!("Lexer returned successfully");
+        verboseln!("Lexer returned successfully");
+    }
+    if options.emit == Some(EmitKind::TokensJson) {
+        return writeEmit(&options, &EmitKind::TokensJson, tokensJson(&path, &myLexer.tokenList)?);
     }
 
     // Initialize the parser
     let mut myParser = Parser::new(&mut myLexer);
+    myParser.numberWidth = options.numberWidth;
 
 
     // // Print the parser's token list
@@ -216,35 +137,56 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut programAst: Stmt;
     match myParser.startParse() {
         Ok((reporting, Some(stmt))) => {
-            println!("Parsing completed successfully.");
+            verboseln!("Parsing completed successfully.");
             programAst = stmt;
         }
         Ok((reporting, None)) => {
-            println!("\n\nParsing succeeded, but no programAST was returned.");
-            return Ok(());
+            return Err("Parsing succeeded, but no program AST was returned".to_string());
         }
         Err(reporting) => {
-            eprintln!("\n\nParsing failed.");
-            eprintln!("Reporting: {:?}", reporting);
-            return Ok(());
+            return Err(format!("Error in parser:\n{}", reporting.errors.join("\n")));
         }
     }
 
+    if options.emit == Some(EmitKind::AstJson) {
+        return writeEmit(&options, &EmitKind::AstJson, astJson(&path, &programAst)?);
+    }
+    if options.emit == Some(EmitKind::AstDot) {
+        return writeEmit(&options, &EmitKind::AstDot, astDot(&path, &programAst));
+    }
+
     programAst.display(0);
 
-    let mut globalTable = SymbolTable::new();
-    let mut myChecker = SyntaxChecker::new(programAst.clone(), &mut globalTable, "Main".to_string());
-    println!("\n\nTypeChecker Created");
-    let programValid: bool = myChecker.checkProgram();
+    //Loads the modules the program imports, they are checked before the program
+    let mut myResolver = Resolver::new(options.moduleDirs.clone());
+    myResolver.numberWidth = options.numberWidth;
+    if !myResolver.resolve(&path, programAst.clone()) {
+        return Err(format!("Error in imports: {}", myResolver.reports.errors.join("\n")));
+    }
+
+    verboseln!("\n\nTypeChecker Created");
+    let programValid: bool = myResolver.checkUnits();
 
 
     if(!programValid){
-        println!("\n\nError in program");
-        return Ok(());
+        return Err(format!("Error in program:\n{}", myResolver.reports.errors.join("\n")));
     } else {
-        println!("\n\nProgram is valid");
+        verboseln!("\n\nProgram is valid");
+    }
+
+    // The interpreter backend runs the checked AST directly and exits with the program's status, LLVM is not used
+    // --emit=cfg-dot needs the LLVM module, parseArgs doesn't allow it with the interpreter
+    if options.backend == Backend::Interp {
+        runInterpreter(myResolver.mergeUnits(), &options);
     }
 
+    return runLlvm(programAst, &myResolver, &options);
+}
+
+//Generates the LLVM module for the checked program, then JIT runs it or writes and links an executable
+#[cfg(feature = "llvm")]
+fn runLlvm(programAst: Stmt, myResolver: &Resolver, options: &CompilerOptions) -> Result<(), String> {
+    let path = options.inputPath.clone();
     let mut global_table: HashMap<String, PointerValue> = HashMap::new();
 
     // let input = env::args().nth(2).expect("Please specify an input value");
@@ -254,22 +196,67 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     //Creates the llvm context and the code generator struct
     let context = Context::create();
     let mut myGen = Compiler::new(programAst.clone(), &context, &mut global_table, "test".to_string(), "Program".to_string());
+    myGen.numberWidth = options.numberWidth;
+    myGen.checkedArith = options.checkedArith;
+    myGen.errorExitCode = options.errorExitCode;
+    myGen.badInput = options.badInput;
+    if options.debugInfo {
+        myGen.enableDebugInfo(&path);
+    }
+    myGen.declareImports(&myResolver.importedDecls(myResolver.program()))?;
 
     
 
-    println!("Created compiler");
+    verboseln!("Created compiler");
     let ret = myGen.compileProgram();
     let mut finalMod: Module;
     match ret{
         Ok(module) => {
-            println!("\n\nModule generated");
-            // module.print_to_string();
-            module.print_to_stderr();
+            verboseln!("\n\nModule generated");
+            if options.verbose {
+                module.print_to_stderr();
+            }
             finalMod = module.clone();
         }
         Err(errMsg) => {
-            println!("Error with generation: {}", errMsg);
-            return Ok(());
+            return Err(format!("Error with generation: {}", errMsg));
+        }
+    }
+
+    // Each imported module is compiled in its own context and linked into the program's module
+    for unit in myResolver.modules() {
+        let bitcode = compileModuleUnit(unit, &myResolver.importedDecls(unit), options)?;
+        let unitModule = context.create_module_from_ir(bitcode).map_err(|err| format!("Error loading module {}: {}", unit.name, err))?;
+        finalMod.link_in_module(unitModule).map_err(|err| format!("Error linking module {}: {}", unit.name, err))?;
+        verboseln!("Linked module {}", unit.name);
+    }
+
+    // The control flow graph is written before the optimizer so it shows the blocks as compileStmt laid them out
+    if options.emit == Some(EmitKind::CfgDot) {
+        return writeEmit(options, &EmitKind::CfgDot, cfgDot(&path, &finalMod));
+    }
+
+    // Runs the optimization pipeline for the selected level before anything is emitted
+    let myOptimizer = Optimizer::new(options.optLevel.clone(), options.printPasses, options.timePasses);
+    match myOptimizer.optimize(&finalMod) {
+        Ok(timings) => {
+            verboseln!("Optimized module with {} passes at {}", timings.len(), options.optLevel);
+        }
+        Err(errMsg) => {
+            return Err(format!("Error optimizing module: {}", errMsg));
+        }
+    }
+
+    // For the run command, JIT compiles the module and exits with the program's status, no linking needed
+    if options.command == CompilerCommand::Run {
+        match runJit(&finalMod, options.optLevel.toLlvm(), &options.libDirs, &options.libs) {
+            Ok(exitCode) => {
+                process::exit(exitCode);
+            }
+            Err(errMsg) => {
+                eprintln!("Error running program: {}", errMsg);
+                process::exit(1);
+            }
         }
     }
 
@@ -286,8 +273,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &target_triple,
         "znver2",
         "",
-        OptimizationLevel::None,
-        RelocMode::Default,
+        options.optLevel.toLlvm(),
+        RelocMode::PIC,
         CodeModel::Default,
 
         // &target_triple,
@@ -304,41 +291,124 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             targetMachine = target;
         }
         None => {
-            println!("no target machine");
-            return Ok(());
+            return Err("Error creating the target machine".to_string());
         }
     }
 
 
 
-    // Generate object file
-    let path = Path::new("output.o");
-    let result = targetMachine.write_to_file(&finalMod, inkwell::targets::FileType::Object, &path);
+    // Generate the object file in the temp directory, it is removed once it has been linked
+    let objStem = Path::new(&path).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or("output".to_string());
+    let objPath = env::temp_dir().join(format!("{}-{}.o", objStem, process::id()));
+    let result = targetMachine.write_to_file(&finalMod, inkwell::targets::FileType::Object, &objPath);
     if let Err(e) = result {
-        println!("Error generating object file: {}", e);
+        return Err(format!("Error generating object file: {}", e));
     }
 
-    let llvm_ir_path = Path::new("./out").with_extension("ll");
-    finalMod.print_to_file(&llvm_ir_path).expect("Error printing ll file");
+    // Links the object with the runtime library using the system linker
+    let mut myLinker = Linker::new(options.linker.clone(), options.libDirs.clone(), options.libs.clone());
+    let linked = myLinker.link(&objPath, Path::new(&options.executablePath()));
+    let _ = fs::remove_file(&objPath);
+    if !linked {
+        return Err(format!("Error in linker:\n{}", myLinker.reports.errors.join("\n")));
+    } else {
+        println!("Linked executable {}", options.executablePath());
+    }
+
+    Ok(())
+}
 
-    let libPath = Path::new("./target/release/libfuncLib").with_extension("a");
+//Generates the LLVM module of an imported module in its own context, it is returned as bitcode to be linked into the program
+#[cfg(feature = "llvm")]
+fn compileModuleUnit(unit: &Unit, imports: &Vec<Stmt>, options: &CompilerOptions) -> Result<MemoryBuffer, String> {
+    let context = Context::create();
+    let mut unitGlobalTable: HashMap<String, PointerValue> = HashMap::new();
+    let mut unitGen = Compiler::new(unit.ast.clone(), &context, &mut unitGlobalTable, "test".to_string(), unit.name.clone());
+    unitGen.numberWidth = options.numberWidth;
+    unitGen.checkedArith = options.checkedArith;
+    if options.debugInfo {
+        unitGen.enableDebugInfo(&unit.path);
+    }
+    unitGen.declareImports(imports)?;
+    let unitModule = unitGen.compileModule()?;
+    verboseln!("Module {} generated", unit.name);
+    return Ok(unitModule.write_bitcode_to_memory());
+}
 
-    let output = Command::new("clang")
-        .current_dir(env::current_dir().expect("failed to find current dir"))
-        .arg(&llvm_ir_path)
-        .arg(&libPath)
-        .output()
-        .expect("failed to execute linker");
-    let status = output.status;
-    if !status.success() {
-        println!("link error: {}", std::str::from_utf8(&output.stderr).unwrap());
-        process::exit(1);
+//The LLVM backend was not built in, the program can only be run with the interpreter
+#[cfg(not(feature = "llvm"))]
+fn runLlvm(programAst: Stmt, myResolver: &Resolver, options: &CompilerOptions) -> Result<(), String> {
+    if options.emit == Some(EmitKind::CfgDot) {
+        return Err("--emit=cfg-dot shows the generated LLVM code, rebuild the compiler with '--features llvm14'".to_string());
     }
+    return Err("The llvm backend is not available, rebuild the compiler with '--features llvm14' or use --backend=interp".to_string());
+}
 
-    fs::remove_file(llvm_ir_path).expect("failed to remove temporary ll file");
+//Formats the input file in place, or with --check reports if it would change and exits with 1
+fn runFormatter(options: &CompilerOptions) -> Result<(), String> {
+    let path = options.inputPath.clone();
+    let source = fs::read_to_string(&path).map_err(|err| format!("Error reading {}: {}", path, err))?;
+    let formatted = formatSource(&path, &source)?;
 
+    if formatted == source {
+        return Ok(());
+    }
+    if options.check {
+        //Shows the first line that would change
+        let mut origLines = source.lines();
+        let mut newLines = formatted.lines();
+        let mut lineNum = 1;
+        loop {
+            let (origLine, newLine) = (origLines.next(), newLines.next());
+            if origLine != newLine {
+                eprintln!("{} is not formatted, first difference on line {}:", path, lineNum);
+                eprintln!("-{}", origLine.unwrap_or(""));
+                eprintln!("+{}", newLine.unwrap_or(""));
+                break;
+            }
+            if origLine.is_none() {
+                eprintln!("{} is not formatted, the whitespace at the end of the file differs", path);
+                break;
+            }
+            lineNum += 1;
+        }
+        process::exit(1);
+    }
+    fs::write(&path, formatted).map_err(|err| format!("Error writing {}: {}", path, err))?;
+    println!("Formatted {}", path);
+    return Ok(());
+}
 
+//Writes the JSON from --emit to the file picked by the options
+fn writeEmit(options: &CompilerOptions, kind: &EmitKind, json: String) -> Result<(), String> {
+    let outPath = options.emitPath(kind);
+    fs::write(&outPath, json + "\n").map_err(|err| format!("Error writing {}: {}", outPath, err))?;
+    println!("Wrote {}", outPath);
+    return Ok(());
+}
 
+//Runs the program with the AST interpreter and exits with its status
+#[cfg(feature = "interp")]
+fn runInterpreter(programAst: Stmt, options: &CompilerOptions) {
+    let mut myInterpreter = Interpreter::new(programAst);
+    myInterpreter.numberWidth = options.numberWidth;
+    myInterpreter.checkedArith = options.checkedArith;
+    //The get builtins read through funcLib, it handles bad input the same as in compiled code
+    funcLib::rt_setup(options.errorExitCode, options.badInput.code());
+    match myInterpreter.runProgram() {
+        Ok(exitCode) => {
+            process::exit(exitCode);
+        }
+        Err(errMsg) => {
+            eprintln!("{}", errMsg);
+            process::exit(options.errorExitCode);
+        }
+    }
+}
 
-    Ok(())
+//The interpreter was not built in, so the interp backend can't be used
+#[cfg(not(feature = "interp"))]
+fn runInterpreter(programAst: Stmt, options: &CompilerOptions) {
+    eprintln!("The interp backend is not available, rebuild the compiler with '--features interp'");
+    process::exit(1);
 }
\ No newline at end of file
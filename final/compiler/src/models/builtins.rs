@@ -0,0 +1,169 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::parser::{NumberWidth, VarType},
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// BUILTINS SECTION /////////////////////////
+//This section is the one list of the builtin procedures, the ones the runtime library (funcLib) implements
+//The typechecker seeds its symbol table from it and the code generator declares the runtime functions from it
+//tests/builtins.rs checks it against the functions funcLib exports, so a builtin is added here and in funcLib (and the interpreter's callBuiltin)
+//Each row has the address of its funcLib function, the JIT maps the generated code's calls with it
+//A builtin with an integer or float in its signature has a second function for --wide-numbers, its symbol with 64 on the end
+//docs/runtime.md describes each of them
+
+//One builtin procedure
+#[derive(Debug, Clone)]
+pub struct Builtin {
+    pub name: &'static str,                             //The name in the language
+    pub symbol: &'static str,                           //The function funcLib exports for it
+    pub retType: VarType,                               //The return type
+    pub params: &'static [(&'static str, VarType)],     //The parameter names and types, in order
+    pub address: fn(NumberWidth) -> usize,              //Where the function for the width is in this process, the JIT calls it there
+}
+
+//The builtin procedures, the ones after the I/O are exported as rt_<name> so they can't clash with the C library
+//putstring is rt_putstring too, funcLib's putstring is the one for C code
+pub const BUILTINS: &[Builtin] = &[
+    Builtin { name: "getbool", symbol: "getbool", retType: VarType::Bool, params: &[], address: |_| funcLib::getbool as *const () as usize },
+    Builtin { name: "getinteger", symbol: "getinteger", retType: VarType::Int, params: &[], address: |width| byWidth(width, funcLib::getinteger as *const (), funcLib::getinteger64 as *const ()) },
+    Builtin { name: "getfloat", symbol: "getfloat", retType: VarType::Float, params: &[], address: |width| byWidth(width, funcLib::getfloat as *const (), funcLib::getfloat64 as *const ()) },
+    Builtin { name: "getstring", symbol: "getstring", retType: VarType::Str, params: &[], address: |_| funcLib::getstring as *const () as usize },
+    Builtin { name: "putbool", symbol: "putbool", retType: VarType::Bool, params: &[("boolparam", VarType::Bool)], address: |_| funcLib::putbool as *const () as usize },
+    Builtin { name: "putinteger", symbol: "putinteger", retType: VarType::Bool, params: &[("intparam", VarType::Int)], address: |width| byWidth(width, funcLib::putinteger as *const (), funcLib::putinteger64 as *const ()) },
+    Builtin { name: "putfloat", symbol: "putfloat", retType: VarType::Bool, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::putfloat as *const (), funcLib::putfloat64 as *const ()) },
+    Builtin { name: "putstring", symbol: "rt_putstring", retType: VarType::Bool, params: &[("stringparam", VarType::Str)], address: |_| funcLib::rt_putstring as *const () as usize },
+
+    Builtin { name: "sqrt", symbol: "rt_sqrt", retType: VarType::Float, params: &[("intparam", VarType::Int)], address: |width| byWidth(width, funcLib::rt_sqrt as *const (), funcLib::rt_sqrt64 as *const ()) },
+    Builtin { name: "abs", symbol: "rt_abs", retType: VarType::Float, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_abs as *const (), funcLib::rt_abs64 as *const ()) },
+    Builtin { name: "pow", symbol: "rt_pow", retType: VarType::Float, params: &[("base", VarType::Float), ("exponent", VarType::Float)], address: |width| byWidth(width, funcLib::rt_pow as *const (), funcLib::rt_pow64 as *const ()) },
+    Builtin { name: "floor", symbol: "rt_floor", retType: VarType::Float, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_floor as *const (), funcLib::rt_floor64 as *const ()) },
+    Builtin { name: "sin", symbol: "rt_sin", retType: VarType::Float, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_sin as *const (), funcLib::rt_sin64 as *const ()) },
+    Builtin { name: "cos", symbol: "rt_cos", retType: VarType::Float, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_cos as *const (), funcLib::rt_cos64 as *const ()) },
+    Builtin { name: "exp", symbol: "rt_exp", retType: VarType::Float, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_exp as *const (), funcLib::rt_exp64 as *const ()) },
+    Builtin { name: "log", symbol: "rt_log", retType: VarType::Float, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_log as *const (), funcLib::rt_log64 as *const ()) },
+    Builtin { name: "stringlength", symbol: "rt_stringlength", retType: VarType::Int, params: &[("stringparam", VarType::Str)], address: |width| byWidth(width, funcLib::rt_stringlength as *const (), funcLib::rt_stringlength64 as *const ()) },
+    Builtin { name: "concat", symbol: "rt_concat", retType: VarType::Str, params: &[("first", VarType::Str), ("second", VarType::Str)], address: |_| funcLib::rt_concat as *const () as usize },
+    Builtin { name: "substring", symbol: "rt_substring", retType: VarType::Str, params: &[("stringparam", VarType::Str), ("start", VarType::Int), ("count", VarType::Int)], address: |width| byWidth(width, funcLib::rt_substring as *const (), funcLib::rt_substring64 as *const ()) },
+    Builtin { name: "stringcompare", symbol: "rt_stringcompare", retType: VarType::Int, params: &[("first", VarType::Str), ("second", VarType::Str)], address: |width| byWidth(width, funcLib::rt_stringcompare as *const (), funcLib::rt_stringcompare64 as *const ()) },
+    Builtin { name: "inttostring", symbol: "rt_inttostring", retType: VarType::Str, params: &[("intparam", VarType::Int)], address: |width| byWidth(width, funcLib::rt_inttostring as *const (), funcLib::rt_inttostring64 as *const ()) },
+    Builtin { name: "floattostring", symbol: "rt_floattostring", retType: VarType::Str, params: &[("floatparam", VarType::Float)], address: |width| byWidth(width, funcLib::rt_floattostring as *const (), funcLib::rt_floattostring64 as *const ()) },
+    Builtin { name: "stringtoint", symbol: "rt_stringtoint", retType: VarType::Int, params: &[("stringparam", VarType::Str)], address: |width| byWidth(width, funcLib::rt_stringtoint as *const (), funcLib::rt_stringtoint64 as *const ()) },
+    Builtin { name: "stringtofloat", symbol: "rt_stringtofloat", retType: VarType::Float, params: &[("stringparam", VarType::Str)], address: |width| byWidth(width, funcLib::rt_stringtofloat as *const (), funcLib::rt_stringtofloat64 as *const ()) },
+    Builtin { name: "seedrandom", symbol: "rt_seedrandom", retType: VarType::Bool, params: &[("seed", VarType::Int)], address: |width| byWidth(width, funcLib::rt_seedrandom as *const (), funcLib::rt_seedrandom64 as *const ()) },
+    Builtin { name: "random", symbol: "rt_random", retType: VarType::Int, params: &[("limit", VarType::Int)], address: |width| byWidth(width, funcLib::rt_random as *const (), funcLib::rt_random64 as *const ()) },
+    Builtin { name: "time", symbol: "rt_time", retType: VarType::Int, params: &[], address: |width| byWidth(width, funcLib::rt_time as *const (), funcLib::rt_time64 as *const ()) },
+    Builtin { name: "exit", symbol: "rt_exit", retType: VarType::Bool, params: &[("code", VarType::Int)], address: |width| byWidth(width, funcLib::rt_exit as *const (), funcLib::rt_exit64 as *const ()) },
+];
+
+impl Builtin {
+    //Checks if the signature has an integer or a float, these change size with the number width
+    pub fn hasNumbers(&self) -> bool {
+        let isNumber = |varType: &VarType| matches!(varType, VarType::Int | VarType::Float | VarType::IntArray(_));
+        return isNumber(&self.retType) || self.params.iter().any(|(_, paramType)| isNumber(paramType));
+    }
+
+    //The function funcLib exports for the builtin at the number width
+    pub fn symbolFor(&self, width: NumberWidth) -> String {
+        if width == NumberWidth::Bits64 && self.hasNumbers() {
+            return format!("{}64", self.symbol);
+        }
+        return self.symbol.to_string();
+    }
+}
+
+//The funcLib functions the generated code calls on its own, a program can't call them
+//rt_setup sets the runtime error exit code and the bad input handling at the start of main
+//rt_panic ends the program with a runtime error and rt_arithpanic reports a failed --checked-arith check
+//rt_flush writes the buffered output before main returns and rt_setline keeps the line of the builtin call for its errors
+pub const SETUP_HOOK: &str = "rt_setup";
+pub const FLUSH_HOOK: &str = "rt_flush";
+pub const PANIC_HOOK: &str = "rt_panic";
+pub const ARITH_PANIC_HOOK: &str = "rt_arithpanic";
+pub const LINE_HOOK: &str = "rt_setline";
+pub const RUNTIME_HOOKS: &[(&str, fn() -> usize)] = &[
+    (SETUP_HOOK, || funcLib::rt_setup as *const () as usize),
+    (FLUSH_HOOK, || funcLib::rt_flush as *const () as usize),
+    (PANIC_HOOK, || funcLib::rt_panic as *const () as usize),
+    (ARITH_PANIC_HOOK, || funcLib::rt_arithpanic as *const () as usize),
+    (LINE_HOOK, || funcLib::rt_setline as *const () as usize),
+];
+
+//The funcLib functions only C code linked with the program calls, they take NUL terminated char* strings
+//putstring prints a C string, the program's putstring is rt_putstring and takes a string value
+pub const C_EXPORTS: &[&str] = &["putstring"];
+
+//Picks the function for the number width
+fn byWidth(width: NumberWidth, narrow: *const (), wide: *const ()) -> usize {
+    match width {
+        NumberWidth::Bits32 => return narrow as usize,
+        NumberWidth::Bits64 => return wide as usize,
+    }
+}
+
+//Every function of funcLib the generated code can call and where it is in this process, (symbol, address)
+//The JIT maps the module's declarations to these, both widths are listed because a module only declares the ones it uses
+pub fn runtimeAddresses() -> Vec<(String, usize)> {
+    let mut addresses: Vec<(String, usize)> = Vec::new();
+    for builtin in BUILTINS {
+        addresses.push((builtin.symbolFor(NumberWidth::Bits32), (builtin.address)(NumberWidth::Bits32)));
+        if builtin.hasNumbers() {
+            addresses.push((builtin.symbolFor(NumberWidth::Bits64), (builtin.address)(NumberWidth::Bits64)));
+        }
+    }
+    for (symbol, address) in RUNTIME_HOOKS {
+        addresses.push((symbol.to_string(), address()));
+    }
+    return addresses;
+}
+
+//Finds a builtin by name
+pub fn findBuiltin(name: &str) -> Option<&'static Builtin> {
+    return BUILTINS.iter().find(|builtin| builtin.name == name);
+}
+
+//The Rust type funcLib uses for a value of the type, strings are passed and returned as a pointer to their characters (after the length header)
+pub fn runtimeType(varType: &VarType, width: NumberWidth) -> &'static str {
+    match (varType, width) {
+        (VarType::Int, NumberWidth::Bits32) => {
+            return "i32";
+        }
+        (VarType::Int, NumberWidth::Bits64) => {
+            return "i64";
+        }
+        (VarType::Float, NumberWidth::Bits32) => {
+            return "f32";
+        }
+        (VarType::Float, NumberWidth::Bits64) => {
+            return "f64";
+        }
+        (VarType::Bool, _) => {
+            return "bool";
+        }
+        (VarType::Str, _) => {
+            return "*const c_char";
+        }
+        (VarType::IntArray(_), NumberWidth::Bits32) => {
+            return "*const i32";
+        }
+        (VarType::IntArray(_), NumberWidth::Bits64) => {
+            return "*const i64";
+        }
+    }
+}
+
+///////////////////////// /BUILTINS SECTION /////////////////////////
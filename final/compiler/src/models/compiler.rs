@@ -18,11 +18,13 @@ extern crate unicode_segmentation;
 
 //package imports
 use {
-    crate::models::{lexer::Lexer, parser::{Expr, Parser, *}, typechecker::{
+    crate::verboseln,
+    crate::models::{builtins::{findBuiltin, ARITH_PANIC_HOOK, BUILTINS, FLUSH_HOOK, LINE_HOOK, SETUP_HOOK}, lexer::Lexer, options::BadInput, parser::{Expr, Parser, *}, typechecker::{
         SymbolTable, SyntaxChecker
-    }}, anyhow::Result, core::panic, inkwell::{builder::Builder, context::{self, Context}, module::Module, types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType}, values::*, AddressSpace, FloatPredicate, IntPredicate}, parse_display::Display, std::{
-        array, collections::HashMap, env::{self, args}, ffi::CString, fmt, rc::Rc
-    }
+    }}, anyhow::Result, core::panic, inkwell::{builder::Builder, context::{self, Context}, module::{FlagBehavior, Linkage, Module}, types::{AnyTypeEnum, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FloatType, FunctionType, IntType, PointerType}, values::*, AddressSpace, FloatPredicate, IntPredicate}, parse_display::Display, std::{
+        array, collections::HashMap, env::{self, args}, ffi::CString, fmt, fs, path::Path, rc::Rc
+    },
+    inkwell::debug_info::{AsDIScope, DebugInfoBuilder, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DIType, DWARFEmissionKind, DWARFSourceLanguage},
 };
 
 ///////////////////////// Setup /////////////////////////
@@ -181,6 +183,13 @@ pub struct Compiler<'ctx> {
     pub globalTable: &'ctx mut HashMap<String, PointerValue<'ctx>>, // Shared global table
     pub name: String,
     pub stdIn: String,
+    debugInfo: Option<DebugInfo<'ctx>>, //The DWARF debug info builder, only set with -g (enableDebugInfo)
+    pub numberWidth: NumberWidth, //The size of integer and float, i32 and f32 unless --wide-numbers makes them i64 and f64
+    pub checkedArith: bool,     //Checks integer arithmetic for overflow and division by zero (--checked-arith)
+    pub errorExitCode: i32,     //The exit code of a runtime error, main gives it to funcLib (--error-exit-code)
+    pub badInput: BadInput,     //What the get builtins do with bad input, main gives it to funcLib (--bad-input)
+    curLine: String,            //The line of the statement being compiled, a failed arithmetic check and a builtin call report it
+    procScopes: Vec<HashMap<String, FunctionValue<'ctx>>>, //The procedures declared in each enclosing procedure, innermost last, LLVM renames a nested procedure that reuses a name
 }
 
 impl<'ctx> Compiler<'ctx> {
@@ -206,18 +215,101 @@ impl<'ctx> Compiler<'ctx> {
             module,
             builder,
             stdIn,
+            debugInfo: None,
+            numberWidth: NumberWidth::Bits32,
+            checkedArith: false,
+            errorExitCode: 1,
+            badInput: BadInput::Abort,
+            curLine: "0".to_string(),
+            procScopes: vec![HashMap::new()],
+        }
+    }
+
+    //The llvm type of an integer (and of an array element) at the number width
+    fn intType(&self) -> IntType<'ctx> {
+        match self.numberWidth {
+            NumberWidth::Bits32 => return self.context.i32_type(),
+            NumberWidth::Bits64 => return self.context.i64_type(),
+        }
+    }
+
+    //The llvm type of a float at the number width
+    fn floatType(&self) -> FloatType<'ctx> {
+        match self.numberWidth {
+            NumberWidth::Bits32 => return self.context.f32_type(),
+            NumberWidth::Bits64 => return self.context.f64_type(),
+        }
+    }
+
+    //The llvm type of a string, a pointer to its UTF-8 bytes with the length header before them (see stringConstant)
+    fn stringType(&self) -> PointerType<'ctx> {
+        return self.context.i8_type().ptr_type(AddressSpace::default());
+    }
+
+    //A string constant in the module, literals and the value a string variable starts with
+    //It is laid out like the strings funcLib makes, { i64 byte length, i64 character count, bytes, NUL }
+    //The block is a private global and a string value points at the first byte, so funcLib finds the lengths before it
+    fn stringConstant(&self, text: &str) -> PointerValue<'ctx> {
+        let i64Type = self.context.i64_type();
+        let chars = self.context.const_string(text.as_bytes(), true);
+        let byteLength = i64Type.const_int(text.len() as u64, false);
+        let charCount = i64Type.const_int(text.chars().count() as u64, false);
+        let block = self.context.const_struct(&[byteLength.into(), charCount.into(), chars.into()], false);
+        let blockGlobal = self.module.add_global(block.get_type(), None, "stringConstant");
+        blockGlobal.set_initializer(&block);
+        blockGlobal.set_constant(true);
+        blockGlobal.set_linkage(Linkage::Private);
+        blockGlobal.set_unnamed_addr(true);
+        let i32Type = self.context.i32_type();
+        let firstByte = [i32Type.const_zero(), i32Type.const_int(2, false), i32Type.const_zero()];
+        return unsafe { blockGlobal.as_pointer_value().const_in_bounds_gep(&firstByte) };
+    }
+
+    //Converts a value to the type of a variable, parameter or return value, like the interpreter's convertTo
+    //Numbers convert between integer and float, a bool is 0 or 1 and a number is true when it isn't 0
+    fn convertValue(&self, value: BasicValueEnum<'ctx>, target: BasicTypeEnum<'ctx>, builder: &Builder<'ctx>) -> Result<BasicValueEnum<'ctx>, String> {
+        let converted = match (target, value) {
+            (BasicTypeEnum::FloatType(floatType), BasicValueEnum::IntValue(intVal)) => {
+                if intVal.get_type().get_bit_width() == 1 {
+                    builder.build_unsigned_int_to_float(intVal, floatType, "boolToFloat").map(BasicValueEnum::FloatValue)
+                } else {
+                    builder.build_signed_int_to_float(intVal, floatType, "intToFloat").map(BasicValueEnum::FloatValue)
+                }
+            }
+            (BasicTypeEnum::IntType(intType), BasicValueEnum::FloatValue(floatVal)) => {
+                if intType.get_bit_width() == 1 {
+                    builder.build_float_compare(FloatPredicate::ONE, floatVal, floatVal.get_type().const_zero(), "floatToBool").map(BasicValueEnum::IntValue)
+                } else {
+                    builder.build_float_to_signed_int(floatVal, intType, "floatToInt").map(BasicValueEnum::IntValue)
+                }
+            }
+            (BasicTypeEnum::IntType(intType), BasicValueEnum::IntValue(intVal)) if intType.get_bit_width() != intVal.get_type().get_bit_width() => {
+                if intType.get_bit_width() == 1 {
+                    builder.build_int_compare(IntPredicate::NE, intVal, intVal.get_type().const_zero(), "intToBool").map(BasicValueEnum::IntValue)
+                } else if intVal.get_type().get_bit_width() == 1 {
+                    builder.build_int_z_extend(intVal, intType, "boolToInt").map(BasicValueEnum::IntValue)
+                } else {
+                    builder.build_int_cast(intVal, intType, "intCast").map(BasicValueEnum::IntValue)
+                }
+            }
+            _ => Ok(value),
+        };
+        return converted.map_err(|err| format!("Error converting {} to {}: {}", value.get_type(), target, err));
+    }
+
+    //The size of an integer or a float in bits, for the debug info
+    fn numberBits(&self) -> u64 {
+        match self.numberWidth {
+            NumberWidth::Bits32 => return 32,
+            NumberWidth::Bits64 => return 64,
         }
     }
 
     pub fn compileProgram(&mut self) -> Result<&Module<'ctx>, String>{
         match self.programAst.clone(){
-            Stmt::Program(progName, headerBox, bodyBox, lineNum) => {
+            Stmt::Program(progName, headerBox, bodyBox, lineNum, ..) => {
                 //Adds the built ints
-                self.defineGetInt();
-                self.definePutInt();
-                self.defineGetFloat();
-                self.definePutBool();
-                self.definePutFloat();
+                self.defineBuiltins();
                 
                 
                 //Creates the main function
@@ -225,8 +317,10 @@ impl<'ctx> Compiler<'ctx> {
                 let mainType = i32Type.fn_type(&[], false);
 
                 let mut mainFunc = self.module.add_function("main", mainType, None);
+                let mainLine = bodyBox.lineNum().parse::<u32>().unwrap_or(0);
+                self.debugFunction(mainFunc, "main", mainLine, Some(i32Type.as_basic_type_enum()), &Vec::new());
 
-                println!("Program ast is a program");
+                verboseln!("Program ast is a program");
                 //Goes through the header and adds each line to the module
                 let header = headerBox.clone();
                 let mut progHeader = *header;
@@ -238,37 +332,48 @@ impl<'ctx> Compiler<'ctx> {
 
                 let mut mainLocalTable: HashMap<String, PointerValue<'ctx>> = HashMap::new();
                 // self.builder = mainBuilder;
-                if let Stmt::Block(ref instrs, lineNum) = progHeader.clone() {
+                if let Stmt::Block(ref instrs, lineNum, ..) = progHeader.clone() {
                     for instr in instrs {
-                        self.compileStmt(instr.clone(), &mainBuilder, &mut mainLocalTable, mainFunc);
+                        self.compileStmt(instr.clone(), &mainBuilder, &mut mainLocalTable, Some(mainFunc));
                     }
                 } else {
-                    println!("Problem with AST: header must be a Block");
+                    verboseln!("Problem with AST: header must be a Block");
                 }
 
-                println!("Header processed");
+                verboseln!("Header processed");
 
                 //Creates the entrypoint at the main function
                 let mainBlock = self.context.append_basic_block(mainFunc, "entry");
                 mainBuilder.position_at_end(mainBlock);
-                println!("Created entry point");
+                verboseln!("Created entry point");
+
+                //Tells funcLib how to handle runtime errors and bad input before anything runs
+                let setupHook = self.setupHook();
+                let setupArgs = [i32Type.const_int(self.errorExitCode as u64, false).into(), i32Type.const_int(self.badInput.code() as u64, false).into()];
+                let _ = mainBuilder.build_call(setupHook, &setupArgs, "runtimeSetup");
 
-                println!("Time to go through body");
+                verboseln!("Time to go through body");
                 //Goes through the body and adds each line to the module
                 let newBodyBox = bodyBox.clone();
                 let mut body = *newBodyBox;
 
                 // Check if the variable is a Block and iterate through it
-                if let Stmt::Block(ref instrs, lineNum) = body.clone() {
+                if let Stmt::Block(ref instrs, lineNum, ..) = body.clone() {
                     for instr in instrs {
-                        let good = self.compileStmt(instr.clone(), &mainBuilder, &mut mainLocalTable, mainFunc);
+                        let good = self.compileStmt(instr.clone(), &mainBuilder, &mut mainLocalTable, Some(mainFunc));
                     }
                 } else {
-                    println!("Problem with AST: header must be a Block");
+                    verboseln!("Problem with AST: header must be a Block");
                     
                 }
-                let mainRet = i32Type.const_int(0, false);
-                let _ = mainBuilder.build_return(Some(&mainRet));
+                //Falling off the end is status 0, a return at the end of the body already ended the block
+                let ended = mainBuilder.get_insert_block().map(|block| block.get_terminator().is_some()).unwrap_or(false);
+                if !ended {
+                    let flushHook = self.flushHook();
+                    let _ = mainBuilder.build_call(flushHook, &[], "flushOutput");
+                    let mainRet = i32Type.const_int(0, false);
+                    let _ = mainBuilder.build_return(Some(&mainRet));
+                }
             }
             _ => {
                 let errMsg = format!("ProgramAst must be a Program Stmt");
@@ -279,14 +384,27 @@ impl<'ctx> Compiler<'ctx> {
         // if let (Stmt::Program(progName, head, body, lineNum) = self.programAst.clone()) {
         //     println!("program good");
         // }
+
+        //The debug info has to be finalized before the module is verified or written
+        if let Some(debug) = &self.debugInfo {
+            debug.builder.finalize();
+        }
+
+        //A module that doesn't verify can't be cloned, optimized or run, so it is a compile error
+        if let Err(errMsg) = self.module.verify() {
+            return Err(format!("The generated module is not valid: {}", errMsg.to_string()));
+        }
         
         return Ok(&self.module);
     }
 
-    fn compileStmt(&mut self, stmt: Stmt, builder: &Builder<'ctx>, localTable: &mut HashMap<String, PointerValue<'ctx>>, function: FunctionValue) -> bool{
+    fn compileStmt(&mut self, stmt: Stmt, builder: &Builder<'ctx>, localTable: &mut HashMap<String, PointerValue<'ctx>>, function: Option<FunctionValue<'ctx>>) -> bool{
+        //The instructions of the statement get its line (with -g)
+        self.debugLocation(&stmt.lineNum(), builder, function);
+        self.curLine = stmt.lineNum();
         match stmt.clone(){
             //For global variable declarations
-            Stmt::VarDecl(varName, varType, lineNum) => {
+            Stmt::VarDecl(varName, varType, lineNum, ..) => {
                 match varType{
                     VarType::Bool => {
                         let localType = self.context.bool_type();
@@ -303,7 +421,7 @@ impl<'ctx> Compiler<'ctx> {
                                 localPtr = ptr.clone();
                             }
                             Err(err) => {
-                                println!("Error allocating local bool variable {}", localName.clone());
+                                verboseln!("Error allocating local bool variable {}", localName.clone());
                                 panic!();
                             }
                         }
@@ -311,12 +429,13 @@ impl<'ctx> Compiler<'ctx> {
                         let initVal = localType.const_int(0, false);
                         let _ = builder.build_store(localPtr, initVal);
 
+                        self.debugDeclare(&varName, self.debugType(&varType), None, localPtr, &lineNum, builder, function);
                         localTable.insert(varName.clone(), localPtr);
                         
                         return true;
                     }
                     VarType::Float => {
-                        let localType = self.context.f32_type();
+                        let localType = self.floatType();
                         let localName = varName.clone();
                         
                         
@@ -330,7 +449,7 @@ impl<'ctx> Compiler<'ctx> {
                                 localPtr = ptr.clone();
                             }
                             Err(err) => {
-                                println!("Error allocating local float variable {}", localName.clone());
+                                verboseln!("Error allocating local float variable {}", localName.clone());
                                 panic!();
                             }
                         }
@@ -338,12 +457,13 @@ impl<'ctx> Compiler<'ctx> {
                         let initVal = localType.const_float(0.0);
                         let _ = builder.build_store(localPtr, initVal);
 
+                        self.debugDeclare(&varName, self.debugType(&varType), None, localPtr, &lineNum, builder, function);
                         localTable.insert(varName.clone(), localPtr);
                         
                         return true;
                     }
                     VarType::Int => {
-                        let localType = self.context.i32_type();
+                        let localType = self.intType();
                         let localName = varName.clone();
                         
                         
@@ -357,7 +477,7 @@ impl<'ctx> Compiler<'ctx> {
                                 localPtr = ptr.clone();
                             }
                             Err(err) => {
-                                println!("Error allocating local int variable {}: {}", localName.clone(), err);
+                                verboseln!("Error allocating local int variable {}: {}", localName.clone(), err);
                                 panic!();
                             }
                         }
@@ -365,18 +485,15 @@ impl<'ctx> Compiler<'ctx> {
                         let initVal = localType.const_int(0, false);
                         let _ = builder.build_store(localPtr, initVal);
 
+                        self.debugDeclare(&varName, self.debugType(&varType), None, localPtr, &lineNum, builder, function);
                         localTable.insert(varName.clone(), localPtr);
                         
                         return true;
                     }
                     VarType::Str => {
-                        let maxStringLen = 64 as u32 + 1;
-                        let i8Type = self.context.i8_type();
-                        let arrayType = i8Type.array_type(maxStringLen);
-                        // let stringVal: Vec<IntValue> = 
-
+                        let stringType = self.stringType();
                         
-                        let localVarCheck = builder.build_alloca(arrayType.clone(), &varName.clone());
+                        let localVarCheck = builder.build_alloca(stringType.clone(), &varName.clone());
 
                         let localPtr: PointerValue;
                         match localVarCheck{
@@ -384,27 +501,23 @@ impl<'ctx> Compiler<'ctx> {
                                 localPtr = ptr.clone();
                             }
                             Err(err) => {
-                                println!("Error allocating local str variable {}", varName.clone());
+                                verboseln!("Error allocating local str variable {}", varName.clone());
                                 panic!();
                             }
                         }
 
-                        let string = "EMPTY";
-                        let stringBytes = string.as_bytes();
-                        let arrayVal = self.context.const_string(stringBytes, false).clone();
-            
-            
-                        // Wrap the array constant in a BasicValueEnum
-                        let initVal = BasicValueEnum::ArrayValue(arrayVal);
+                        //A string starts empty like it does in the interpreter
+                        let initVal = self.stringConstant("");
                         let _ = builder.build_store(localPtr, initVal);
 
+                        self.debugDeclare(&varName, self.debugType(&varType), None, localPtr, &lineNum, builder, function);
                         localTable.insert(varName.clone(), localPtr);
                         
                         return true;
                     }
                     VarType::IntArray(size) => {
                         let arrSize = size as u32;
-                        let i32Type = self.context.i32_type();
+                        let i32Type = self.intType();
                         let arrayType = i32Type.array_type(arrSize);
                         let globName = varName.clone();
 
@@ -418,10 +531,11 @@ impl<'ctx> Compiler<'ctx> {
                                 localPtr = ptr.clone();
                             }
                             Err(err) => {
-                                println!("Error allocating local str variable {}", varName.clone());
+                                verboseln!("Error allocating local str variable {}", varName.clone());
                                 panic!();
                             }
                         }
+                        self.debugDeclare(&varName, self.debugType(&varType), None, localPtr, &lineNum, builder, function);
                         localTable.insert(varName.clone(), localPtr);
                         
                         return true;
@@ -431,7 +545,7 @@ impl<'ctx> Compiler<'ctx> {
                 
             }
             
-            Stmt::GlobVarDecl(varName, varType, lineNum) => {
+            Stmt::GlobVarDecl(varName, varType, lineNum, ..) => {
                 match varType{
                     VarType::Bool => {
                         let boolType = self.context.bool_type();
@@ -444,7 +558,7 @@ impl<'ctx> Compiler<'ctx> {
                         return true;
                     }
                     VarType::Float => {
-                        let varType = self.context.f32_type();
+                        let varType = self.floatType();
                         let globName = varName.clone();
                         let globVar = self.module.add_global(varType.clone(), None, &globName);
                         let _ =  globVar.set_initializer(&varType.const_float(0.0));
@@ -455,7 +569,7 @@ impl<'ctx> Compiler<'ctx> {
                         return true;
                     }
                     VarType::Int => {
-                        let varType = self.context.i32_type();
+                        let varType = self.intType();
                         let globName = varName.clone();
                         let globVar = self.module.add_global(varType.clone(), None, &globName);
                         
@@ -467,43 +581,26 @@ impl<'ctx> Compiler<'ctx> {
                         return true;
                     }
                     VarType::Str => {
-
-                        // Define the array type [65 x i8]
-                        let max_string_len = 65;
-                        let i8_type = self.context.i8_type();
-                        let array_type = i8_type.array_type(max_string_len);
-
-                        // Create a string that fits exactly 65 characters with padding and null terminator
-                        let string_value = "A".repeat(64) + "\0"; // 64 spaces + null terminator
-
-                        // Convert the string into a byte array
-                        let string_bytes = string_value.into_bytes();
+                        let stringType = self.stringType();
+                        let globName = varName.clone();
+                        let globVar = self.module.add_global(stringType.clone(), None, &globName);
+                        let _ = globVar.set_initializer(&self.stringConstant(""));
                         
-                        // // Create the constant array with the string bytes
-                        // let const_array = array_type.const_array(
-                        //     &string_bytes.iter().map(|&byte| i8_type.const_int(byte as u64, false).into()).collect::<Vec<BasicValueEnum>>()
-                        // );
-
-                        // Declare the global variable and initialize it
-                        let glob_name = varName.clone();
-                        let glob_var = self.module.add_global(array_type, Some(AddressSpace::default()), &glob_name);
-                        let globPtr = glob_var.as_pointer_value();
-                        // let test = unsafe { ArrayValue::new(string_value) };
-                        let test = array_type.const_zero();
-                        self.globalTable.insert(glob_name.clone(), globPtr);
-                        glob_var.set_initializer(&test);
+                        let globPtr = globVar.as_pointer_value();
+                        self.globalTable.insert(varName.clone(), globPtr);
                         
                         return true;
                     }
                     VarType::IntArray(size) => {
                         let arrSize = size as u32;
-                        let i32Type = self.context.i32_type();
+                        let i32Type = self.intType();
                         let arrayType = i32Type.array_type(arrSize);
                         let globName = varName.clone();
 
 
-                        //Adds to the global variables
+                        //Adds to the global variables, every element starts at 0 like the other globals
                         let globVar = self.module.add_global(arrayType.clone(), None, &globName);
+                        let _ = globVar.set_initializer(&arrayType.const_zero());
                         let globPtr = globVar.as_pointer_value();
                         self.globalTable.insert(varName.clone(), globPtr);
                         
@@ -513,28 +610,28 @@ impl<'ctx> Compiler<'ctx> {
                 }
                 
             }
-            Stmt::Assign(variable, newValue, lineNum) => {
+            Stmt::Assign(variable, newValue, lineNum, ..) => {
                 let mut variablePtr: PointerValue;
                 let mut newEnumValue: BasicValueEnum;
                 let mut varName: String;
 
-                if let Expr::VarRef(ref targName) = variable {
+                if let Expr::VarRef(ref targName, ..) = variable {
                     varName = targName.clone();
                     let checkLocVar = localTable.get(&targName.clone());
                     match checkLocVar{
                         Some(ptr) => {
-                            println!("Assigninig local variable {} at location {}", targName.clone(), ptr.clone());
+                            verboseln!("Assigninig local variable {} at location {}", targName.clone(), ptr.clone());
                             variablePtr = ptr.clone();
                         }
                         None => {
                             let checkGlobVar = self.globalTable.get(&targName.clone());
                             match checkGlobVar{
                                 Some(ptr) => {
-                                    println!("Assigninig global variable {} at location {}", targName.clone(), ptr.clone());
+                                    verboseln!("Assigninig global variable {} at location {}", targName.clone(), ptr.clone());
                                     variablePtr = ptr.clone();
                                 }
                                 None => {
-                                    println!("variable {} not found", targName.clone());
+                                    verboseln!("variable {} not found", targName.clone());
                                     panic!();
                                 }
                             }
@@ -542,11 +639,11 @@ impl<'ctx> Compiler<'ctx> {
                     }
                 }
                 
-                else if let Expr::ArrayRef(ref targName, indexExpr) = variable{
+                else if let Expr::ArrayRef(ref targName, indexExpr, ..) = variable{
                     // println!("ASsigning")
                     varName = targName.clone();
                     let arrSize = 64 as u32;
-                    let i32Type = self.context.i32_type().clone();
+                    let i32Type = self.intType().clone();
                     let arrayType = i32Type.array_type(arrSize).clone();
                     
                     //Gets the value of the index expression
@@ -557,7 +654,7 @@ impl<'ctx> Compiler<'ctx> {
                             indexVal = val.clone();
                         }
                         Err(err) => {
-                            println!("{}", err.clone());
+                            verboseln!("{}", err.clone());
                             panic!();
                         }
                     }
@@ -567,18 +664,18 @@ impl<'ctx> Compiler<'ctx> {
                     let checkLocVar = localTable.get(&targName.clone());
                     match checkLocVar{
                         Some(ptr) => {
-                            println!("Assigninig local variable {} at location {}", targName.clone(), ptr.clone());
+                            verboseln!("Assigninig local variable {} at location {}", targName.clone(), ptr.clone());
                             arrayPtr = ptr.clone();
                         }
                         None => {
                             let checkGlobVar = self.globalTable.get(&targName.clone());
                             match checkGlobVar{
                                 Some(ptr) => {
-                                    println!("Assigninig global array variable {} at location {}", targName.clone(), ptr.clone());
+                                    verboseln!("Assigninig global array variable {} at location {}", targName.clone(), ptr.clone());
                                     arrayPtr = ptr.clone();
                                 }
                                 None => {
-                                    println!("variable {} not found", targName.clone());
+                                    verboseln!("variable {} not found", targName.clone());
                                     panic!();
                                 }
                             }
@@ -591,38 +688,38 @@ impl<'ctx> Compiler<'ctx> {
                             indexInt = val.clone();
                         }
                         BasicValueEnum::FloatValue(val) => {
-                            let intType = self.context.i32_type().clone();
+                            let intType = self.intType().clone();
                             let intValue = builder.build_float_to_signed_int(val.clone(), intType, "float_to_int");
                             match intValue{
                                 Ok(iVal) => {
                                     indexInt = iVal.clone();
                                 }
                                 Err(err) => {
-                                    println!("Error converting float to int");
+                                    verboseln!("Error converting float to int");
                                     panic!();
                                 }
                             }
 
                         }
                         _ => {
-                            println!("Can only index by integer");
+                            verboseln!("Can only index by integer");
                             panic!();
                         }
                     }
                 
                     // Get the pointer to the desired index
                     // let variablePtr: PointerValue;
-                    let intType = self.context.i32_type().clone();
+                    let intType = self.intType().clone();
                     let zero = intType.const_int(0, false);
                     let indexList = [zero, indexInt];
                     let checkIndexPtr = unsafe { builder.build_gep(arrayPtr, &indexList, "arrayIndexLoad") };
                     match checkIndexPtr{
                         Ok(ptr) => {
-                            println!("GOT ARRAY INDEX PTR");
+                            verboseln!("GOT ARRAY INDEX PTR");
                             variablePtr = ptr.clone();
                         }
                         Err(err) => {
-                            println!("Error getting array index ptr");
+                            verboseln!("Error getting array index ptr");
                             panic!();
                         }
                     }
@@ -631,128 +728,39 @@ impl<'ctx> Compiler<'ctx> {
                 }
                 
                 else {
-                    println!("Cannot assing to a non variable");
+                    verboseln!("Cannot assing to a non variable");
                     panic!();
                 }
 
-                if let Expr::ArrayRef(ref targName, indexExpr) = newValue.clone() {
-                    println!("array reference");
-                    // let targName = name.clone();
-                    let arrSize = 64 as u32;
-                    let i32Type = self.context.i32_type().clone();
-                    let arrayType = i32Type.array_type(arrSize).clone();
-                    
-                    //Gets the value of the index expression
-                    let indexExprCheck = self.compileExpr(&*&indexExpr.clone(), builder, localTable);
-                    let mut indexVal: BasicValueEnum;
-                    match indexExprCheck{
-                        Ok(val) => {
-                            indexVal = val.clone();
-                        }
-                        Err(err) => {
-                            println!("{}", err.clone());
-                            println!("Could error with index {}", err.clone());
-                            panic!();
-                        }
+                //An array element is loaded by compileExpr like any other value
+                let checkNewValue = self.compileExpr(&newValue.clone(), builder, localTable);
+                match checkNewValue.clone(){
+                    Ok(value) => {
+                        newEnumValue = value.clone();
                     }
-
-                    
-                    let mut arrayPtr: PointerValue;
-                    let checkLocVar = localTable.get(&targName.clone());
-                    match checkLocVar{
-                        Some(ptr) => {
-                            arrayPtr = ptr.clone();
-                        }
-                        None => {
-                            let checkGlobVar = self.globalTable.get(&targName.clone());
-                            match checkGlobVar{
-                                Some(ptr) => {
-                                    arrayPtr = ptr.clone();
-                                }
-                                None => {
-                                    println!("variable {} not found", targName.clone());
-                                    panic!();
-                                }
-                            }
-                        }
-                    }
-
-                    let mut indexInt: IntValue;
-                    match indexVal{
-                        BasicValueEnum::IntValue(val) => {
-                            indexInt = val.clone();
-                        }
-                        BasicValueEnum::FloatValue(val) => {
-                            let intType = self.context.i32_type().clone();
-                            let intValue = builder.build_float_to_signed_int(val.clone(), intType, "float_to_int");
-                            match intValue{
-                                Ok(iVal) => {
-                                    indexInt = iVal.clone();
-                                }
-                                Err(err) => {
-                                    println!("Error converting float to int");
-                                    panic!();
-                                }
-                            }
-
-                        }
-                        _ => {
-                            println!("Can only index by integer");
-                            panic!();
-                        }
-                    }
-                
-                    // Get the pointer to the desired index
-                    let variablePtr: PointerValue;
-                    let checkIndexPtr = unsafe { builder.build_in_bounds_gep(arrayPtr, &[indexInt], "test") };
-                    match checkIndexPtr{
-                        Ok(ptr) => {
-                            variablePtr = ptr.clone();
-                        }
-                        Err(err) => {
-                            println!("Error getting array index ptr");
-                            panic!();
-                        }
-                    }
-
-                    //Gets the value at that pointer
-                    let retValCheck = builder.build_load(variablePtr, "arrayIndexReference");
-                    match retValCheck{
-                        Ok(val) => {
-                            println!("ARRAY INDEX VALUE GOT {}", val.clone());
-                            newEnumValue = val.clone();
-                        }
-                        Err(msg) => {
-                            println!("Error getting array index value");
-                            panic!();
-                        }
-                    }
-                    // let elementPtr = builder.
-                
-                }
-                else {
-                    let checkNewValue = self.compileExpr(&newValue.clone(), builder, localTable);
-                    match checkNewValue.clone(){
-                        Ok(value) => {
-                            
-                            newEnumValue = value.clone();
-                        }
-                        Err(msg) => {
-                            println!("{}", msg.clone());
-                            panic!();
-                        }
+                    Err(msg) => {
+                        verboseln!("{}", msg.clone());
+                        panic!();
                     }
                 }
                 
                 
                 let mut finalVal = newEnumValue.clone();
 
+                //The value is converted to the variable's type, like the interpreter does
+                if let Ok(varType) = BasicTypeEnum::try_from(variablePtr.get_type().get_element_type()) {
+                    match self.convertValue(finalVal, varType, builder) {
+                        Ok(val) => finalVal = val,
+                        Err(err) => panic!("Error assigning {}: {}", varName.clone(), err),
+                    }
+                }
+
                 // let builder = &mut builder;
 
                 // let mut finalVal: IntValue;
                 match finalVal{
                     BasicValueEnum::IntValue(intVal) => {
-                        println!("Stored int value {} in variable {}",intVal.clone(), varName.clone());
+                        verboseln!("Stored int value {} in variable {}",intVal.clone(), varName.clone());
                         let _ = builder.build_store(variablePtr, intVal.clone());
                         return true;
                     }
@@ -762,21 +770,27 @@ impl<'ctx> Compiler<'ctx> {
                         
 
                         
-                        println!("Stored int value {} in variable {}",intVal.clone(), varName.clone());
+                        verboseln!("Stored int value {} in variable {}",intVal.clone(), varName.clone());
                         let _ = builder.build_store(variablePtr, intVal.clone());
                         return true;
                     }
                     BasicValueEnum::ArrayValue(val) => {
                         
 
-                        println!("ARRAY {}", val.clone());
+                        verboseln!("ARRAY {}", val.clone());
                         let _ = builder.build_store(variablePtr, val.clone());
 
                         return true;
                     }
+                    BasicValueEnum::PointerValue(stringVal) => {
+                        //A string value is a pointer to characters that are never changed, so the pointer is all that is stored
+                        verboseln!("Stored string value {} in variable {}", stringVal.clone(), varName.clone());
+                        let _ = builder.build_store(variablePtr, stringVal.clone());
+                        return true;
+                    }
 
                     _ => {
-                        println!("Not implemented for that type yet");
+                        verboseln!("Not implemented for that type yet");
                         return true;
                     }
                 }
@@ -785,11 +799,11 @@ impl<'ctx> Compiler<'ctx> {
                 // return true;
                     
             }
-            Stmt::Block(blockStmt, lineNum) => {
+            Stmt::Block(blockStmt, lineNum, ..) => {
                 for instr in blockStmt.clone() {
                     let good = self.compileStmt(instr.clone(), builder, localTable, function);
                     if (!good){
-                        println!("Error in block:");
+                        verboseln!("Error in block:");
                         instr.display(0);
                         panic!();
                     } else {
@@ -798,11 +812,11 @@ impl<'ctx> Compiler<'ctx> {
                 }
                 return true;
             }
-            Stmt::Error(err, lineNum) => {
-                println!("Somehow an error made it to the compiler");
+            Stmt::Error(err, lineNum, ..) => {
+                verboseln!("Somehow an error made it to the compiler");
                 panic!();
             }
-            Stmt::Expr(exprStmt, lineNum) => {
+            Stmt::Expr(exprStmt, lineNum, ..) => {
                 // println!("ExprStmt needs written");
                 // return true;
                 match (exprStmt.clone()){
@@ -811,58 +825,51 @@ impl<'ctx> Compiler<'ctx> {
                         match checked {
                             Ok(val) => {
                                 // println!("Called expr{}")
-                                println!("SOMETHING SHOULD BE DONE HERE, IDK");
+                                verboseln!("SOMETHING SHOULD BE DONE HERE, IDK");
                                 return true;
                             }
                             Err(err) => {
-                                println!("Error: {}", err.clone());
+                                verboseln!("Error: {}", err.clone());
                                 panic!();
                             }
                         }
                     }
                 }
             }
-            Stmt::For(assignment, condExpr, body, lineNum) => {
+            Stmt::For(assignment, condExpr, body, lineNum, ..) => {
                 //Creates the local builder
                 let forBuilder = builder;
 
                 
-                //Parses the assignment first
-                let mut iInitVal: BasicValueEnum;
-                let mut iName: String;
+                //Checks the assignment first, it is compiled before the loop condition
                 let assignStmt = Rc::clone(&assignment);
-                if let Stmt::Assign(varRef, val, lineNum) = &*assignStmt.clone() {
-                    if let Expr::VarRef(varName) = varRef.clone(){
-                        println!("for loop variable i {}", varName.clone());
-                        iName = varName.clone();
-                        let iteratorValCheck = self.compileExpr(&val.clone(), &forBuilder, localTable);
-                        match iteratorValCheck{
-                            Ok(val) => {
-                                println!("Iterator value: {}", val.clone());
-                                iInitVal = val;
-                            }
-                            Err(err) => {
-                                println!("Error parsing for loop iterator assignment: {}", err.clone());
-                                panic!();
-                            }
-                        }
+                if let Stmt::Assign(varRef, _, _, ..) = &*assignStmt.clone() {
+                    if let Expr::VarRef(varName, ..) = varRef.clone(){
+                        verboseln!("for loop variable i {}", varName.clone());
                     }
                     else {
-                        println!("Error: For loop iterator must be a variable");
+                        verboseln!("Error: For loop iterator must be a variable");
                         panic!();
                     }
                 }
                 else {
-                    println!("Error: For loop assignment must be a variable assignment");
+                    verboseln!("Error: For loop assignment must be a variable assignment");
                     return false
                 }
                 
                 
-                let intType = self.context.i32_type().clone();
+                let intType = self.intType().clone();
                 let fnType = intType.fn_type(&[], false);
 
                 //set up the loop "function"
-                let loopFunction = function;
+                //A module's top level has no function to put the loop in
+                let loopFunction = match function {
+                    Some(loopFunction) => loopFunction,
+                    None => {
+                        verboseln!("For loop outside of a procedure");
+                        return false;
+                    }
+                };
                 // let forEntry = self.context.append_basic_block(loopFunction, "ForEntry");
                 let loopCond = self.context.append_basic_block(loopFunction, "forCond");
                 let loopBody = self.context.append_basic_block(loopFunction, "forBody");
@@ -871,7 +878,9 @@ impl<'ctx> Compiler<'ctx> {
 
                 
                 //Sets up the conditional
-                // let _ = forBuilder.build_store(iPtr, iInitVal.clone());
+                if !self.compileStmt((*assignStmt).clone(), forBuilder, localTable, function){
+                    return false;
+                }
                 let _ = forBuilder.build_unconditional_branch(loopCond);
                 
                 //Loop condition block
@@ -881,7 +890,7 @@ impl<'ctx> Compiler<'ctx> {
                 let mut condOp1Val: BasicValueEnum;
                 let mut condOp2Val: BasicValueEnum;
                 let mut condOp: IntPredicate; 
-                if let Expr::RelOp(op1Box, op, op2Box) = condExpr{
+                if let Expr::RelOp(op1Box, op, op2Box, ..) = condExpr{
                    let op1 = *op1Box.clone();
                    let op2 = *op2Box.clone();
                     match op{
@@ -904,7 +913,7 @@ impl<'ctx> Compiler<'ctx> {
                             condOp = IntPredicate::NE;
                         }
                         _ => {
-                            println!("For condition operator must be logical operator");
+                            verboseln!("For condition operator must be logical operator");
                             panic!();
                         }
                     }
@@ -947,20 +956,20 @@ impl<'ctx> Compiler<'ctx> {
                         op1Int = val.clone();
                     }
                     BasicValueEnum::FloatValue(val) => {
-                        let intType = self.context.i32_type().clone();
+                        let intType = self.intType().clone();
                         let intVal = forBuilder.build_float_to_signed_int(val.clone(), intType.clone(), "floatToInt");
                         match intVal{
                             Ok(val) => {
                                 op1Int = val.clone()
                             }
                             Err(msg) => {
-                                println!("Error converting float to int");
+                                verboseln!("Error converting float to int");
                                 panic!();
                             }
                         }
                     }
                     _ => {
-                        println!("For loop condition values must be numbers");
+                        verboseln!("For loop condition values must be numbers");
                         panic!();
                     }
                 }
@@ -969,20 +978,20 @@ impl<'ctx> Compiler<'ctx> {
                         op2Int = val.clone();
                     }
                     BasicValueEnum::FloatValue(val) => {
-                        let intType = self.context.i32_type().clone();
+                        let intType = self.intType().clone();
                         let intVal = forBuilder.build_float_to_signed_int(val.clone(), intType.clone(), "floatToInt");
                         match intVal{
                             Ok(val) => {
                                 op2Int = val.clone()
                             }
                             Err(msg) => {
-                                println!("Error converting float to int");
+                                verboseln!("Error converting float to int");
                                 panic!();
                             }
                         }
                     }
                     _ => {
-                        println!("For loop condition values must be numbers");
+                        verboseln!("For loop condition values must be numbers");
                         panic!();
                     }
                 }
@@ -995,7 +1004,7 @@ impl<'ctx> Compiler<'ctx> {
                         condition = val.clone();
                     }
                     Err(msg) => {
-                        println!("Error creating condition");
+                        verboseln!("Error creating condition");
                         panic!();
                     }
                 }
@@ -1016,20 +1025,26 @@ impl<'ctx> Compiler<'ctx> {
                 //Moves builder to the end of the block
                 forBuilder.position_at_end(mergeFor);
 
-                println!("CREATED FOR LOOP ");
+                verboseln!("CREATED FOR LOOP ");
 
                 // let _ = builder.build_call(loopFunction.clone(), &[], "forLoopCall");
                 // let _ = builder.build_unconditional_branch(forEntry);
-                println!("Inserted for loop");
+                verboseln!("Inserted for loop");
                 return true;
             }
-            Stmt::If(condExpr, body, elseStmt, lineNum) => {
+            Stmt::If(condExpr, body, elseStmt, lineNum, ..) => {
                 
                 
                 //Sets up the function stuff
                 let voidType = self.context.void_type().clone();
                 let fnType = voidType.fn_type(&[], false);
-                let ifFunction = function;
+                let ifFunction = match function {
+                    Some(ifFunction) => ifFunction,
+                    None => {
+                        verboseln!("If statement outside of a procedure");
+                        return false;
+                    }
+                };
                 // let ifEntry = self.context.append_basic_block(ifFunction, "ifEntry");
                 // let ifCond = self.context.append_basic_block(ifFunction, "ifCondition");
                 let ifBody = self.context.append_basic_block(ifFunction, "ifBody");
@@ -1047,7 +1062,7 @@ impl<'ctx> Compiler<'ctx> {
                 let mut condOp1Val: BasicValueEnum;
                 let mut condOp2Val: BasicValueEnum;
                 let mut condOp: IntPredicate; 
-                if let Expr::RelOp(op1Box, op, op2Box) = condExpr.clone(){
+                if let Expr::RelOp(op1Box, op, op2Box, ..) = condExpr.clone(){
                    let op1 = *op1Box.clone();
                    let op2 = *op2Box.clone();
                     match op{
@@ -1070,7 +1085,7 @@ impl<'ctx> Compiler<'ctx> {
                             condOp = IntPredicate::NE;
                         }
                         _ => {
-                            println!("For condition operator must be logical operator");
+                            verboseln!("For condition operator must be logical operator");
                             panic!();
                         }
                     }
@@ -1082,7 +1097,7 @@ impl<'ctx> Compiler<'ctx> {
                             condOp1Val = val.clone();
                         }
                         Err(err) => {
-                            println!("Error getting if condition op 1: {}", err.clone());
+                            verboseln!("Error getting if condition op 1: {}", err.clone());
                             panic!();
                         }
                     }
@@ -1092,12 +1107,12 @@ impl<'ctx> Compiler<'ctx> {
                             condOp2Val = val.clone();
                         }
                         Err(err) => {
-                            println!("Error getting if condition op 2");
+                            verboseln!("Error getting if condition op 2");
                             panic!();
                         }
                     } 
 
-                } else if let Expr::BoolLiteral(boolVal) = condExpr.clone() {
+                } else if let Expr::BoolLiteral(boolVal, ..) = condExpr.clone() {
                     let intBool = boolVal.clone() as u64;
                     let intVal = self.context.bool_type();
                     let boolConst = intVal.const_int(intBool.clone(), false);
@@ -1108,7 +1123,7 @@ impl<'ctx> Compiler<'ctx> {
                     condOp = IntPredicate::EQ;
 
                 } else {
-                    println!("If loop condition must be a logical operation");
+                    verboseln!("If loop condition must be a logical operation");
                     panic!();
                 }
                 
@@ -1120,20 +1135,20 @@ impl<'ctx> Compiler<'ctx> {
                         op1Int = val.clone();
                     }
                     BasicValueEnum::FloatValue(val) => {
-                        let intType = self.context.i32_type().clone();
+                        let intType = self.intType().clone();
                         let intVal = ifBuilder.build_float_to_signed_int(val.clone(), intType.clone(), "floatToInt");
                         match intVal{
                             Ok(val) => {
                                 op1Int = val.clone()
                             }
                             Err(msg) => {
-                                println!("Error converting float to int");
+                                verboseln!("Error converting float to int");
                                 panic!();
                             }
                         }
                     }
                     _ => {
-                        println!("For loop condition values must be numbers");
+                        verboseln!("For loop condition values must be numbers");
                         panic!();
                     }
                 }
@@ -1142,20 +1157,20 @@ impl<'ctx> Compiler<'ctx> {
                         op2Int = val.clone();
                     }
                     BasicValueEnum::FloatValue(val) => {
-                        let intType = self.context.i32_type().clone();
+                        let intType = self.intType().clone();
                         let intVal = ifBuilder.build_float_to_signed_int(val.clone(), intType.clone(), "floatToInt");
                         match intVal{
                             Ok(val) => {
                                 op2Int = val.clone()
                             }
                             Err(msg) => {
-                                println!("Error converting float to int");
+                                verboseln!("Error converting float to int");
                                 panic!();
                             }
                         }
                     }
                     _ => {
-                        println!("For loop condition values must be numbers");
+                        verboseln!("For loop condition values must be numbers");
                         panic!();
                     }
                 }
@@ -1168,7 +1183,7 @@ impl<'ctx> Compiler<'ctx> {
                         condition = val.clone();
                     }
                     Err(msg) => {
-                        println!("Error creating condition");
+                        verboseln!("Error creating condition");
                         panic!("Invalid condition");
                         
                     }
@@ -1184,29 +1199,29 @@ impl<'ctx> Compiler<'ctx> {
                 let mut ifRet: bool = false;
                 let bodyStmt = *body.clone();
                 match bodyStmt.clone(){
-                    Stmt::Block(stmtVec, lineNum) => {
+                    Stmt::Block(stmtVec, lineNum, ..) => {
                         for stmt in stmtVec.clone(){
-                            println!("COMPILING IF STATEMENT");
+                            verboseln!("COMPILING IF STATEMENT");
                             match stmt.clone(){
-                                Stmt::Return(val, lineNum) => {
-                                    println!("IF RETURN");
+                                Stmt::Return(val, lineNum, ..) => {
+                                    verboseln!("IF RETURN");
                                     let checkedIfBody = self.compileStmt(stmt.clone(), builder, localTable, function);
                                     if checkedIfBody{
                                         //continue
                                     } else {
-                                        println!("Error building if body");
+                                        verboseln!("Error building if body");
                                         panic!();
                                     }
                                     ifRet = true;
                                     break;
                                 }
                                 _ => {
-                                    println!("Not if return");
+                                    verboseln!("Not if return");
                                     let checkedIfBody = self.compileStmt(stmt.clone(), builder, localTable, function);
                                     if checkedIfBody{
                                         //continue
                                     } else {
-                                        println!("Error building if body");
+                                        verboseln!("Error building if body");
                                         panic!();
                                     }
                                     ifRet = false;
@@ -1231,27 +1246,27 @@ impl<'ctx> Compiler<'ctx> {
                 match elseStmt.clone(){
                     Some(elseVal) => {
                         let elseStmt = *elseVal.clone();
-                        println!("If statement with else");
+                        verboseln!("If statement with else");
                         match elseStmt{
-                            Stmt::Return(val, lineNum) => {
+                            Stmt::Return(val, lineNum, ..) => {
                                 let checkedIfBody = self.compileStmt(bodyStmt.clone(), &ifBuilder, localTable, function);
                                 if checkedIfBody{
                                     //continue
                                 } else {
-                                    println!("Error building if body");
+                                    verboseln!("Error building if body");
                                     panic!();
                                 }
                                 elseRet = true;
                             }
-                            Stmt::Block(stmtVec, lineNum) => {
+                            Stmt::Block(stmtVec, lineNum, ..) => {
                                 for stmt in stmtVec.clone(){
                                     match stmt.clone(){
-                                        Stmt::Return(val, lineNum) => {
+                                        Stmt::Return(val, lineNum, ..) => {
                                             let checkedIfBody = self.compileStmt(bodyStmt.clone(), &ifBuilder, localTable, function);
                                             if checkedIfBody{
                                                 //continue
                                             } else {
-                                                println!("Error building if body");
+                                                verboseln!("Error building if body");
                                                 panic!();
                                             }
                                             elseRet = true;
@@ -1261,7 +1276,7 @@ impl<'ctx> Compiler<'ctx> {
                                             if checkedIfBody{
                                                 //continue
                                             } else {
-                                                println!("Error building if body");
+                                                verboseln!("Error building if body");
                                                 panic!();
                                             }
                                             elseRet = false;
@@ -1274,7 +1289,7 @@ impl<'ctx> Compiler<'ctx> {
                                 if checkedIfBody{
                                     //continue
                                 } else {
-                                    println!("Error building if body");
+                                    verboseln!("Error building if body");
                                     panic!();
                                 }
                                 elseRet = false;
@@ -1282,7 +1297,7 @@ impl<'ctx> Compiler<'ctx> {
                         }
                     }
                     None => {
-                        println!("If statement no else");
+                        verboseln!("If statement no else");
                         // let _ = builder.build_unconditional_branch(mergeBack);
                         elseRet = false;
                     }
@@ -1295,15 +1310,15 @@ impl<'ctx> Compiler<'ctx> {
                 //Moves builder to the end of the block
                 ifBuilder.position_at_end(mergeBack);
 
-                println!("CREATED if LOOP ");
+                verboseln!("CREATED if LOOP ");
 
                 // let _ = builder.build_call(ifFunction.clone(), &[], "ifStatementCall");
                 // let _ = builder.build_unconditional_branch(ifEntry);
                 return true;
                 
             }
-            Stmt::ProcDecl(procRetType, procName, params, headerBox, bodyBox, lineNum) => {
-                println!("DECLARING A PROCEDURE");
+            Stmt::ProcDecl(procRetType, procName, params, headerBox, bodyBox, lineNum, ..) => {
+                verboseln!("DECLARING A PROCEDURE");
                 //Creates the local variable hash table
                 let mut procLocTable: HashMap<String, PointerValue<'ctx>> = HashMap::new();
                 
@@ -1316,34 +1331,29 @@ impl<'ctx> Compiler<'ctx> {
                 //Parses the params
                 let paramStmtBlock = *params.clone();
                 match paramStmtBlock.clone(){
-                    Stmt::Block(params, lineNum) => {
+                    Stmt::Block(params, lineNum, ..) => {
                         for param in params{
                             match param.clone(){
-                                Stmt::VarDecl(varName, varType, lineNum) => {
+                                Stmt::VarDecl(varName, varType, lineNum, ..) => {
                                     let mut paramType: BasicTypeEnum;
                                     match varType{
                                         VarType::Bool => {
                                             paramType = self.context.bool_type().as_basic_type_enum().clone();
                                         }
                                         VarType::Float => {
-                                            paramType = self.context.f32_type().as_basic_type_enum().clone();
+                                            paramType = self.floatType().as_basic_type_enum().clone();
                     
                                         }
                                         VarType::Int => {
-                                            paramType = self.context.i32_type().as_basic_type_enum().clone();
+                                            paramType = self.intType().as_basic_type_enum().clone();
                     
                                         }
                                         VarType::IntArray(size) => {
-                                            let arrSize = size as u32;
-                                            let i32Type = self.context.i32_type();
-                                            let arrayType = i32Type.array_type(arrSize);
-                                            
-                                            
-                                            paramType = self.context.i32_type().as_basic_type_enum().clone();
-                    
+                                            //An array is passed by value, the procedure gets its own copy like in the interpreter
+                                            paramType = self.intType().array_type(size as u32).as_basic_type_enum();
                                         }
                                         VarType::Str => {
-                                            paramType = self.context.i8_type().as_basic_type_enum().clone();
+                                            paramType = self.stringType().as_basic_type_enum().clone();
                                             
                                         }
                                     }
@@ -1351,37 +1361,31 @@ impl<'ctx> Compiler<'ctx> {
                                     
                                 }
                                 _ => {
-                                    println!("Function delcaration parameters can only be local variable declarations");
+                                    verboseln!("Function delcaration parameters can only be local variable declarations");
                                     false;
                                 }
                             }
                         }
                     }
-                    Stmt::VarDecl(varName, varType, lineNum) => {
+                    Stmt::VarDecl(varName, varType, lineNum, ..) => {
                         let mut paramType: BasicTypeEnum;
                         match varType{
                             VarType::Bool => {
                                 paramType = self.context.bool_type().as_basic_type_enum().clone();
                             }
                             VarType::Float => {
-                                paramType = self.context.f32_type().as_basic_type_enum().clone();
+                                paramType = self.floatType().as_basic_type_enum().clone();
         
                             }
                             VarType::Int => {
-                                paramType = self.context.i32_type().as_basic_type_enum().clone();
+                                paramType = self.intType().as_basic_type_enum().clone();
         
                             }
                             VarType::IntArray(size) => {
-                                let arrSize = size as u32;
-                                let i32Type = self.context.i32_type();
-                                let arrayType = i32Type.array_type(arrSize);
-                                
-                                
-                                paramType = self.context.i32_type().as_basic_type_enum().clone();
-        
+                                paramType = self.intType().array_type(size as u32).as_basic_type_enum();
                             }
                             VarType::Str => {
-                                paramType = self.context.i8_type().as_basic_type_enum().clone();
+                                paramType = self.stringType().as_basic_type_enum().clone();
                                 
                             }
                         }
@@ -1389,12 +1393,12 @@ impl<'ctx> Compiler<'ctx> {
                         
                     }
                     _ => {
-                        println!("Function delcaration parameters can only be local variable declarations");
+                        verboseln!("Function delcaration parameters can only be local variable declarations");
                         false;
                     }
                 }
                     
-                println!("Created param list");
+                verboseln!("Created param list");
 
                 
                 //Creates the main function
@@ -1404,24 +1408,18 @@ impl<'ctx> Compiler<'ctx> {
                         procTypeEnum = self.context.bool_type().as_basic_type_enum().clone();
                     }
                     VarType::Float => {
-                        procTypeEnum = self.context.f32_type().as_basic_type_enum().clone();
+                        procTypeEnum = self.floatType().as_basic_type_enum().clone();
 
                     }
                     VarType::Int => {
-                        procTypeEnum = self.context.i32_type().as_basic_type_enum().clone();
+                        procTypeEnum = self.intType().as_basic_type_enum().clone();
 
                     }
                     VarType::IntArray(size) => {
-                        let arrSize = size as u32;
-                        let i32Type = self.context.i32_type();
-                        let arrayType = i32Type.array_type(arrSize);
-                        
-                        
-                        procTypeEnum = self.context.i32_type().as_basic_type_enum().clone();
-
+                        procTypeEnum = self.intType().array_type(size as u32).as_basic_type_enum();
                     }
                     VarType::Str => {
-                        procTypeEnum = self.context.i8_type().as_basic_type_enum().clone();
+                        procTypeEnum = self.stringType().as_basic_type_enum().clone();
                         
                     }
                 }
@@ -1430,23 +1428,29 @@ impl<'ctx> Compiler<'ctx> {
 
                 let funcType = procTypeEnum.fn_type(paramTypesSlice, false);
 
-                let intType = self.context.i32_type().clone();
+                let intType = self.intType().clone();
                 // let procType = intType.fn_type(&[], false);
 
                 let procFunVal = self.module.add_function(&procName.clone(), funcType, None);
+                //The procedure can be called from the scope it is declared in, its header starts a new scope
+                if let Some(scope) = self.procScopes.last_mut() {
+                    scope.insert(procName.clone(), procFunVal);
+                }
+                self.procScopes.push(HashMap::new());
+                self.debugFunction(procFunVal, &procName, lineNum.parse::<u32>().unwrap_or(0), Some(procTypeEnum), &paramTypes);
 
-                let function = procFunVal;
+                let function = Some(procFunVal);
 
                 //Creates the entrypoint at the procedure
                 let procEntry = self.context.append_basic_block(procFunVal, "procEntry");
                 procBuilder.position_at_end(procEntry);
-                println!("Created entry point");
+                verboseln!("Created entry point");
                 
 
                 let parmStmt = paramStmtBlock.clone();
                 // let checkParm = self.compileStmt(parmStmt.clone(), &procBuilder, &mut procLocTable, function);
                 match parmStmt{
-                    Stmt::VarDecl(varName, varType, lineNum) => {
+                    Stmt::VarDecl(varName, varType, lineNum, ..) => {
                         let params = procFunVal.get_params();
                         let paramValue = params[1];
                         let paramName = varName.clone();
@@ -1468,15 +1472,16 @@ impl<'ctx> Compiler<'ctx> {
 
                         //Adds location to the hash table
                         procLocTable.insert(paramName.clone(), paramPtr.clone());
+                        self.debugDeclare(&paramName, self.debugLlvmType(paramType), Some(1), paramPtr, &lineNum, &procBuilder, Some(procFunVal));
 
 
                     }
-                    Stmt::Block(stmtVec, lineNum) => {
+                    Stmt::Block(stmtVec, lineNum, ..) => {
                         let mut i = 0;
                         for paramStmt in stmtVec.clone(){
                             let curStmt = paramStmt.clone();
                             match curStmt{
-                                Stmt::VarDecl(varName, varType, lineNum) => {
+                                Stmt::VarDecl(varName, varType, lineNum, ..) => {
                                     let params = procFunVal.get_params();
                                     let paramValue = params[i];
                                     let paramName = varName.clone();
@@ -1498,6 +1503,7 @@ impl<'ctx> Compiler<'ctx> {
 
                                     //Adds location to the hash table
                                     procLocTable.insert(paramName.clone(), paramPtr.clone());
+                                    self.debugDeclare(&paramName, self.debugLlvmType(paramType), Some(i as u32 + 1), paramPtr, &lineNum, &procBuilder, Some(procFunVal));
 
 
                                 }
@@ -1519,16 +1525,16 @@ impl<'ctx> Compiler<'ctx> {
                 let header = headerBox.clone();
                 let mut procHeader = *header;
                 // Check if the variable is a Block and iterate through it
-                if let Stmt::Block(ref instrs, lineNum) = procHeader.clone() {
+                if let Stmt::Block(ref instrs, lineNum, ..) = procHeader.clone() {
                     for instr in instrs {
                         self.compileStmt(instr.clone(), &procBuilder, &mut procLocTable, function);
                     }
                 } else {
-                    println!("Problem with procedure AST: header must be a Block");
+                    verboseln!("Problem with procedure AST: header must be a Block");
                     panic!();
                 }
 
-                println!("procedure Header processed");
+                verboseln!("procedure Header processed");
 
                 let procBody = self.context.append_basic_block(procFunVal, "procBody");
 
@@ -1537,35 +1543,40 @@ impl<'ctx> Compiler<'ctx> {
 
                 procBuilder.position_at_end(procBody);
 
-                println!("Time to go through body");
+                verboseln!("Time to go through body");
                 //Goes through the body and adds each line to the module
                 let newBodyBox = bodyBox.clone();
                 let mut body = *newBodyBox;
 
                 // Check if the variable is a Block and iterate through it
-                if let Stmt::Block(ref instrs, lineNum) = body.clone() {
+                if let Stmt::Block(ref instrs, lineNum, ..) = body.clone() {
                     for instr in instrs {
-                        println!("Proc expressions");
+                        verboseln!("Proc expressions");
                         let good = self.compileStmt(instr.clone(), &procBuilder, &mut procLocTable, function);
                     }
                 } else {
-                    println!("Problem with proc AST: body must be a Block");
+                    verboseln!("Problem with proc AST: body must be a Block");
                     panic!();
                 }
                 
-                println!("Procedure created");
+                verboseln!("Procedure created");
+                self.procScopes.pop();
                 
                 return true;
              
             }
-            Stmt::StringLiteral(str, lineNum) => {
-                println!("StringLiteral Stmt, this should never happe");
+            Stmt::StringLiteral(str, lineNum, ..) => {
+                verboseln!("StringLiteral Stmt, this should never happe");
                 return true;
             }
-            Stmt::Return(valueExpr, lineNum) => {
+            Stmt::Return(valueExpr, lineNum, ..) => {
+                //A return in the main program ends it, its value is the exit status like in the interpreter
+                if function.map(|func| func.get_name().to_bytes() == b"main").unwrap_or(false) {
+                    return self.compileMainReturn(&valueExpr, builder, localTable);
+                }
                 let retValExpr = valueExpr.clone();
-                if let Expr::VarRef(varName) = retValExpr.clone(){
-                    println!("RETURN EXPRESSION");
+                if let Expr::VarRef(varName, ..) = retValExpr.clone(){
+                    verboseln!("RETURN EXPRESSION");
                     if varName.clone() == ""{
                         let _ = builder.build_return(None);
                         return true;
@@ -1573,6 +1584,12 @@ impl<'ctx> Compiler<'ctx> {
                         let exprCheck = self.compileExpr(&retValExpr.clone(), builder, localTable);
                         match exprCheck {
                             Ok(val) => {
+                                //The value is converted to the procedure's return type
+                                let retType = function.and_then(|func| func.get_type().get_return_type());
+                                let val = match retType {
+                                    Some(retType) => self.convertValue(val, retType, builder).unwrap_or_else(|err| panic!("Error returning a value: {}", err)),
+                                    None => val,
+                                };
                                 match val {
                                     BasicValueEnum::IntValue(int_val) => {
                                         let _ = builder.build_return(Some(&int_val));
@@ -1597,7 +1614,7 @@ impl<'ctx> Compiler<'ctx> {
                             }
                             Err(e) => {
                                 // Handle the error case
-                                println!("Failed get return value: {}", e);
+                                verboseln!("Failed get return value: {}", e);
                                 panic!();
                             }
                         }
@@ -1609,6 +1626,12 @@ impl<'ctx> Compiler<'ctx> {
                     let exprCheck = self.compileExpr(&retValExpr.clone(), builder, localTable);
                         match exprCheck {
                             Ok(val) => {
+                                //The value is converted to the procedure's return type
+                                let retType = function.and_then(|func| func.get_type().get_return_type());
+                                let val = match retType {
+                                    Some(retType) => self.convertValue(val, retType, builder).unwrap_or_else(|err| panic!("Error returning a value: {}", err)),
+                                    None => val,
+                                };
                                 match val {
                                     BasicValueEnum::IntValue(int_val) => {
                                         let _ = builder.build_return(Some(&int_val));
@@ -1633,17 +1656,29 @@ impl<'ctx> Compiler<'ctx> {
                             }
                             Err(e) => {
                                 // Handle the error case
-                                println!("Failed get return value: {}", e);
+                                verboseln!("Failed get return value: {}", e);
                                 panic!();
                             }
                         }
                 }
                 
             }
-            Stmt::Program(name, headerBox, bodyBox, lineNum) => {
-                println!("Program Stmt, this should never happen");
+            Stmt::Program(name, headerBox, bodyBox, lineNum, ..) => {
+                verboseln!("Program Stmt, this should never happen");
+                return true;
+            }
+            //The imported procedures and globals were already declared by declareImports
+            Stmt::Import(moduleName, lineNum, ..) => {
+                return true;
+            }
+            Stmt::ExternProcDecl(retType, procName, params, lineNum, ..) => {
+                self.declareExtern(&retType, &procName, &params);
                 return true;
             }
+            Stmt::Module(name, headerBox, lineNum, ..) => {
+                verboseln!("Module Stmt, this should never happen");
+                return false;
+            }
             
         }
         
@@ -1652,33 +1687,26 @@ impl<'ctx> Compiler<'ctx> {
         
     fn compileExpr(&mut self, expr: &Expr, builder: &Builder<'ctx>, localTable: &mut HashMap<String, PointerValue<'ctx>>) -> Result<BasicValueEnum<'ctx>, String> {
         match expr {
-            Expr::IntLiteral(value) => {
+            Expr::IntLiteral(value, ..) => {
                 let val = value.clone() as u64;
-                let intType = self.context.i32_type().clone();
+                let intType = self.intType().clone();
                 let intVal = intType.const_int(val, false);
                 return Ok(BasicValueEnum::IntValue(intVal));
             }
                 
-            Expr::FloatLiteral(value) => {
+            Expr::FloatLiteral(value, ..) => {
                 // let val = value.clone() as f32;
-                let floatType = self.context.f32_type().clone();
+                let floatType = self.floatType().clone();
                 let floatVal = floatType.const_float(value.clone().into());
                 return Ok(BasicValueEnum::FloatValue(floatVal.clone()));
             }
             
-            Expr::StringLiteral(string) => {
-                let stringBytes = string.as_bytes();
-    
-    
-                let arrayVal = self.context.const_string(stringBytes, false).clone();
-    
-    
-                // Wrap the array constant in a BasicValueEnum
-                let basicArrayVal = BasicValueEnum::ArrayValue(arrayVal);
-                return Ok(basicArrayVal.clone());
-    
+            Expr::StringLiteral(string, ..) => {
+                //The lexer keeps the decoded characters, they are stored once in the module after their lengths
+                let stringPtr = self.stringConstant(string);
+                return Ok(BasicValueEnum::PointerValue(stringPtr));
             }
-            Expr::IntArrayLiteral(size, values) => {
+            Expr::IntArrayLiteral(size, values, ..) => {
                 // let byte_vec = string.as_bytes().to_vec();
                 // let max_len = 65 as u32;
                 // let i8_type = self.context.i8_type(); // Define the element type (8-bit integer for characters)
@@ -1687,12 +1715,12 @@ impl<'ctx> Compiler<'ctx> {
                 
     
                 // return basic_value_enum;
-                println!("intarray NEEDS WRITTEN");
-                let i32_type = self.context.i32_type();
+                verboseln!("intarray NEEDS WRITTEN");
+                let i32_type = self.intType();
                 let intValue = i32_type.const_int(0, false);                
                 return Ok(BasicValueEnum::IntValue(intValue));
             }
-            Expr::BoolLiteral(boolVal) => {
+            Expr::BoolLiteral(boolVal, ..) => {
                 let boolType = self.context.custom_width_int_type(1).clone();
                 let trueVal = BasicValueEnum::IntValue(boolType.const_int(1, false));
                 let falseVal = BasicValueEnum::IntValue(boolType.const_int(0, false));
@@ -1706,12 +1734,12 @@ impl<'ctx> Compiler<'ctx> {
                 }
             }
             
-            Expr::VarRef(varName) => {
+            Expr::VarRef(varName, ..) => {
                 //Gets the type if defined in local scope
                 let checkLocVar = localTable.get(&varName.clone());
                 match checkLocVar{
                     Some(varPtr) => {
-                        println!("Loading local value {} at location {}", varName.clone(), varPtr.clone());
+                        verboseln!("Loading local value {} at location {}", varName.clone(), varPtr.clone());
                         let loadedVal = builder.build_load(varPtr.clone(), &varName.clone());
                         match loadedVal{
                             Ok(val) => {
@@ -1726,7 +1754,7 @@ impl<'ctx> Compiler<'ctx> {
                         let checkGlobVar = self.globalTable.get(&varName.clone());
                             match checkGlobVar{
                                 Some(varPtr) => {
-                                    println!("Loading local value {} at location {}", varName.clone(), varPtr.clone());
+                                    verboseln!("Loading local value {} at location {}", varName.clone(), varPtr.clone());
                                     
                                     let loadedVal = builder.build_load(varPtr.clone(), &varName.clone());
                                     match loadedVal{
@@ -1747,11 +1775,11 @@ impl<'ctx> Compiler<'ctx> {
                 }
                 
             }
-            Expr::ArrayRef(name, indexExpr) => {
-                println!("array reference");
+            Expr::ArrayRef(name, indexExpr, ..) => {
+                verboseln!("array reference");
                 let targName = name.clone();
                 let arrSize = 64 as u32;
-                let i32Type = self.context.i32_type().clone();
+                let i32Type = self.intType().clone();
                 let arrayType = i32Type.array_type(arrSize).clone();
                 
                 //Gets the value of the index expression
@@ -1762,7 +1790,7 @@ impl<'ctx> Compiler<'ctx> {
                         indexVal = val.clone();
                     }
                     Err(err) => {
-                        println!("{}", err.clone());
+                        verboseln!("{}", err.clone());
                         let errMsg = format!("Could error with index {}", err.clone());
                         panic!("{}", errMsg.clone());
                     }
@@ -1773,14 +1801,14 @@ impl<'ctx> Compiler<'ctx> {
                 let checkLocVar = localTable.get(&targName.clone());
                 match checkLocVar{
                     Some(ptr) => {
-                        println!("getting local array {} at location {}", targName.clone(), ptr.clone());
+                        verboseln!("getting local array {} at location {}", targName.clone(), ptr.clone());
                         arrayPtr = ptr.clone();
                     }
                     None => {
                         let checkGlobVar = self.globalTable.get(&targName.clone());
                         match checkGlobVar{
                             Some(ptr) => {
-                                println!("Gettting global array index  {} at location {}", targName.clone(), ptr.clone());
+                                verboseln!("Gettting global array index  {} at location {}", targName.clone(), ptr.clone());
                                 arrayPtr = ptr.clone();
                             }
                             None => {
@@ -1797,7 +1825,7 @@ impl<'ctx> Compiler<'ctx> {
                         indexInt = val.clone();
                     }
                     BasicValueEnum::FloatValue(val) => {
-                        let intType = self.context.i32_type().clone();
+                        let intType = self.intType().clone();
                         let intValue = builder.build_float_to_signed_int(val.clone(), intType, "float_to_int");
                         match intValue{
                             Ok(iVal) => {
@@ -1818,7 +1846,7 @@ impl<'ctx> Compiler<'ctx> {
             
                 // Get the pointer to the desired index
                 let variablePtr: PointerValue;
-                let intType = self.context.i32_type().clone();
+                let intType = self.intType().clone();
                 let zero = intType.const_int(0, false);
                 let indexList = [zero, indexInt];
                 let checkIndexPtr = unsafe { builder.build_gep(arrayPtr, &indexList, "arrayIndexLoad") };
@@ -1858,12 +1886,12 @@ impl<'ctx> Compiler<'ctx> {
                 
             }
     
-            Expr::ArthOp(op1, op, op2) => {
+            Expr::ArthOp(op1, op, op2, ..) => {
                 // let context = &mut self.context;
                 // let builder = &mut builder;
                 
-                let intType = self.context.i32_type().clone();
-                let floatType = self.context.f32_type().clone();
+                let intType = self.intType().clone();
+                let floatType = self.floatType().clone();
     
                 //First gets the values of both operands
                 let op1Res = self.compileExpr(&*op1.clone(), builder, localTable).clone();
@@ -1959,7 +1987,10 @@ impl<'ctx> Compiler<'ctx> {
                         else {
                             let op1Int = op1Val.into_int_value();
                             let op2Int = op2Val.into_int_value();
-                            let retOp = builder.build_int_add(op1Int.clone(), op2Int.clone(), "addInt");
+                            let retOp = match self.checkedArith {
+                                true => self.checkedIntOp(builder, &Operator::Add, op1Int, op2Int),
+                                false => builder.build_int_add(op1Int.clone(), op2Int.clone(), "addInt").map_err(|err| err.to_string()),
+                            };
                             match retOp{
                                 Ok(result) => {
                                     return Ok(BasicValueEnum::IntValue(result.clone()));
@@ -2030,7 +2061,10 @@ impl<'ctx> Compiler<'ctx> {
                         else {
                             let op1Int = op1Val.into_int_value();
                             let op2Int = op2Val.into_int_value();
-                            let retOp = builder.build_int_sub(op1Int.clone(), op2Int.clone(), "subInt");
+                            let retOp = match self.checkedArith {
+                                true => self.checkedIntOp(builder, &Operator::Sub, op1Int, op2Int),
+                                false => builder.build_int_sub(op1Int.clone(), op2Int.clone(), "subInt").map_err(|err| err.to_string()),
+                            };
                             match retOp{
                                 Ok(result) => {
                                     return Ok(BasicValueEnum::IntValue(result.clone()));
@@ -2101,7 +2135,10 @@ impl<'ctx> Compiler<'ctx> {
                         else {
                             let op1Int = op1Val.clone().into_int_value();
                             let op2Int = op2Val.clone().into_int_value();
-                            let retOp = builder.build_int_mul(op1Int.clone(), op2Int.clone(), "multiplyInt");
+                            let retOp = match self.checkedArith {
+                                true => self.checkedIntOp(builder, &Operator::Mul, op1Int, op2Int),
+                                false => builder.build_int_mul(op1Int.clone(), op2Int.clone(), "multiplyInt").map_err(|err| err.to_string()),
+                            };
                             match retOp{
                                 Ok(result) => {
                                     return Ok(BasicValueEnum::IntValue(result.clone()));
@@ -2172,7 +2209,10 @@ impl<'ctx> Compiler<'ctx> {
                         else {
                             let op1Int = op1Val.into_int_value();
                             let op2Int = op2Val.into_int_value();
-                            let retOp = builder.build_int_signed_div(op1Int.clone(), op2Int.clone(), "divideInt");
+                            let retOp = match self.checkedArith {
+                                true => self.checkedIntOp(builder, &Operator::Div, op1Int, op2Int),
+                                false => builder.build_int_signed_div(op1Int.clone(), op2Int.clone(), "divideInt").map_err(|err| err.to_string()),
+                            };
                             match retOp{
                                 Ok(result) => {
                                     return Ok(BasicValueEnum::IntValue(result.clone()));
@@ -2191,7 +2231,7 @@ impl<'ctx> Compiler<'ctx> {
                 }
             
             }
-            Expr::RelOp(op1, op, op2) => {   
+            Expr::RelOp(op1, op, op2, ..) => {   
                 
                 //First gets the values of both operands
                 let op1Res = self.compileExpr(&*op1.clone(), builder, localTable);
@@ -2238,7 +2278,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::FloatValue(val) => op1Float = val,
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Float = val;
@@ -2259,7 +2299,7 @@ impl<'ctx> Compiler<'ctx> {
                                 }
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Float = val;
@@ -2309,7 +2349,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::FloatValue(val) => op1Float = val,
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Float = val;
@@ -2330,7 +2370,7 @@ impl<'ctx> Compiler<'ctx> {
                                 }
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Float = val;
@@ -2380,7 +2420,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::FloatValue(val) => op1Float = val,
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Float = val;
@@ -2401,7 +2441,7 @@ impl<'ctx> Compiler<'ctx> {
                                 }
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Float = val;
@@ -2451,7 +2491,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::FloatValue(val) => op1Float = val,
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Float = val;
@@ -2472,7 +2512,7 @@ impl<'ctx> Compiler<'ctx> {
                                 }
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Float = val;
@@ -2522,7 +2562,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::FloatValue(val) => op1Float = val,
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Float = val;
@@ -2543,7 +2583,7 @@ impl<'ctx> Compiler<'ctx> {
                                 }
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Float = val;
@@ -2593,7 +2633,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::FloatValue(val) => op1Float = val,
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Float = val;
@@ -2614,7 +2654,7 @@ impl<'ctx> Compiler<'ctx> {
                                 }
                                 BasicValueEnum::IntValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_signed_int_to_float(val, self.context.f32_type(), "intToFloat");
+                                    let resConv = builder.build_signed_int_to_float(val, self.floatType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Float = val;
@@ -2662,7 +2702,7 @@ impl<'ctx> Compiler<'ctx> {
                 }
             
             }
-            Expr::LogOp(op1, op, op2) => {
+            Expr::LogOp(op1, op, op2, ..) => {
 
                 
                 //First gets the values of both operands
@@ -2710,7 +2750,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::IntValue(val) => op1Int = val,
                                 BasicValueEnum::FloatValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_float_to_signed_int(val, self.context.i32_type(), "intToFloat");
+                                    let resConv = builder.build_float_to_signed_int(val, self.intType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Int = val;
@@ -2729,7 +2769,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::IntValue(val) => op2Int = val,
                                 BasicValueEnum::FloatValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_float_to_signed_int(val, self.context.i32_type(), "intToFloat");
+                                    let resConv = builder.build_float_to_signed_int(val, self.intType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Int = val;
@@ -2780,7 +2820,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::IntValue(val) => op1Int = val,
                                 BasicValueEnum::FloatValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_float_to_signed_int(val, self.context.i32_type(), "intToFloat");
+                                    let resConv = builder.build_float_to_signed_int(val, self.intType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op1Int = val;
@@ -2799,7 +2839,7 @@ impl<'ctx> Compiler<'ctx> {
                                 BasicValueEnum::IntValue(val) => op2Int = val,
                                 BasicValueEnum::FloatValue(val) => {
                                     // Convert integer to float if necessary
-                                    let resConv = builder.build_float_to_signed_int(val, self.context.i32_type(), "intToFloat");
+                                    let resConv = builder.build_float_to_signed_int(val, self.intType(), "intToFloat");
                                     match resConv{
                                         Ok(val) => {
                                             op2Int = val;
@@ -2849,12 +2889,31 @@ impl<'ctx> Compiler<'ctx> {
                 
             }
         
-            Expr::ProcRef(procName, params) => {
+            Expr::ProcRef(procName, params, ..) => {
                 // self.scope += 1;
                 
                 //Get the function
                 let mut function: FunctionValue;
-                let functionCheck = self.module.get_function(&procName.clone());
+                //The innermost procedure with the name, then the imported and external procedures
+                let mut functionCheck = self.procScopes.iter().rev().find_map(|scope| scope.get(procName).cloned());
+                if functionCheck.is_none() {
+                    functionCheck = self.module.get_function(&procName.clone());
+                }
+                //A builtin is declared under the name funcLib exports it as, a procedure of the program with the same name wins
+                if functionCheck.is_none() {
+                    if let Some(builtin) = findBuiltin(procName) {
+                        functionCheck = self.module.get_function(&builtin.symbolFor(self.numberWidth));
+                    }
+                }
+                //funcLib is told the line first, so an error in the builtin reports it like the interpreter does
+                let callsBuiltin = match (functionCheck, findBuiltin(procName)) {
+                    (Some(fun), Some(builtin)) => fun.get_name().to_bytes() == builtin.symbolFor(self.numberWidth).as_bytes(),
+                    _ => false,
+                };
+                if callsBuiltin {
+                    let line = self.context.i32_type().const_int(self.curLine.parse::<u64>().unwrap_or(0), false);
+                    let _ = builder.build_call(self.lineHook(), &[line.into()], "setLine");
+                }
                 match functionCheck{
                     Some(fun) => {
                         function = fun.clone();
@@ -2882,6 +2941,13 @@ impl<'ctx> Compiler<'ctx> {
                     }
                 }
 
+                //The value is converted to the parameter's type, like it is for an assignment
+                for (i, compiledParam) in compiledParams.iter_mut().enumerate() {
+                    if let Some(param) = function.get_nth_param(i as u32) {
+                        *compiledParam = self.convertValue(*compiledParam, param.get_type(), builder).map_err(|err| format!("Error passing a value to {}: {}", procName.clone(), err))?;
+                    }
+                }
+
                 //COnvert params to correct type
                 let metadata_values: Vec<BasicMetadataValueEnum> = compiledParams.into_iter()
                     .map(|val| val.into())
@@ -2911,65 +2977,544 @@ impl<'ctx> Compiler<'ctx> {
     
     }
 
-    fn definePutInt(&mut self) {
-        let intType = self.context.i32_type();
-        let retType = self.context.bool_type();
-        let paramTypes = vec![BasicMetadataTypeEnum::from(intType)];
-        let parmVals = paramTypes.as_slice();
-        let printFnType = retType.fn_type(parmVals, false);
-        let putInt = self.module.add_function("putinteger", printFnType, None);        
+    //Declares the runtime functions of the builtins (funcLib), they take and return C types like an external procedure
+    //With wide numbers the builtins that take or return a number are the funcLib functions ending in 64
+    fn defineBuiltins(&mut self) {
+        for builtin in BUILTINS {
+            let paramTypes: Vec<BasicMetadataTypeEnum> = builtin.params.iter().map(|(paramName, paramType)| self.externType(paramType).into()).collect();
+            let funcType = self.externType(&builtin.retType).fn_type(&paramTypes[..], false);
+            self.module.add_function(&builtin.symbolFor(self.numberWidth), funcType, None);
+        }
+    }
+
+}
+
+
+///////////////////////// MODULE SECTION /////////////////////////
+//Each module of the program is compiled on its own into a separate llvm module and linked into the program's
+//The importing unit only gets declarations (no bodies or initializers) for what it imports, the linker resolves them
+
+impl<'ctx> Compiler<'ctx> {
+    //Generates the llvm module of an imported module, it has the procedures and globals of the module but no main
+    pub fn compileModule(&mut self) -> Result<&Module<'ctx>, String> {
+        match self.programAst.clone() {
+            Stmt::Module(moduleName, headerBox, lineNum, ..) => {
+                //Adds the built ins so the module's procedures can call them
+                self.defineBuiltins();
+
+                let moduleBuilder = self.context.create_builder();
+                let mut moduleLocalTable: HashMap<String, PointerValue<'ctx>> = HashMap::new();
+                if let Stmt::Block(ref instrs, lineNum, ..) = *headerBox {
+                    for instr in instrs {
+                        let good = self.compileStmt(instr.clone(), &moduleBuilder, &mut moduleLocalTable, None);
+                        if !good {
+                            let errMsg = format!("Error compiling module {} on line {}", moduleName.clone(), instr.lineNum());
+                            return Err(errMsg);
+                        }
+                    }
+                } else {
+                    let errMsg = format!("Problem with AST: header of module {} must be a Block", moduleName.clone());
+                    return Err(errMsg);
+                }
+            }
+            _ => {
+                let errMsg = format!("ModuleAst must be a Module Stmt");
+                return Err(errMsg);
+            }
+        }
+
+        if let Some(debug) = &self.debugInfo {
+            debug.builder.finalize();
+        }
+
+        if let Err(errMsg) = self.module.verify() {
+            return Err(format!("The generated module of {} is not valid: {}", self.name, errMsg.to_string()));
+        }
+
+        return Ok(&self.module);
+    }
+
+    //Declares the procedures and globals a unit imports, has to be called before compileProgram or compileModule
+    pub fn declareImports(&mut self, imports: &Vec<Stmt>) -> Result<(), String> {
+        for import in imports {
+            match import {
+                Stmt::GlobVarDecl(varName, varType, lineNum, ..) => {
+                    let globVar;
+                    match varType {
+                        VarType::IntArray(size) => {
+                            let arrayType = self.intType().array_type(*size as u32);
+                            globVar = self.module.add_global(arrayType, None, varName);
+                        }
+                        _ => {
+                            globVar = self.module.add_global(self.importType(varType), None, varName);
+                        }
+                    }
+                    self.globalTable.insert(varName.clone(), globVar.as_pointer_value());
+                }
+                Stmt::ExternProcDecl(retType, procName, params, lineNum, ..) => {
+                    self.declareExtern(retType, procName, params);
+                }
+                Stmt::ProcDecl(retType, procName, params, header, body, lineNum, ..) => {
+                    let mut paramVarTypes: Vec<VarType> = Vec::new();
+                    match *params.clone() {
+                        Stmt::Block(params, lineNum, ..) => {
+                            for param in params {
+                                if let Stmt::VarDecl(varName, varType, lineNum, ..) = param {
+                                    paramVarTypes.push(varType);
+                                }
+                            }
+                        }
+                        Stmt::VarDecl(varName, varType, lineNum, ..) => {
+                            paramVarTypes.push(varType);
+                        }
+                        _ => {}
+                    }
+                    //An array isn't passed the same way on both sides of a module yet, so it is refused instead of passed wrong
+                    let crossesAsScalar = |varType: &VarType| !matches!(varType, VarType::IntArray(_));
+                    if !crossesAsScalar(retType) || !paramVarTypes.iter().all(crossesAsScalar) {
+                        let errMsg = format!("Procedure {} can't be imported, only integer, float, bool and string parameters and return values can be passed between modules", procName);
+                        return Err(errMsg);
+                    }
+                    let paramTypes: Vec<BasicMetadataTypeEnum> = paramVarTypes.iter().map(|varType| self.importType(varType).into()).collect();
+                    let funcType = self.importType(retType).fn_type(&paramTypes[..], false);
+                    self.module.add_function(procName, funcType, None);
+                }
+                _ => {
+                    let errMsg = format!("Only procedures and globals can be imported, got {}", import.lineNum());
+                    return Err(errMsg);
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    //The llvm type a procedure or scalar global of an imported module has, the same as ProcDecl uses
+    //declareImports refuses procedures with an array, the type here for one is only what ProcDecl gives
+    fn importType(&self, varType: &VarType) -> BasicTypeEnum<'ctx> {
+        match varType {
+            VarType::Bool => {
+                return self.context.bool_type().as_basic_type_enum();
+            }
+            VarType::Float => {
+                return self.floatType().as_basic_type_enum();
+            }
+            VarType::Int => {
+                return self.intType().as_basic_type_enum();
+            }
+            VarType::IntArray(size) => {
+                return self.intType().as_basic_type_enum();
+            }
+            VarType::Str => {
+                return self.stringType().as_basic_type_enum();
+            }
+        }
+    }
+}
+
+
+///////////////////////// EXTERNAL PROCEDURE SECTION /////////////////////////
+//An external procedure is a C function the program calls, it only gets a declaration with the C calling convention
+//integer, float and bool are passed as a C int, float and bool, a string is passed as a char* to its bytes, which end with a NUL
+//C can't make a string value with its length header, so an external procedure can't return a string
+//With --wide-numbers an integer is passed as an int64_t (long on Linux) and a float as a double
+
+impl<'ctx> Compiler<'ctx> {
+    //Declares the C function, the linker finds it in libc, libm or any library given with -l
+    fn declareExtern(&mut self, retType: &VarType, procName: &String, params: &Stmt) -> FunctionValue<'ctx> {
+        //The same C function can be declared by the program and by a module it imports
+        if let Some(existing) = self.module.get_function(procName) {
+            return existing;
+        }
+        let mut paramTypes: Vec<BasicMetadataTypeEnum> = Vec::new();
+        if let Stmt::Block(paramStmts, lineNum, ..) = params {
+            for param in paramStmts {
+                if let Stmt::VarDecl(paramName, paramType, lineNum, ..) = param {
+                    paramTypes.push(self.externType(paramType).into());
+                }
+            }
+        }
+        let funcType = self.externType(retType).fn_type(&paramTypes[..], false);
+        let externFunc = self.module.add_function(procName, funcType, Some(Linkage::External));
+        //0 is the C calling convention
+        externFunc.set_call_conventions(0);
+        return externFunc;
+    }
+
+    //The llvm type a value has when it is passed to or returned from C, the same as between procedures
+    fn externType(&self, varType: &VarType) -> BasicTypeEnum<'ctx> {
+        return self.importType(varType);
+    }
+}
+
+
+///////////////////////// RUNTIME SECTION /////////////////////////
+//The generated main sets up funcLib first and returns the program's status, after writing the output funcLib buffered
+//A return with an integer in the main program exits with it (cut to the 32 bits of a status), a bool exits with 1 or 0
+//Any other return, and falling off the end, exits with 0. Runtime errors exit from funcLib with the --error-exit-code
+
+impl<'ctx> Compiler<'ctx> {
+    //Declares rt_setup(int errorExitCode, int badInput)
+    fn setupHook(&self) -> FunctionValue<'ctx> {
+        if let Some(existing) = self.module.get_function(SETUP_HOOK) {
+            return existing;
+        }
+        let i32Type = self.context.i32_type();
+        let funcType = self.context.bool_type().fn_type(&[i32Type.into(), i32Type.into()], false);
+        return self.module.add_function(SETUP_HOOK, funcType, None);
     }
-    fn definePutBool(&mut self) {
-        let boolType = self.context.bool_type();
-        let retType = self.context.bool_type();
-        let paramTypes = vec![BasicMetadataTypeEnum::from(boolType)];
-        let parmVals = paramTypes.as_slice();
-        let printFnType = retType.fn_type(parmVals, false);
-        let putInt = self.module.add_function("putbool", printFnType, None);        
+
+    //Declares rt_flush()
+    fn flushHook(&self) -> FunctionValue<'ctx> {
+        if let Some(existing) = self.module.get_function(FLUSH_HOOK) {
+            return existing;
+        }
+        let funcType = self.context.bool_type().fn_type(&[], false);
+        return self.module.add_function(FLUSH_HOOK, funcType, None);
     }
-    fn definePutFloat(&mut self) {
-        let floatType = self.context.f32_type();
-        let retType = self.context.bool_type();
-        let paramTypes = vec![BasicMetadataTypeEnum::from(floatType)];
-        let parmVals = paramTypes.as_slice();
-        let printFnType = retType.fn_type(parmVals, false);
-        let putInt = self.module.add_function("putfloat", printFnType, None);        
+
+    //Declares rt_setline(int line)
+    fn lineHook(&self) -> FunctionValue<'ctx> {
+        if let Some(existing) = self.module.get_function(LINE_HOOK) {
+            return existing;
+        }
+        let funcType = self.context.bool_type().fn_type(&[self.context.i32_type().into()], false);
+        return self.module.add_function(LINE_HOOK, funcType, None);
     }
-    fn definePutStr(&mut self) {
-        let i8_type = self.context.i8_type();
-        let array_type = i8_type.array_type(65); // Assuming the array size is 65
-        let string_type = array_type.ptr_type(AddressSpace::default());
-        let retType = self.context.bool_type();
-        let paramTypes = vec![BasicMetadataTypeEnum::from(string_type)];
-        let parmVals = paramTypes.as_slice();
-        let printFnType = retType.fn_type(parmVals, false);
-        let putInt = self.module.add_function("putfloat", printFnType, None);
+
+    //Returns from main with the status of the return value
+    fn compileMainReturn(&mut self, valueExpr: &Expr, builder: &Builder<'ctx>, localTable: &mut HashMap<String, PointerValue<'ctx>>) -> bool {
+        let i32Type = self.context.i32_type();
+        let mut status = i32Type.const_zero();
+        let isEmpty = matches!(valueExpr, Expr::VarRef(varName, ..) if varName == "");
+        if !isEmpty {
+            match self.compileExpr(valueExpr, builder, localTable) {
+                Ok(BasicValueEnum::IntValue(intVal)) => {
+                    //A bool is 1 bit and is zero extended, an integer is cut to 32 bits
+                    let isBool = intVal.get_type().get_bit_width() == 1;
+                    match builder.build_int_cast_sign_flag(intVal, i32Type, !isBool, "exitStatus") {
+                        Ok(converted) => {
+                            status = converted;
+                        }
+                        Err(err) => {
+                            verboseln!("Error converting the exit status: {}", err);
+                            return false;
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    verboseln!("Failed get return value: {}", err);
+                    return false;
+                }
+            }
+        }
+        let flushHook = self.flushHook();
+        let _ = builder.build_call(flushHook, &[], "flushOutput");
+        let _ = builder.build_return(Some(&status));
+        return true;
     }
+}
 
 
-    fn defineGetInt(&mut self) {
-        let intType = self.context.i32_type();
-        // let retType = self.context.bool_type();
-        let paramTypes = vec![];
-        let parmVals = paramTypes.as_slice();
-        let getIntType = intType.fn_type(parmVals, false);
-        let putInt = self.module.add_function("getinteger", getIntType, None);
+///////////////////////// CHECKED ARITHMETIC SECTION /////////////////////////
+//With --checked-arith integer +, -, * and / check their result instead of wrapping (or trapping on a division by zero)
+//+, - and * use the llvm.s*.with.overflow intrinsics, / checks for a 0 divisor and for the smallest integer divided by -1
+//A failed check calls rt_arithpanic in funcLib with the operator, the operands and the .src line, it prints them and exits
+//Float arithmetic isn't checked, it gives inf and NaN like IEEE 754 says
+
+impl<'ctx> Compiler<'ctx> {
+    //Does one checked integer operation, the builder is left in the block after the check
+    fn checkedIntOp(&mut self, builder: &Builder<'ctx>, op: &Operator, left: IntValue<'ctx>, right: IntValue<'ctx>) -> Result<IntValue<'ctx>, String> {
+        let function = match builder.get_insert_block().and_then(|block| block.get_parent()) {
+            Some(function) => function,
+            None => {
+                return Err("Checked arithmetic outside of a procedure".to_string());
+            }
+        };
+        let intType = left.get_type();
+
+        //The result (for + - *) and the condition that makes the operation fail
+        let mut result: Option<IntValue<'ctx>> = None;
+        let failed: IntValue<'ctx>;
+        match op {
+            Operator::Add | Operator::Sub | Operator::Mul => {
+                let intrinsic = match op {
+                    Operator::Add => "sadd",
+                    Operator::Sub => "ssub",
+                    _ => "smul",
+                };
+                let withOverflow = self.overflowIntrinsic(intrinsic, intType);
+                let call = builder.build_call(withOverflow, &[left.into(), right.into()], "checkedOp").map_err(|err| err.to_string())?;
+                let pair = match call.try_as_basic_value().left() {
+                    Some(pair) => pair.into_struct_value(),
+                    None => {
+                        return Err(format!("llvm.{}.with.overflow returned nothing", intrinsic));
+                    }
+                };
+                let value = builder.build_extract_value(pair, 0, "checkedValue").map_err(|err| err.to_string())?;
+                let overflowed = builder.build_extract_value(pair, 1, "overflowed").map_err(|err| err.to_string())?;
+                result = Some(value.into_int_value());
+                failed = overflowed.into_int_value();
+            }
+            Operator::Div => {
+                let bits = intType.get_bit_width();
+                let smallest = intType.const_int(1u64 << (bits - 1), false);
+                let isZero = builder.build_int_compare(IntPredicate::EQ, right, intType.const_zero(), "isZero").map_err(|err| err.to_string())?;
+                let isSmallest = builder.build_int_compare(IntPredicate::EQ, left, smallest, "isSmallest").map_err(|err| err.to_string())?;
+                let isMinusOne = builder.build_int_compare(IntPredicate::EQ, right, intType.const_all_ones(), "isMinusOne").map_err(|err| err.to_string())?;
+                let overflows = builder.build_and(isSmallest, isMinusOne, "divOverflows").map_err(|err| err.to_string())?;
+                failed = builder.build_or(isZero, overflows, "divFails").map_err(|err| err.to_string())?;
+            }
+            _ => {
+                return Err(format!("Improper operator {} for arithmetic operation", op));
+            }
+        }
+
+        //The failed branch reports the operation and never comes back
+        let failBlock = self.context.append_basic_block(function, "arithFailed");
+        let okBlock = self.context.append_basic_block(function, "arithOk");
+        builder.build_conditional_branch(failed, failBlock, okBlock).map_err(|err| err.to_string())?;
+        builder.position_at_end(failBlock);
+        let i64Type = self.context.i64_type();
+        let opText = builder.build_global_string_ptr(&op.to_string(), "arithOp").map_err(|err| err.to_string())?;
+        let leftWide = builder.build_int_s_extend_or_bit_cast(left, i64Type, "leftWide").map_err(|err| err.to_string())?;
+        let rightWide = builder.build_int_s_extend_or_bit_cast(right, i64Type, "rightWide").map_err(|err| err.to_string())?;
+        let line = self.context.i32_type().const_int(self.curLine.parse::<u64>().unwrap_or(0), false);
+        let panicHook = self.arithPanicHook();
+        builder.build_call(panicHook, &[opText.as_pointer_value().into(), leftWide.into(), rightWide.into(), line.into()], "arithPanic").map_err(|err| err.to_string())?;
+        builder.build_unreachable().map_err(|err| err.to_string())?;
+
+        builder.position_at_end(okBlock);
+        match result {
+            Some(value) => {
+                return Ok(value);
+            }
+            None => {
+                return builder.build_int_signed_div(left, right, "divideInt").map_err(|err| err.to_string());
+            }
+        }
     }
 
+    //Declares llvm.<name>.with.overflow for the integer type, it returns the result and an i1 that is set on overflow
+    fn overflowIntrinsic(&self, name: &str, intType: IntType<'ctx>) -> FunctionValue<'ctx> {
+        let intrinsicName = format!("llvm.{}.with.overflow.i{}", name, intType.get_bit_width());
+        if let Some(existing) = self.module.get_function(&intrinsicName) {
+            return existing;
+        }
+        let retType = self.context.struct_type(&[intType.into(), self.context.bool_type().into()], false);
+        let funcType = retType.fn_type(&[intType.into(), intType.into()], false);
+        return self.module.add_function(&intrinsicName, funcType, None);
+    }
 
-    fn defineGetFloat(&mut self) {
-        let intType = self.context.f32_type();
-        // let retType = self.context.bool_type();
-        let paramTypes = vec![];
-        let parmVals = paramTypes.as_slice();
-        let getIntType = intType.fn_type(parmVals, false);
-        let putInt = self.module.add_function("getfloat", getIntType, None);
+    //Declares the funcLib hook a failed check calls, rt_arithpanic(char *op, int64_t left, int64_t right, int line)
+    fn arithPanicHook(&self) -> FunctionValue<'ctx> {
+        if let Some(existing) = self.module.get_function(ARITH_PANIC_HOOK) {
+            return existing;
+        }
+        let paramTypes: Vec<BasicMetadataTypeEnum> = vec![
+            self.context.i8_type().ptr_type(AddressSpace::default()).into(),
+            self.context.i64_type().into(),
+            self.context.i64_type().into(),
+            self.context.i32_type().into(),
+        ];
+        let funcType = self.context.bool_type().fn_type(&paramTypes[..], false);
+        return self.module.add_function(ARITH_PANIC_HOOK, funcType, None);
     }
+}
+
 
+///////////////////////// DEBUG INFO SECTION /////////////////////////
+//This section adds the DWARF debug info for -g so that gdb and lldb can set breakpoints on .src lines and print variables
+//It makes a compile unit for the .src file, a subprogram for main and each procedure, a line location for each statement
+//and a variable descriptor for each local and parameter. Everything here does nothing unless enableDebugInfo was called
+
+//The DWARF base type encodings
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_SIGNED_CHAR: u32 = 0x06;
+
+//The debug info builder and the compile unit of the module
+pub struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,    //Builds the debug metadata
+    compileUnit: DICompileUnit<'ctx>,   //The compile unit for the .src file
+    file: DIFile<'ctx>,                 //The .src file, every location points into it
 }
 
-#[no_mangle]
-pub extern "C" fn putinteger(val: i32) -> bool {
-    println!("{}", val);
-    return true;
+impl<'ctx> Compiler<'ctx> {
+    //Turns on the debug info (-g), has to be called before compileProgram
+    pub fn enableDebugInfo(&mut self, srcPath: &str) {
+        //The directory is absolute so the debugger finds the source from anywhere
+        let path = fs::canonicalize(srcPath).unwrap_or(Path::new(srcPath).to_path_buf());
+        let fileName = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or(srcPath.to_string());
+        let directory = path.parent().map(|dir| dir.display().to_string()).unwrap_or(".".to_string());
+        self.module.set_source_file_name(&fileName);
+
+        let (builder, compileUnit) = self.module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &fileName,
+            &directory,
+            "compiler",
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+        let debugVersion = self.context.i32_type().const_int(3, false);
+        self.module.add_basic_value_flag("Debug Info Version", FlagBehavior::Warning, debugVersion);
+        let dwarfVersion = self.context.i32_type().const_int(4, false);
+        self.module.add_basic_value_flag("Dwarf Version", FlagBehavior::Warning, dwarfVersion);
+
+        let file = compileUnit.get_file();
+        self.debugInfo = Some(DebugInfo {
+            builder,
+            compileUnit,
+            file,
+        });
+    }
+
+    //Creates the subprogram of a function (main or a procedure) and attaches it to the function
+    fn debugFunction(&self, function: FunctionValue<'ctx>, name: &str, lineNum: u32, retType: Option<BasicTypeEnum<'ctx>>, paramTypes: &Vec<BasicTypeEnum<'ctx>>) {
+        let debug = match &self.debugInfo {
+            Some(debug) => debug,
+            None => return,
+        };
+        //A type with no debug type (a string or an array) is left out
+        let debugRetType = retType.and_then(|retType| self.debugLlvmType(retType));
+        let debugParamTypes: Vec<DIType<'ctx>> = paramTypes.iter().filter_map(|paramType| self.debugLlvmType(*paramType)).collect();
+        let subroutineType = debug.builder.create_subroutine_type(debug.file, debugRetType, &debugParamTypes, DIFlags::PUBLIC);
+        let subprogram = debug.builder.create_function(
+            debug.compileUnit.as_debug_info_scope(),
+            name,
+            None,
+            debug.file,
+            lineNum,
+            subroutineType,
+            false,
+            true,
+            lineNum,
+            DIFlags::PUBLIC,
+            false,
+        );
+        function.set_subprogram(subprogram);
+    }
+
+    //Points the builder's debug location at a statement's line in the function
+    fn debugLocation(&self, lineNum: &str, builder: &Builder<'ctx>, function: Option<FunctionValue<'ctx>>) {
+        let debug = match &self.debugInfo {
+            Some(debug) => debug,
+            None => return,
+        };
+        let subprogram = match function.and_then(|function| function.get_subprogram()) {
+            Some(subprogram) => subprogram,
+            None => return,
+        };
+        let line = lineNum.parse::<u32>().unwrap_or(0);
+        let location = debug.builder.create_debug_location(self.context, line, 0, subprogram.as_debug_info_scope(), None);
+        builder.set_current_debug_location(location);
+    }
+
+    //Describes a local variable or a parameter (argNum, counted from 1) that lives in an alloca
+    //The llvm.dbg.declare goes at the end of the builder's current block, right after the alloca and its first store
+    fn debugDeclare(&self, varName: &str, varType: Option<DIType<'ctx>>, argNum: Option<u32>, ptr: PointerValue<'ctx>, lineNum: &str, builder: &Builder<'ctx>, function: Option<FunctionValue<'ctx>>) {
+        let debug = match &self.debugInfo {
+            Some(debug) => debug,
+            None => return,
+        };
+        let (subprogram, block, varType) = match (function.and_then(|function| function.get_subprogram()), builder.get_insert_block(), varType) {
+            (Some(subprogram), Some(block), Some(varType)) => (subprogram, block, varType),
+            _ => return,
+        };
+        let line = lineNum.parse::<u32>().unwrap_or(0);
+        let scope = subprogram.as_debug_info_scope();
+        let variable = match argNum {
+            Some(argNum) => debug.builder.create_parameter_variable(scope, varName, argNum, debug.file, line, varType, true, DIFlags::ZERO),
+            None => debug.builder.create_auto_variable(scope, varName, debug.file, line, varType, true, DIFlags::ZERO, 0),
+        };
+        let location = debug.builder.create_debug_location(self.context, line, 0, scope, None);
+        debug.builder.insert_declare_at_end(ptr, Some(variable), None, location, block);
+    }
+
+    //The debug type of a declared variable, strings are a char* and arrays keep their size
+    fn debugType(&self, varType: &VarType) -> Option<DIType<'ctx>> {
+        match varType {
+            VarType::Int => {
+                return self.debugBasicType("integer", self.numberBits(), DW_ATE_SIGNED);
+            }
+            VarType::Bool => {
+                return self.debugBasicType("bool", 8, DW_ATE_BOOLEAN);
+            }
+            VarType::Float => {
+                return self.debugBasicType("float", self.numberBits(), DW_ATE_FLOAT);
+            }
+            VarType::Str => {
+                return self.debugStringType();
+            }
+            VarType::IntArray(size) => {
+                let intType = self.debugBasicType("integer", self.numberBits(), DW_ATE_SIGNED)?;
+                return self.debugArrayType(intType, self.numberBits(), *size as i64);
+            }
+        }
+    }
+
+    //The debug type of an llvm type, used for the parameters and return values (the code generator passes those as scalars)
+    fn debugLlvmType(&self, llvmType: BasicTypeEnum<'ctx>) -> Option<DIType<'ctx>> {
+        match llvmType {
+            BasicTypeEnum::IntType(intType) => match intType.get_bit_width() {
+                1 => {
+                    return self.debugBasicType("bool", 8, DW_ATE_BOOLEAN);
+                }
+                8 => {
+                    return self.debugBasicType("char", 8, DW_ATE_SIGNED_CHAR);
+                }
+                bits => {
+                    return self.debugBasicType("integer", bits as u64, DW_ATE_SIGNED);
+                }
+            },
+            BasicTypeEnum::FloatType(_) => {
+                return self.debugBasicType("float", self.numberBits(), DW_ATE_FLOAT);
+            }
+            BasicTypeEnum::PointerType(_) => {
+                return self.debugStringType();
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+
+    fn debugBasicType(&self, name: &str, sizeInBits: u64, encoding: u32) -> Option<DIType<'ctx>> {
+        let debug = self.debugInfo.as_ref()?;
+        match debug.builder.create_basic_type(name, sizeInBits, encoding, DIFlags::PUBLIC) {
+            Ok(basicType) => {
+                return Some(basicType.as_type());
+            }
+            Err(err) => {
+                verboseln!("Error creating the debug type {}: {}", name, err);
+                return None;
+            }
+        }
+    }
+
+    fn debugStringType(&self) -> Option<DIType<'ctx>> {
+        let debug = self.debugInfo.as_ref()?;
+        let charType = self.debugBasicType("char", 8, DW_ATE_SIGNED_CHAR)?;
+        let stringType = debug.builder.create_pointer_type("string", charType, 64, 64, AddressSpace::default());
+        return Some(stringType.as_type());
+    }
+
+    fn debugArrayType(&self, elemType: DIType<'ctx>, elemBits: u64, len: i64) -> Option<DIType<'ctx>> {
+        let debug = self.debugInfo.as_ref()?;
+        let arrayType = debug.builder.create_array_type(elemType, elemBits * len as u64, elemBits as u32, &[0..len]);
+        return Some(arrayType.as_type());
+    }
 }
+
+///////////////////////// /DEBUG INFO SECTION /////////////////////////
@@ -0,0 +1,464 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::{
+        models::lexer::{Lexer, Token},
+        tokenTypeEnum,
+    },
+    std::fmt,
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// CST SECTION /////////////////////////
+//This section contains the concrete syntax tree, a tree over every token of the file including the whitespace and comments
+//Nothing is dropped, so the text of the tree is the text of the file, which is what the formatter and other tools that edit source need
+//The tree is built from the raw tokens of the lexer in trivia mode, it never fails, anything unexpected is put in an Error node
+
+//The kinds of nodes in the tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Root,               //The whole file
+    ProgramHeader,      //program name is
+    Declaration,        //[global] variable name : type;
+    Procedure,          //A procedure declaration, from its header to end procedure;
+    ProcedureHeader,    //procedure name : type(params)
+    Parameters,         //The parameters between the parentheses of a procedure header
+    Body,               //begin, the statements and the end that closes them
+    Assignment,         //name := expression; (also a procedure call on its own)
+    If,                 //if (condition) then statements [else statements] end if;
+    For,                //for (assignment; condition) statements end for;
+    Return,             //return expression;
+    Error,              //Tokens that don't fit where they are
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+//A token in the tree, with its exact text from the file
+#[derive(Clone, PartialEq)]
+pub struct CstToken {
+    pub token: Token,       //The lexer token (type, line, column, span)
+    pub text: String,       //The text of the token in the file
+}
+
+impl CstToken {
+    //Whitespace and comments, the tokens the parser never sees
+    pub fn isTrivia(&self) -> bool {
+        return self.token.tt == tokenTypeEnum::WHITESPACE || self.token.tt == tokenTypeEnum::COMMENT;
+    }
+}
+
+//A child of a node, either another node or a token
+#[derive(Clone, PartialEq)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(CstToken),
+}
+
+//A node in the tree
+#[derive(Clone, PartialEq)]
+pub struct CstNode {
+    pub kind: NodeKind,
+    pub children: Vec<CstElement>,
+}
+
+impl CstNode {
+    fn new(kind: NodeKind) -> CstNode {
+        CstNode {
+            kind,
+            children: Vec::new(),
+        }
+    }
+
+    //The text of the node, exactly as it is in the file
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for token in self.tokens() {
+            text.push_str(&token.text);
+        }
+        return text;
+    }
+
+    //Every token under the node in order, including the trivia
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        let mut tokens: Vec<&CstToken> = Vec::new();
+        for child in self.children.iter() {
+            match child {
+                CstElement::Node(node) => tokens.extend(node.tokens()),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+        return tokens;
+    }
+
+    //The nodes of a kind under this node (and this node if it matches), outer nodes first
+    pub fn find(&self, kind: &NodeKind) -> Vec<&CstNode> {
+        let mut found: Vec<&CstNode> = Vec::new();
+        if &self.kind == kind {
+            found.push(self);
+        }
+        for child in self.children.iter() {
+            if let CstElement::Node(node) = child {
+                found.extend(node.find(kind));
+            }
+        }
+        return found;
+    }
+
+    //Prints the tree (used for debugging)
+    pub fn display(&self, indent: usize) {
+        let indentation = " ".repeat(indent);
+        println!("{}{}", indentation, self.kind);
+        for child in self.children.iter() {
+            match child {
+                CstElement::Node(node) => node.display(indent + 2),
+                CstElement::Token(token) => println!("{}  {} {:?}", indentation, token.token.tt, token.text),
+            }
+        }
+    }
+}
+
+//The concrete syntax tree of a file
+pub struct Cst {
+    pub root: CstNode,
+}
+
+impl Cst {
+    //Lexes the source in trivia mode and builds the tree
+    pub fn parse(fileName: &str, source: &str) -> Cst {
+        let mut myLexer = Lexer::newFromString(fileName, source.to_string());
+        myLexer.keepTrivia = true;
+        myLexer.scanThrough();
+        return Cst::new(&myLexer.rawTokens, source);
+    }
+
+    //Builds the tree from the raw tokens of a lexer that kept its trivia
+    pub fn new(rawTokens: &Vec<Token>, source: &str) -> Cst {
+        let sourceChars: Vec<char> = source.chars().collect();
+        let mut tokens: Vec<CstToken> = Vec::new();
+        for token in rawTokens.iter() {
+            let start = token.start.min(sourceChars.len());
+            let end = token.end.min(sourceChars.len()).max(start);
+            tokens.push(CstToken {
+                token: token.clone(),
+                text: sourceChars[start..end].iter().collect(),
+            });
+        }
+        let mut builder = CstBuilder {
+            tokens,
+            pos: 0,
+        };
+        return Cst {
+            root: builder.buildRoot(),
+        };
+    }
+
+    //The text of the tree, the same as the source it was built from
+    pub fn text(&self) -> String {
+        return self.root.text();
+    }
+
+    //Every token in order, including the trivia
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        return self.root.tokens();
+    }
+
+    //The comments in order
+    pub fn comments(&self) -> Vec<&CstToken> {
+        return self.tokens().into_iter().filter(|token| token.token.tt == tokenTypeEnum::COMMENT).collect();
+    }
+
+    //The token that starts at a character index of the file
+    pub fn tokenAt(&self, start: usize) -> Option<&CstToken> {
+        return self.tokens().into_iter().find(|token| token.token.start == start && !token.text.is_empty());
+    }
+}
+
+//Builds the tree, a cursor over the raw tokens
+struct CstBuilder {
+    tokens: Vec<CstToken>,
+    pos: usize,
+}
+
+impl CstBuilder {
+    //The type of the nth token from the cursor that isn't trivia, EOF past the end
+    fn peekNth(&self, n: usize) -> tokenTypeEnum {
+        let mut seen = 0;
+        for token in self.tokens[self.pos..].iter() {
+            if token.isTrivia() {
+                continue;
+            }
+            if seen == n {
+                return token.token.tt.clone();
+            }
+            seen += 1;
+        }
+        return tokenTypeEnum::EOF;
+    }
+
+    fn peek(&self) -> tokenTypeEnum {
+        return self.peekNth(0);
+    }
+
+    //Moves the trivia before the next token into a node
+    fn takeTrivia(&mut self, node: &mut CstNode) {
+        while self.pos < self.tokens.len() && self.tokens[self.pos].isTrivia() {
+            node.children.push(CstElement::Token(self.tokens[self.pos].clone()));
+            self.pos += 1;
+        }
+    }
+
+    //Moves the next token (and the trivia before it) into a node, returns its type
+    fn bump(&mut self, node: &mut CstNode) -> tokenTypeEnum {
+        self.takeTrivia(node);
+        if self.pos < self.tokens.len() {
+            let token = self.tokens[self.pos].clone();
+            self.pos += 1;
+            let tokenType = token.token.tt.clone();
+            node.children.push(CstElement::Token(token));
+            return tokenType;
+        }
+        return tokenTypeEnum::EOF;
+    }
+
+    //Moves the next token into a node if it is of a type
+    fn eat(&mut self, node: &mut CstNode, tokenType: tokenTypeEnum) -> bool {
+        if self.peek() == tokenType {
+            self.bump(node);
+            return true;
+        }
+        return false;
+    }
+
+    //If the next token is the end keyword that closes a block
+    fn atEnd(&self) -> bool {
+        return self.peek() == tokenTypeEnum::END || self.peek() == tokenTypeEnum::EOF;
+    }
+
+    //Adds a child node, the trivia before it stays in the parent
+    fn addChild(&mut self, parent: &mut CstNode, child: CstNode) {
+        parent.children.push(CstElement::Node(child));
+    }
+
+    //Moves tokens into a node until one of the stop tokens (which is included) at paren depth 0
+    //The tokens that start a block are not taken, so a missing ; doesn't swallow the rest of the file
+    fn bumpUntil(&mut self, node: &mut CstNode, stops: &[tokenTypeEnum]) {
+        let mut depth: usize = 0;
+        loop {
+            let next = self.peek();
+            if next == tokenTypeEnum::EOF {
+                return;
+            }
+            if depth == 0 && node.children.len() > 0 && matches!(next, tokenTypeEnum::BEGIN | tokenTypeEnum::END | tokenTypeEnum::PROCEDURE) {
+                return;
+            }
+            let tokenType = self.bump(node);
+            match tokenType {
+                tokenTypeEnum::L_PAREN => depth += 1,
+                tokenTypeEnum::R_PAREN => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth == 0 && stops.contains(&tokenType) {
+                return;
+            }
+        }
+    }
+
+    //The whole file
+    fn buildRoot(&mut self) -> CstNode {
+        let mut root = CstNode::new(NodeKind::Root);
+        loop {
+            self.takeTrivia(&mut root);
+            match self.peek() {
+                tokenTypeEnum::EOF => {
+                    //The EOF token has no text, it is kept so the tree has every token
+                    self.bump(&mut root);
+                    break;
+                }
+                tokenTypeEnum::PROGRAM => {
+                    let mut header = CstNode::new(NodeKind::ProgramHeader);
+                    self.bumpUntil(&mut header, &[tokenTypeEnum::IS]);
+                    self.addChild(&mut root, header);
+                }
+                tokenTypeEnum::GLOBAL | tokenTypeEnum::VARIABLE => {
+                    let declaration = self.buildDeclaration();
+                    self.addChild(&mut root, declaration);
+                }
+                tokenTypeEnum::PROCEDURE => {
+                    let procedure = self.buildProcedure();
+                    self.addChild(&mut root, procedure);
+                }
+                tokenTypeEnum::BEGIN => {
+                    let body = self.buildBody();
+                    self.addChild(&mut root, body);
+                }
+                _ => {
+                    let mut error = CstNode::new(NodeKind::Error);
+                    self.bump(&mut error);
+                    self.addChild(&mut root, error);
+                }
+            }
+        }
+        return root;
+    }
+
+    //[global] variable name : type;
+    fn buildDeclaration(&mut self) -> CstNode {
+        let mut declaration = CstNode::new(NodeKind::Declaration);
+        self.bumpUntil(&mut declaration, &[tokenTypeEnum::SEMICOLON]);
+        return declaration;
+    }
+
+    //procedure name : type(params) declarations begin statements end procedure;
+    fn buildProcedure(&mut self) -> CstNode {
+        let mut procedure = CstNode::new(NodeKind::Procedure);
+
+        let mut header = CstNode::new(NodeKind::ProcedureHeader);
+        self.bump(&mut header);
+        while !matches!(self.peek(), tokenTypeEnum::L_PAREN | tokenTypeEnum::EOF | tokenTypeEnum::BEGIN
+            | tokenTypeEnum::VARIABLE | tokenTypeEnum::PROCEDURE | tokenTypeEnum::END) {
+            self.bump(&mut header);
+        }
+        if self.eat(&mut header, tokenTypeEnum::L_PAREN) {
+            let mut parameters = CstNode::new(NodeKind::Parameters);
+            let mut depth: usize = 0;
+            loop {
+                self.takeTrivia(&mut parameters);
+                let next = self.peek();
+                if next == tokenTypeEnum::EOF || (next == tokenTypeEnum::R_PAREN && depth == 0) {
+                    break;
+                }
+                match self.bump(&mut parameters) {
+                    tokenTypeEnum::L_PAREN => depth += 1,
+                    tokenTypeEnum::R_PAREN => depth -= 1,
+                    _ => {}
+                }
+            }
+            //The trivia before the ) goes with the parameters
+            self.addChild(&mut header, parameters);
+            self.eat(&mut header, tokenTypeEnum::R_PAREN);
+        }
+        self.addChild(&mut procedure, header);
+
+        //The declarations and nested procedures
+        loop {
+            self.takeTrivia(&mut procedure);
+            match self.peek() {
+                tokenTypeEnum::GLOBAL | tokenTypeEnum::VARIABLE => {
+                    let declaration = self.buildDeclaration();
+                    self.addChild(&mut procedure, declaration);
+                }
+                tokenTypeEnum::PROCEDURE => {
+                    let nested = self.buildProcedure();
+                    self.addChild(&mut procedure, nested);
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+
+        if self.peek() == tokenTypeEnum::BEGIN {
+            let body = self.buildBody();
+            self.addChild(&mut procedure, body);
+        }
+        return procedure;
+    }
+
+    //begin statements end (program|procedure) followed by its ; or .
+    fn buildBody(&mut self) -> CstNode {
+        let mut body = CstNode::new(NodeKind::Body);
+        self.bump(&mut body);
+        self.buildStatements(&mut body);
+        self.buildEnd(&mut body);
+        return body;
+    }
+
+    //The end keyword, the keyword after it and the ; or . that closes it
+    fn buildEnd(&mut self, node: &mut CstNode) {
+        if self.eat(node, tokenTypeEnum::END) {
+            if matches!(self.peek(), tokenTypeEnum::PROGRAM | tokenTypeEnum::PROCEDURE | tokenTypeEnum::IF | tokenTypeEnum::FOR) {
+                self.bump(node);
+            }
+            if !self.eat(node, tokenTypeEnum::SEMICOLON) {
+                self.eat(node, tokenTypeEnum::PERIOD);
+            }
+        }
+    }
+
+    //The statements of a block, up to the end or else that closes it
+    fn buildStatements(&mut self, parent: &mut CstNode) {
+        loop {
+            self.takeTrivia(parent);
+            if self.atEnd() || self.peek() == tokenTypeEnum::ELSE {
+                return;
+            }
+            let statement = self.buildStatement();
+            self.addChild(parent, statement);
+        }
+    }
+
+    //One statement
+    fn buildStatement(&mut self) -> CstNode {
+        match self.peek() {
+            tokenTypeEnum::IF => {
+                let mut ifNode = CstNode::new(NodeKind::If);
+                self.bumpUntil(&mut ifNode, &[tokenTypeEnum::THEN]);
+                self.buildStatements(&mut ifNode);
+                if self.eat(&mut ifNode, tokenTypeEnum::ELSE) {
+                    self.buildStatements(&mut ifNode);
+                }
+                self.buildEnd(&mut ifNode);
+                return ifNode;
+            }
+            tokenTypeEnum::FOR => {
+                let mut forNode = CstNode::new(NodeKind::For);
+                self.bump(&mut forNode);
+                //The header is everything up to the ) that closes it
+                if self.peek() == tokenTypeEnum::L_PAREN {
+                    self.bumpUntil(&mut forNode, &[tokenTypeEnum::R_PAREN]);
+                }
+                self.buildStatements(&mut forNode);
+                self.buildEnd(&mut forNode);
+                return forNode;
+            }
+            tokenTypeEnum::RETURN => {
+                let mut returnNode = CstNode::new(NodeKind::Return);
+                self.bumpUntil(&mut returnNode, &[tokenTypeEnum::SEMICOLON]);
+                return returnNode;
+            }
+            tokenTypeEnum::IDENTIFIER => {
+                let mut assignment = CstNode::new(NodeKind::Assignment);
+                self.bumpUntil(&mut assignment, &[tokenTypeEnum::SEMICOLON]);
+                return assignment;
+            }
+            _ => {
+                //A declaration or a stray token among the statements, always takes at least one token
+                let mut error = CstNode::new(NodeKind::Error);
+                self.bump(&mut error);
+                if error.children.len() > 0 && self.peek() != tokenTypeEnum::END {
+                    self.bumpUntil(&mut error, &[tokenTypeEnum::SEMICOLON]);
+                }
+                return error;
+            }
+        }
+    }
+}
+
+///////////////////////// /CST SECTION /////////////////////////
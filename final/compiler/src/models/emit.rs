@@ -0,0 +1,364 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::lexer::{escapeString, Token},
+    crate::models::parser::{Expr, Stmt},
+    serde::Serialize,
+};
+#[cfg(feature = "llvm")]
+use inkwell::{basic_block::BasicBlock, module::Module, values::InstructionOpcode};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// EMIT SECTION /////////////////////////
+//This section writes the compiler's internal structures for outside tools and for debugging (--emit)
+//The tokens and the AST can be written as JSON, the layout is described in docs/json-schema.md, bump the version when it changes
+//The AST and the LLVM control flow graph can be written as Graphviz DOT (dot -Tsvg prog.ast.dot > prog.svg)
+
+//The version of the JSON layout, tools should check it before reading the rest
+pub const JSON_SCHEMA_VERSION: u32 = 4;
+
+//The top level object of --emit=tokens-json
+#[derive(Serialize)]
+struct TokensOutput<'a> {
+    schemaVersion: u32,         //JSON_SCHEMA_VERSION
+    kind: &'static str,         //Always "tokens"
+    file: &'a str,              //The .src file that was lexed
+    tokens: &'a Vec<Token>,     //The tokens the parser sees, ending with the EOF token
+}
+
+//The top level object of --emit=ast-json
+#[derive(Serialize)]
+struct AstOutput<'a> {
+    schemaVersion: u32,         //JSON_SCHEMA_VERSION
+    kind: &'static str,         //Always "ast"
+    file: &'a str,              //The .src file that was parsed
+    ast: &'a Stmt,              //The Program statement
+}
+
+//Writes the token list as JSON
+pub fn tokensJson(fileName: &str, tokens: &Vec<Token>) -> Result<String, String> {
+    let output = TokensOutput {
+        schemaVersion: JSON_SCHEMA_VERSION,
+        kind: "tokens",
+        file: fileName,
+        tokens: tokens,
+    };
+    match serde_json::to_string_pretty(&output) {
+        Ok(json) => {
+            return Ok(json);
+        }
+        Err(err) => {
+            return Err(format!("Error writing the tokens as JSON: {}", err));
+        }
+    }
+}
+
+//Writes the program AST as JSON
+pub fn astJson(fileName: &str, programAst: &Stmt) -> Result<String, String> {
+    let output = AstOutput {
+        schemaVersion: JSON_SCHEMA_VERSION,
+        kind: "ast",
+        file: fileName,
+        ast: programAst,
+    };
+    match serde_json::to_string_pretty(&output) {
+        Ok(json) => {
+            return Ok(json);
+        }
+        Err(err) => {
+            return Err(format!("Error writing the AST as JSON: {}", err));
+        }
+    }
+}
+
+//Builds up a DOT graph, gives each node a unique id
+struct DotGraph {
+    lines: Vec<String>,     //The node, edge and subgraph lines of the graph
+    nextId: usize,          //The id of the next node
+}
+impl DotGraph {
+    fn new() -> DotGraph {
+        DotGraph {
+            lines: Vec::new(),
+            nextId: 0,
+        }
+    }
+
+    //Adds a node with the label and returns its id
+    fn node(&mut self, label: &str, shape: &str) -> String {
+        let id = format!("n{}", self.nextId);
+        self.nextId += 1;
+        self.lines.push(format!("    {} [label=\"{}\", shape={}];", id, dotEscape(label), shape));
+        return id;
+    }
+
+    //Adds an edge, the label is left off when it is empty
+    fn edge(&mut self, from: &str, to: &str, label: &str) {
+        if label == "" {
+            self.lines.push(format!("    {} -> {};", from, to));
+        } else {
+            self.lines.push(format!("    {} -> {} [label=\"{}\"];", from, to, dotEscape(label)));
+        }
+    }
+
+    //The finished graph
+    fn finish(&self, name: &str) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n", dotEscape(name));
+        dot.push_str("    node [fontname=\"monospace\"];\n");
+        for line in &self.lines {
+            dot.push_str(line);
+            dot.push_str("\n");
+        }
+        dot.push_str("}\n");
+        return dot;
+    }
+}
+
+//Escapes a label for a quoted DOT string
+fn dotEscape(label: &str) -> String {
+    let mut escaped = String::new();
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+//Writes the program AST as a DOT graph, statements are boxes and expressions are ellipses
+//The edges are labeled with the part of the parent statement the child is (cond, then, else, body, etc.)
+pub fn astDot(fileName: &str, programAst: &Stmt) -> String {
+    let mut graph = DotGraph::new();
+    stmtDot(&mut graph, programAst);
+    return graph.finish(fileName);
+}
+
+//Adds a statement and everything under it to the graph, returns the statement's node
+fn stmtDot(graph: &mut DotGraph, stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Program(name, header, body, lineNum, ..) => {
+            let id = graph.node(&format!("Program {}\nline {}", name, lineNum), "box");
+            let headerId = stmtDot(graph, header);
+            graph.edge(&id, &headerId, "header");
+            let bodyId = stmtDot(graph, body);
+            graph.edge(&id, &bodyId, "body");
+            return id;
+        }
+        Stmt::Module(name, header, lineNum, ..) => {
+            let id = graph.node(&format!("Module {}\nline {}", name, lineNum), "box");
+            let headerId = stmtDot(graph, header);
+            graph.edge(&id, &headerId, "header");
+            return id;
+        }
+        Stmt::Import(name, lineNum, ..) => {
+            return graph.node(&format!("Import {}\nline {}", name, lineNum), "box");
+        }
+        Stmt::ExternProcDecl(retType, name, params, lineNum, ..) => {
+            let id = graph.node(&format!("External procedure {} : {}\nline {}", name, retType, lineNum), "box");
+            let paramsId = stmtDot(graph, params);
+            graph.edge(&id, &paramsId, "params");
+            return id;
+        }
+        Stmt::ProcDecl(retType, name, params, header, body, lineNum, ..) => {
+            let id = graph.node(&format!("Procedure {} : {}\nline {}", name, retType, lineNum), "box");
+            let paramsId = stmtDot(graph, params);
+            graph.edge(&id, &paramsId, "params");
+            let headerId = stmtDot(graph, header);
+            graph.edge(&id, &headerId, "header");
+            let bodyId = stmtDot(graph, body);
+            graph.edge(&id, &bodyId, "body");
+            return id;
+        }
+        Stmt::Block(stmts, lineNum, ..) => {
+            let id = graph.node(&format!("Block\nline {}", lineNum), "box");
+            for (index, child) in stmts.iter().enumerate() {
+                let childId = stmtDot(graph, child);
+                graph.edge(&id, &childId, &index.to_string());
+            }
+            return id;
+        }
+        Stmt::VarDecl(name, varType, lineNum, ..) => {
+            return graph.node(&format!("VarDecl {} : {}\nline {}", name, varType, lineNum), "box");
+        }
+        Stmt::GlobVarDecl(name, varType, lineNum, ..) => {
+            return graph.node(&format!("GlobVarDecl {} : {}\nline {}", name, varType, lineNum), "box");
+        }
+        Stmt::Assign(target, value, lineNum, ..) => {
+            let id = graph.node(&format!("Assign\nline {}", lineNum), "box");
+            let targetId = exprDot(graph, target);
+            graph.edge(&id, &targetId, "target");
+            let valueId = exprDot(graph, value);
+            graph.edge(&id, &valueId, "value");
+            return id;
+        }
+        Stmt::If(cond, thenBlock, elseBlock, lineNum, ..) => {
+            let id = graph.node(&format!("If\nline {}", lineNum), "box");
+            let condId = exprDot(graph, cond);
+            graph.edge(&id, &condId, "cond");
+            let thenId = stmtDot(graph, thenBlock);
+            graph.edge(&id, &thenId, "then");
+            if let Some(elseBlock) = elseBlock {
+                let elseId = stmtDot(graph, elseBlock);
+                graph.edge(&id, &elseId, "else");
+            }
+            return id;
+        }
+        Stmt::For(init, cond, body, lineNum, ..) => {
+            let id = graph.node(&format!("For\nline {}", lineNum), "box");
+            let initId = stmtDot(graph, init);
+            graph.edge(&id, &initId, "init");
+            let condId = exprDot(graph, cond);
+            graph.edge(&id, &condId, "cond");
+            let bodyId = stmtDot(graph, body);
+            graph.edge(&id, &bodyId, "body");
+            return id;
+        }
+        Stmt::Return(value, lineNum, ..) => {
+            let id = graph.node(&format!("Return\nline {}", lineNum), "box");
+            let valueId = exprDot(graph, value);
+            graph.edge(&id, &valueId, "");
+            return id;
+        }
+        Stmt::Expr(expr, lineNum, ..) => {
+            let id = graph.node(&format!("Expr\nline {}", lineNum), "box");
+            let exprId = exprDot(graph, expr);
+            graph.edge(&id, &exprId, "");
+            return id;
+        }
+        Stmt::StringLiteral(text, lineNum, ..) => {
+            return graph.node(&format!("StringLiteral \"{}\"\nline {}", escapeString(text), lineNum), "box");
+        }
+        Stmt::Error(reporting, lineNum, ..) => {
+            return graph.node(&format!("Error {}\nline {}", reporting.errors.join("; "), lineNum), "octagon");
+        }
+    }
+}
+
+//Adds an expression and its operands to the graph, returns the expression's node
+fn exprDot(graph: &mut DotGraph, expr: &Expr) -> String {
+    match expr {
+        Expr::IntLiteral(value, ..) => {
+            return graph.node(&value.to_string(), "ellipse");
+        }
+        Expr::FloatLiteral(value, ..) => {
+            return graph.node(&value.to_string(), "ellipse");
+        }
+        Expr::StringLiteral(text, ..) => {
+            return graph.node(&format!("\"{}\"", escapeString(text)), "ellipse");
+        }
+        Expr::BoolLiteral(value, ..) => {
+            return graph.node(&value.to_string(), "ellipse");
+        }
+        Expr::IntArrayLiteral(size, values, ..) => {
+            return graph.node(&format!("int[{}] {:?}", size, values), "ellipse");
+        }
+        Expr::VarRef(name, ..) => {
+            return graph.node(name, "ellipse");
+        }
+        Expr::ProcRef(name, args, ..) => {
+            let id = graph.node(&format!("call {}", name), "ellipse");
+            if let Some(args) = args {
+                for (index, arg) in args.iter().enumerate() {
+                    let argId = exprDot(graph, arg);
+                    graph.edge(&id, &argId, &format!("arg {}", index));
+                }
+            }
+            return id;
+        }
+        Expr::ArrayRef(name, index, ..) => {
+            let id = graph.node(&format!("{}[]", name), "ellipse");
+            let indexId = exprDot(graph, index);
+            graph.edge(&id, &indexId, "index");
+            return id;
+        }
+        Expr::ArthOp(left, op, right, ..) | Expr::RelOp(left, op, right, ..) | Expr::LogOp(left, op, right, ..) => {
+            let id = graph.node(&op.to_string(), "circle");
+            let leftId = exprDot(graph, left);
+            graph.edge(&id, &leftId, "");
+            let rightId = exprDot(graph, right);
+            graph.edge(&id, &rightId, "");
+            return id;
+        }
+    }
+}
+
+//Writes the basic blocks of each function in the generated module as a DOT graph, before it is optimized
+//Each function is a cluster, each block is labeled with its name, its instruction count and its terminator
+//The edges of a conditional branch are labeled true and false
+#[cfg(feature = "llvm")]
+pub fn cfgDot(fileName: &str, module: &Module) -> String {
+    let mut graph = DotGraph::new();
+    for (funcIndex, function) in module.get_functions().enumerate() {
+        let blocks: Vec<BasicBlock> = function.get_basic_blocks();
+        //Declarations of the runtime functions have no blocks
+        if blocks.len() == 0 {
+            continue;
+        }
+        let funcName = function.get_name().to_string_lossy().to_string();
+        graph.lines.push(format!("    subgraph cluster_{} {{", funcIndex));
+        graph.lines.push(format!("    label=\"{}\";", dotEscape(&funcName)));
+
+        //Adds every block first so that the edges can point at blocks later in the function
+        let mut ids: Vec<String> = Vec::new();
+        for (blockIndex, block) in blocks.iter().enumerate() {
+            let mut blockName = block.get_name().to_string_lossy().to_string();
+            if blockName == "" {
+                blockName = format!("block{}", blockIndex);
+            }
+            let mut numInstrs = 0;
+            let mut instr = block.get_first_instruction();
+            while let Some(current) = instr {
+                numInstrs += 1;
+                instr = current.get_next_instruction();
+            }
+            let terminator = match block.get_terminator() {
+                Some(term) => format!("{:?}", term.get_opcode()),
+                None => "no terminator".to_string(),
+            };
+            ids.push(graph.node(&format!("{}\n{} instructions\n{}", blockName, numInstrs, terminator), "box"));
+        }
+
+        //The successors are the block operands of the terminator
+        for (blockIndex, block) in blocks.iter().enumerate() {
+            let terminator = match block.get_terminator() {
+                Some(term) => term,
+                None => continue,
+            };
+            //A conditional br has the operands (condition, false block, true block)
+            let isCondBr = terminator.get_opcode() == InstructionOpcode::Br && terminator.get_num_operands() == 3;
+            for operandIndex in 0..terminator.get_num_operands() {
+                let target = match terminator.get_operand(operandIndex).and_then(|operand| operand.right()) {
+                    Some(target) => target,
+                    None => continue,
+                };
+                let targetIndex = match blocks.iter().position(|other| *other == target) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let label = if isCondBr && operandIndex == 1 { "false" } else if isCondBr && operandIndex == 2 { "true" } else { "" };
+                let (from, to) = (ids[blockIndex].clone(), ids[targetIndex].clone());
+                graph.edge(&from, &to, label);
+            }
+        }
+        graph.lines.push("    }".to_string());
+    }
+    return graph.finish(fileName);
+}
+
+///////////////////////// /EMIT SECTION /////////////////////////
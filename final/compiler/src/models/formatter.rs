@@ -0,0 +1,453 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::{
+        models::{
+            cst::Cst,
+            lexer::{escapeString, Lexer, Token, tokenGroup},
+            parser::Parser,
+        },
+        tokenTypeEnum,
+    },
+    std::collections::HashMap,
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// FORMATTER SECTION /////////////////////////
+//This section contains the source formatter used by 'compiler fmt'
+//The program is parsed first so only valid programs are formatted, then the tokens are printed again in the canonical layout
+//The tokens are used instead of the AST because the AST drops things the source has (parentheses, array indexes in calls), the comments and the text of string literals and names come from the concrete syntax tree
+
+//The indentation for one level of blocks
+const INDENT: &str = "    ";
+
+//Formats the source of a program, returns the formatted source or the errors that stopped it from being formatted
+pub fn formatSource(fileName: &str, source: &str) -> Result<String, String> {
+    let (tokens, cst) = lexWithTrivia(fileName, source)?;
+
+    //Only formats programs that parse, so the layout can't hide a syntax error
+    let mut myLexer = Lexer::newFromString(fileName, source.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    match myParser.startParse() {
+        Ok((reporting, Some(stmt))) => {}
+        Ok((reporting, None)) => {
+            return Err(format!("Error formatting {}: the program could not be parsed", fileName));
+        }
+        Err(reporting) => {
+            return Err(format!("Error formatting {}: the program could not be parsed\n{}", fileName, reporting.errors.join("\n")));
+        }
+    }
+
+    let mut myFormatter = Formatter::new(tokens, &cst);
+    let formatted = myFormatter.format();
+
+    //The formatter only changes whitespace, checks that the tokens and comments are the same after formatting
+    let (newTokens, newCst) = lexWithTrivia(fileName, &formatted)?;
+    let newComments: Vec<Token> = newCst.comments().into_iter().map(|comment| comment.token.clone()).collect();
+    let sameTokens = newTokens.len() == myFormatter.tokens.len()
+        && newTokens.iter().zip(myFormatter.tokens.iter()).all(|(new, old)| new.tt == old.tt && new.tokenString == old.tokenString);
+    let sameComments = newComments.len() == myFormatter.comments.len()
+        && newComments.iter().zip(myFormatter.comments.iter()).all(|(new, old)| new.tokenString == old.tokenString);
+    if !sameTokens || !sameComments {
+        return Err(format!("Error formatting {}: the formatted program doesn't match the original, the file was not changed", fileName));
+    }
+    return Ok(formatted);
+}
+
+//Lexes the source keeping the trivia, returns the tokens the parser sees (without the EOF) and the concrete syntax tree
+fn lexWithTrivia(fileName: &str, source: &str) -> Result<(Vec<Token>, Cst), String> {
+    let mut myLexer = Lexer::newFromString(fileName, source.to_string());
+    myLexer.keepTrivia = true;
+    myLexer.scanThrough();
+    if myLexer.reports.status {
+        return Err(format!("Error formatting {}:\n{}", fileName, myLexer.reports.errors.join("\n")));
+    }
+    let tokens: Vec<Token> = myLexer.tokenList.iter().filter(|token| token.tt != tokenTypeEnum::EOF).cloned().collect();
+    return Ok((tokens, Cst::new(&myLexer.rawTokens, source)));
+}
+
+//The headers that end at the ) that closes them
+#[derive(Debug, Clone, PartialEq)]
+enum Header {
+    None,
+    Procedure,      //procedure name : type(params)
+    For,            //for (assignment; condition)
+}
+
+//One line of the formatted output
+#[derive(Debug, Clone, PartialEq)]
+struct FormattedLine {
+    indent: usize,
+    text: String,
+    colonAt: Option<usize>,     //Where the ' :' of a variable declaration starts, used to line up the declarations
+    trailing: Option<String>,   //A comment at the end of the line
+}
+
+//The structure that holds the state of the formatter while it prints the tokens
+pub struct Formatter {
+    pub tokens: Vec<Token>,         //The tokens of the program
+    pub comments: Vec<Token>,       //The comments, in the order they were found
+    sourceTexts: HashMap<usize, String>, //The text of the string literals and names by where they start, the lexer pads strings and lowercases names
+    lines: Vec<FormattedLine>,      //The finished lines
+    pending: String,                //The line being built
+    pendingIndent: usize,
+    pendingColon: Option<usize>,
+    pendingTrailing: Option<String>,
+    pendingFirst: Option<tokenTypeEnum>, //The first token of the line being built
+    prevToken: Option<Token>,       //The token before the current one on the line being built
+    prevUnary: bool,                //If the previous token was a unary minus
+    indent: usize,                  //The current block depth
+    parenDepth: usize,
+    header: Header,
+    continuation: bool,             //Set when a comment split a statement, the rest of it is indented one more level
+    lastLine: usize,                //The source line of the last token or comment that was printed
+    lastTokenLine: Option<usize>,   //The source line of the last token that was printed
+}
+
+impl Formatter {
+    //The constructor
+    pub fn new(tokens: Vec<Token>, cst: &Cst) -> Formatter {
+        let mut sourceTexts: HashMap<usize, String> = HashMap::new();
+        for cstToken in cst.tokens() {
+            if cstToken.token.tt == tokenTypeEnum::STRING || cstToken.token.tt == tokenTypeEnum::IDENTIFIER {
+                sourceTexts.insert(cstToken.token.start, cstToken.text.clone());
+            }
+        }
+        Formatter {
+            tokens,
+            comments: cst.comments().into_iter().map(|comment| comment.token.clone()).collect(),
+            sourceTexts,
+            lines: Vec::new(),
+            pending: String::new(),
+            pendingIndent: 0,
+            pendingColon: None,
+            pendingTrailing: None,
+            pendingFirst: None,
+            prevToken: None,
+            prevUnary: false,
+            indent: 0,
+            parenDepth: 0,
+            header: Header::None,
+            continuation: false,
+            lastLine: 0,
+            lastTokenLine: None,
+        }
+    }
+
+    //Formats the program, returns the formatted source
+    pub fn format(&mut self) -> String {
+        let tokens = self.tokens.clone();
+        let comments = self.comments.clone();
+        let mut commentIdx = 0;
+
+        for token in tokens.iter() {
+            //Prints the comments that come before this token
+            while commentIdx < comments.len() && position(&comments[commentIdx]) < position(token) {
+                self.addComment(&comments[commentIdx]);
+                commentIdx += 1;
+            }
+            self.addToken(token);
+        }
+        //The comments after the end of the program
+        while commentIdx < comments.len() {
+            self.addComment(&comments[commentIdx]);
+            commentIdx += 1;
+        }
+        self.flush();
+
+        self.alignDeclarations();
+        return self.render();
+    }
+
+    //Adds a token to the line being built, ending the line where the layout says to
+    fn addToken(&mut self, token: &Token) {
+        let line = tokenLine(token);
+
+        //These always start their own line, one level out from their body
+        let opensLine = matches!(token.tt, tokenTypeEnum::BEGIN | tokenTypeEnum::ELSE | tokenTypeEnum::END_PROGRAM
+            | tokenTypeEnum::END_MODULE | tokenTypeEnum::END_PROCEDURE | tokenTypeEnum::END_IF | tokenTypeEnum::END_FOR);
+        if opensLine && self.pending != "" {
+            self.flush();
+        }
+
+        if self.pending == "" {
+            self.startLine(line);
+            self.pendingIndent = if opensLine {
+                self.indent.saturating_sub(1)
+            } else if self.continuation {
+                self.indent + 1
+            } else {
+                self.indent
+            };
+            self.pendingFirst = Some(token.tt.clone());
+            if token.tt == tokenTypeEnum::PROCEDURE {
+                self.header = Header::Procedure;
+            } else if token.tt == tokenTypeEnum::FOR {
+                self.header = Header::For;
+            }
+        }
+
+        //Saves where the : of a variable declaration is so the declarations can be lined up
+        let isDeclaration = matches!(self.pendingFirst, Some(tokenTypeEnum::VARIABLE) | Some(tokenTypeEnum::GLOBAL));
+        if token.tt == tokenTypeEnum::COLON && isDeclaration && self.parenDepth == 0 && self.pendingColon.is_none() {
+            self.pendingColon = Some(self.pending.len());
+        }
+
+        let isUnary = token.tt == tokenTypeEnum::MINUS && self.isUnaryPosition();
+        if self.needsSpace(token) {
+            self.pending.push(' ');
+        }
+        let text = self.tokenText(token);
+        self.pending.push_str(&text);
+        self.prevToken = Some(token.clone());
+        self.prevUnary = isUnary;
+        self.lastLine = line;
+        self.lastTokenLine = Some(line);
+
+        //Decides if the line, or a block, ends after this token
+        match token.tt {
+            tokenTypeEnum::L_PAREN | tokenTypeEnum::PROCEDURE_CALL => {
+                self.parenDepth += 1;
+            }
+            tokenTypeEnum::R_PAREN => {
+                self.parenDepth = self.parenDepth.saturating_sub(1);
+                //The end of a procedure or for header starts a block
+                if self.parenDepth == 0 && self.header != Header::None {
+                    self.header = Header::None;
+                    self.flush();
+                    self.indent += 1;
+                }
+            }
+            tokenTypeEnum::SEMICOLON => {
+                if self.parenDepth == 0 {
+                    self.flush();
+                }
+            }
+            tokenTypeEnum::IS | tokenTypeEnum::THEN => {
+                self.flush();
+                self.indent += 1;
+            }
+            tokenTypeEnum::BEGIN | tokenTypeEnum::ELSE => {
+                self.flush();
+            }
+            tokenTypeEnum::END_PROGRAM | tokenTypeEnum::END_MODULE | tokenTypeEnum::END_PROCEDURE | tokenTypeEnum::END_IF | tokenTypeEnum::END_FOR => {
+                self.indent = self.indent.saturating_sub(1);
+            }
+            tokenTypeEnum::PERIOD => {
+                if matches!(self.pendingFirst, Some(tokenTypeEnum::END_PROGRAM) | Some(tokenTypeEnum::END_MODULE)) {
+                    self.flush();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    //Adds a comment, at the end of the line it was on or on a line of its own
+    fn addComment(&mut self, comment: &Token) {
+        let line = tokenLine(comment);
+        let endLine = line + comment.tokenString.matches('\n').count();
+        let sameLine = self.lastTokenLine == Some(line);
+
+        if sameLine {
+            //A comment after code on the same line stays at the end of that line
+            if self.pending != "" {
+                self.pendingTrailing = Some(joinComments(self.pendingTrailing.clone(), &comment.tokenString));
+            } else if let Some(lastLine) = self.lines.last_mut() {
+                lastLine.trailing = Some(joinComments(lastLine.trailing.clone(), &comment.tokenString));
+            }
+        } else {
+            //A comment in the middle of a statement splits it, the rest of the statement is indented one more level
+            if self.pending != "" {
+                self.flush();
+                self.continuation = true;
+            }
+            self.startLine(line);
+            let indent = if self.continuation { self.indent + 1 } else { self.indent };
+            self.lines.push(FormattedLine {
+                indent,
+                text: comment.tokenString.trim_end().to_string(),
+                colonAt: None,
+                trailing: None,
+            });
+        }
+        self.lastLine = endLine;
+    }
+
+    //Keeps one blank line where the source had blank lines before the line that is starting
+    fn startLine(&mut self, line: usize) {
+        let lastIsBlank = self.lines.last().map(|last| last.text == "").unwrap_or(true);
+        if self.lines.len() > 0 && line > self.lastLine + 1 && !lastIsBlank {
+            self.lines.push(FormattedLine {
+                indent: 0,
+                text: "".to_string(),
+                colonAt: None,
+                trailing: None,
+            });
+        }
+    }
+
+    //Ends the line being built
+    fn flush(&mut self) {
+        if self.pending == "" {
+            return;
+        }
+        self.lines.push(FormattedLine {
+            indent: self.pendingIndent,
+            text: self.pending.clone(),
+            colonAt: self.pendingColon,
+            trailing: self.pendingTrailing.clone(),
+        });
+        //A statement split by a comment ends with its ;, and every header or block keyword ends a line
+        if self.parenDepth == 0 {
+            self.continuation = false;
+        }
+        self.pending = String::new();
+        self.pendingColon = None;
+        self.pendingTrailing = None;
+        self.pendingFirst = None;
+        self.prevToken = None;
+        self.prevUnary = false;
+    }
+
+    //If a minus is a unary minus, which goes right against its operand
+    fn isUnaryPosition(&self) -> bool {
+        match &self.prevToken {
+            None => true,
+            Some(prev) => {
+                matches!(prev.tt, tokenTypeEnum::L_PAREN | tokenTypeEnum::L_BRACKET | tokenTypeEnum::COMMA
+                    | tokenTypeEnum::SET_EQUALS | tokenTypeEnum::RETURN | tokenTypeEnum::PROCEDURE_CALL)
+                    || prev.tg == tokenGroup::OPERATOR
+            }
+        }
+    }
+
+    //If a space goes between the previous token on the line and this one
+    fn needsSpace(&self, token: &Token) -> bool {
+        let prev = match &self.prevToken {
+            Some(prev) => prev,
+            None => return false,
+        };
+        if matches!(token.tt, tokenTypeEnum::SEMICOLON | tokenTypeEnum::COMMA | tokenTypeEnum::R_PAREN
+            | tokenTypeEnum::R_BRACKET | tokenTypeEnum::PERIOD | tokenTypeEnum::L_BRACKET) {
+            return false;
+        }
+        if matches!(prev.tt, tokenTypeEnum::L_PAREN | tokenTypeEnum::L_BRACKET | tokenTypeEnum::PROCEDURE_CALL) {
+            return false;
+        }
+        if self.prevUnary {
+            return false;
+        }
+        return true;
+    }
+
+    //The text a token is printed as
+    fn tokenText(&self, token: &Token) -> String {
+        match token.tt {
+            tokenTypeEnum::END_PROGRAM => "end program".to_string(),
+            tokenTypeEnum::END_PROCEDURE => "end procedure".to_string(),
+            tokenTypeEnum::END_MODULE => "end module".to_string(),
+            tokenTypeEnum::EXTERNAL_PROCEDURE => "external procedure".to_string(),
+            tokenTypeEnum::END_IF => "end if".to_string(),
+            tokenTypeEnum::END_FOR => "end for".to_string(),
+            tokenTypeEnum::PROCEDURE_CALL => format!("{}(", self.nameText(token)),
+            tokenTypeEnum::IDENTIFIER => self.nameText(token),
+            tokenTypeEnum::STRING => self.stringText(token),
+            _ => token.tokenString.clone(),
+        }
+    }
+
+    //Only keywords are lowercased, a name keeps the spelling it was written with
+    fn nameText(&self, token: &Token) -> String {
+        match self.sourceTexts.get(&token.start) {
+            Some(text) => return text.clone(),
+            None => return token.tokenString.clone(),
+        }
+    }
+
+    //The lexer pads string literals, so the text is taken from the tree
+    fn stringText(&self, token: &Token) -> String {
+        if let Some(text) = self.sourceTexts.get(&token.start) {
+            return text.clone();
+        }
+        //Falls back on the token, with its escapes put back
+        return format!("\"{}\"", escapeString(&token.tokenString));
+    }
+
+    //Lines up the : of variable declarations that are next to each other in the same block
+    fn alignDeclarations(&mut self) {
+        let mut start = 0;
+        while start < self.lines.len() {
+            if self.lines[start].colonAt.is_none() {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end < self.lines.len() && self.lines[end].colonAt.is_some() && self.lines[end].indent == self.lines[start].indent {
+                end += 1;
+            }
+            let width = self.lines[start..end].iter().filter_map(|line| line.colonAt).max().unwrap_or(0);
+            for line in self.lines[start..end].iter_mut() {
+                if let Some(colonAt) = line.colonAt {
+                    let padding = " ".repeat(width - colonAt);
+                    line.text = format!("{}{}{}", &line.text[..colonAt], padding, &line.text[colonAt..]);
+                }
+            }
+            start = end;
+        }
+    }
+
+    //Puts the lines together
+    fn render(&self) -> String {
+        let mut output = String::new();
+        for line in self.lines.iter() {
+            if line.text != "" {
+                output.push_str(&INDENT.repeat(line.indent));
+                output.push_str(&line.text);
+                if let Some(trailing) = &line.trailing {
+                    output.push(' ');
+                    output.push_str(trailing.trim_end());
+                }
+            }
+            output.push('\n');
+        }
+        //No blank lines at the end of the file
+        while output.ends_with("\n\n") {
+            output.pop();
+        }
+        return output;
+    }
+}
+
+//The line a token or comment starts on
+fn tokenLine(token: &Token) -> usize {
+    return token.lineNum.parse::<usize>().unwrap_or(0);
+}
+
+//Where a token or comment is in the source, used to put the comments back between the tokens
+fn position(token: &Token) -> (usize, usize) {
+    return (tokenLine(token), token.col);
+}
+
+//Puts two comments that end up at the end of the same line together
+fn joinComments(existing: Option<String>, comment: &String) -> String {
+    match existing {
+        Some(existing) => format!("{} {}", existing, comment),
+        None => comment.clone(),
+    }
+}
+
+///////////////////////// /FORMATTER SECTION /////////////////////////
@@ -0,0 +1,121 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::{
+        lexer::{Lexer, Token},
+        parser::{Parser, Stmt},
+        reporting::{Reporting, Span},
+        typechecker::{SymbolTable, SyntaxChecker},
+    },
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// FRONT END SECTION /////////////////////////
+//This section runs the lexer, parser and SyntaxChecker over source that is already in memory
+//The language server and the fuzz targets use it, every input gives either an AST or the errors that stopped it
+
+//The stages of the front end, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Lex,
+    Parse,
+    Check,
+}
+
+//What the front end made of the source
+pub struct FrontEnd {
+    pub tokens: Vec<Token>,             //The tokens from the lexer
+    pub ast: Option<Stmt>,              //The AST, if the source parsed
+    pub valid: bool,                    //If every stage that ran found no errors
+    pub errors: Vec<String>,            //The errors from the stages that ran
+    pub errorSpans: Vec<Option<Span>>,  //Where each error is, None for errors that aren't about one place in the source
+    pub globalTable: SymbolTable,       //The global table the checker filled in
+}
+
+//Runs the front end over the source, up to and including the last stage
+//Imports are not loaded, the checker only sees the one file
+pub fn runFrontEnd(fileName: &str, source: &str, lastStage: Stage) -> FrontEnd {
+    let mut frontEnd = FrontEnd {
+        tokens: Vec::new(),
+        ast: None,
+        valid: false,
+        errors: Vec::new(),
+        errorSpans: Vec::new(),
+        globalTable: SymbolTable::new(),
+    };
+
+    let mut myLexer = Lexer::newFromString(fileName, source.to_string());
+    myLexer.scanThrough();
+    frontEnd.tokens = myLexer.tokenList.clone();
+    if myLexer.reports.status {
+        frontEnd.addReports(&myLexer.reports);
+        return frontEnd;
+    }
+    if lastStage == Stage::Lex {
+        frontEnd.valid = true;
+        return frontEnd;
+    }
+
+    let mut myParser = Parser::new(&myLexer);
+    let programAst: Stmt;
+    match myParser.startParse() {
+        Ok((reporting, Some(stmt))) => {
+            frontEnd.addReports(&reporting);
+            programAst = stmt;
+        }
+        Ok((reporting, None)) => {
+            frontEnd.addReports(&reporting);
+            return frontEnd;
+        }
+        Err(reporting) => {
+            frontEnd.addReports(&reporting);
+            if reporting.errors.len() == 0 {
+                frontEnd.errors.push("Error parsing program".to_string());
+                frontEnd.errorSpans.push(None);
+            }
+            return frontEnd;
+        }
+    }
+    frontEnd.ast = Some(programAst.clone());
+    if lastStage == Stage::Parse {
+        frontEnd.valid = true;
+        return frontEnd;
+    }
+
+    let mut globalTable = SymbolTable::new();
+    let mut myChecker = SyntaxChecker::new(programAst, &mut globalTable, "Main".to_string());
+    let programValid = myChecker.checkProgram();
+    frontEnd.addReports(&myChecker.reports);
+    if !programValid && myChecker.reports.errors.len() == 0 {
+        frontEnd.errors.push("Error in program".to_string());
+        frontEnd.errorSpans.push(None);
+    }
+    frontEnd.valid = programValid;
+    frontEnd.globalTable = globalTable;
+    return frontEnd;
+}
+
+impl FrontEnd {
+    //Adds the errors of a stage with where they are
+    fn addReports(&mut self, reports: &Reporting) {
+        for (index, message) in reports.errors.iter().enumerate() {
+            self.errors.push(message.clone());
+            self.errorSpans.push(reports.spanOf(index));
+        }
+    }
+}
+
+///////////////////////// /FRONT END SECTION /////////////////////////
@@ -0,0 +1,361 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::frontend::{runFrontEnd, FrontEnd, Stage},
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// FUZZING SECTION /////////////////////////
+//This section is shared by the cargo-fuzz targets in fuzz/ and tests/fuzz.rs
+//A fuzz input is either used as the source itself or read as the choices of a program generator that follows the grammar (docs/grammar.md)
+//The rule the targets check is that no input panics, every input gives an AST or at least one error
+
+//How deeply the generator nests blocks and expressions
+const MAX_DEPTH: usize = 4;
+
+//The names the generated programs use, a few are declared so some programs get past the checker
+const NAMES: &[&str] = &["x", "y", "total", "arr", "flag", "s", "f", "g", "putinteger", "sqrt", "undefined"];
+
+//Tokens the generator drops in at random, so the programs also hit the error paths
+const STRAY_TOKENS: &[&str] = &[
+    ";", ":", ":=", "(", ")", "[", "]", ",", ".", "-", "end", "begin", "is", "if", "then", "else", "for",
+    "return", "procedure", "variable", "global", "program", "module", "import", "external", "not", "\"",
+    "/*", "*/", "//", "=", "!", "1.2.3", "99999999999999999999", "@", "0x", "1e", "1__0", "12ab", "1e99",
+];
+
+//Reads the choices of the generator from the fuzz input, when the input runs out every choice is 0
+//Choice 0 is always the one that ends soonest, so the generated program stays finite
+pub struct ByteChoices<'d> {
+    data: &'d [u8],     //The fuzz input
+    pos: usize,         //The next byte to read
+}
+
+impl<'d> ByteChoices<'d> {
+    pub fn new(data: &'d [u8]) -> ByteChoices<'d> {
+        ByteChoices {
+            data,
+            pos: 0,
+        }
+    }
+
+    //A number from 0 up to but not including count
+    pub fn choose(&mut self, count: usize) -> usize {
+        if count <= 1 || self.pos >= self.data.len() {
+            return 0;
+        }
+        let byte = self.data[self.pos] as usize;
+        self.pos += 1;
+        return byte % count;
+    }
+
+    //True about one time in chance
+    pub fn oneIn(&mut self, chance: usize) -> bool {
+        return self.choose(chance) == chance - 1;
+    }
+}
+
+//The source a fuzz input stands for, the first byte picks raw bytes (even) or a generated program (odd)
+pub fn fuzzSource(data: &[u8]) -> String {
+    match data.split_first() {
+        Some((first, rest)) if first % 2 == 1 => {
+            return generateProgram(rest);
+        }
+        Some((first, rest)) => {
+            return String::from_utf8_lossy(rest).to_string();
+        }
+        None => {
+            return String::new();
+        }
+    }
+}
+
+//Runs the front end up to the stage on the fuzz input and checks the result
+//Panics (which the fuzzer reports) if the front end gives neither an AST nor an error
+pub fn fuzzStage(data: &[u8], stage: Stage) -> FrontEnd {
+    let source = fuzzSource(data);
+    let frontEnd = runFrontEnd("fuzz.src", &source, stage);
+    if stage >= Stage::Parse && frontEnd.ast.is_none() && frontEnd.errors.is_empty() {
+        panic!("The front end gave no AST and no error for:\n{}", source);
+    }
+    if !frontEnd.valid && frontEnd.errors.is_empty() {
+        panic!("The front end failed without an error for:\n{}", source);
+    }
+    return frontEnd;
+}
+
+//Generates a program from the choices in the data, most of it follows the grammar
+pub fn generateProgram(data: &[u8]) -> String {
+    let mut gen = Generator {
+        choices: ByteChoices::new(data),
+        out: String::new(),
+    };
+    gen.unit();
+    return gen.out;
+}
+
+//The program generator, one function for each part of the grammar
+struct Generator<'d> {
+    choices: ByteChoices<'d>,   //Where the choices come from
+    out: String,                //The program so far
+}
+
+impl<'d> Generator<'d> {
+    //Adds a token and a space
+    fn emit(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.out.push(' ');
+    }
+
+    //Sometimes adds a token that doesn't belong
+    fn maybeStray(&mut self) {
+        if self.choices.oneIn(16) {
+            let stray = STRAY_TOKENS[self.choices.choose(STRAY_TOKENS.len())];
+            self.emit(stray);
+        }
+    }
+
+    fn name(&mut self) {
+        let name = NAMES[self.choices.choose(NAMES.len())];
+        self.emit(name);
+    }
+
+    fn unit(&mut self) {
+        if self.choices.oneIn(8) {
+            self.emit("module m is");
+            self.declarations(0);
+            self.emit("end module.");
+            return;
+        }
+        self.emit("program p is\nvariable x : integer;\nvariable arr : integer[4];\n");
+        self.declarations(0);
+        self.emit("begin\n");
+        self.statements(0);
+        self.emit("end program.\n");
+    }
+
+    fn declarations(&mut self, depth: usize) {
+        let count = self.choices.choose(4);
+        for _ in 0..count {
+            self.declaration(depth);
+            self.maybeStray();
+        }
+    }
+
+    fn declaration(&mut self, depth: usize) {
+        match self.choices.choose(6) {
+            0 => {
+                self.variableSpec();
+                self.emit(";\n");
+            }
+            1 => {
+                self.emit("global");
+                self.variableSpec();
+                self.emit(";\n");
+            }
+            2 if depth < MAX_DEPTH => {
+                self.procedure(depth + 1);
+            }
+            3 => {
+                self.emit("external procedure");
+                self.name();
+                self.emit(":");
+                self.scalarType();
+                self.emit("(");
+                self.parameters();
+                self.emit(");\n");
+            }
+            4 => {
+                self.emit("import");
+                self.name();
+                self.emit(";\n");
+            }
+            _ => {
+                self.emit("variable y : float;\n");
+            }
+        }
+    }
+
+    fn variableSpec(&mut self) {
+        self.emit("variable");
+        self.name();
+        self.emit(":");
+        if self.choices.oneIn(4) {
+            let size = self.choices.choose(10);
+            self.emit(&format!("integer[{}]", size));
+        } else {
+            self.scalarType();
+        }
+    }
+
+    fn scalarType(&mut self) {
+        let types = ["integer", "float", "bool", "string"];
+        let varType = types[self.choices.choose(types.len())];
+        self.emit(varType);
+    }
+
+    fn parameters(&mut self) {
+        let count = self.choices.choose(4);
+        for i in 0..count {
+            if i > 0 {
+                self.emit(",");
+            }
+            self.variableSpec();
+        }
+    }
+
+    fn procedure(&mut self, depth: usize) {
+        self.emit("procedure");
+        self.name();
+        self.emit(":");
+        self.scalarType();
+        self.emit("(");
+        self.parameters();
+        self.emit(")\n");
+        self.declarations(depth);
+        self.emit("begin\n");
+        self.statements(depth);
+        self.emit("return");
+        self.expression(depth);
+        self.emit(";\nend procedure;\n");
+    }
+
+    fn statements(&mut self, depth: usize) {
+        let count = self.choices.choose(5);
+        for _ in 0..count {
+            self.statement(depth);
+            self.maybeStray();
+        }
+    }
+
+    fn statement(&mut self, depth: usize) {
+        match self.choices.choose(6) {
+            1 if depth < MAX_DEPTH => {
+                self.emit("if (");
+                self.expression(depth + 1);
+                self.emit(") then\n");
+                self.statements(depth + 1);
+                if self.choices.oneIn(2) {
+                    self.emit("else\n");
+                    self.statements(depth + 1);
+                }
+                self.emit("end if;\n");
+            }
+            2 if depth < MAX_DEPTH => {
+                self.emit("for (");
+                self.assignment(depth + 1);
+                self.expression(depth + 1);
+                self.emit(")\n");
+                self.statements(depth + 1);
+                self.emit("end for;\n");
+            }
+            3 => {
+                self.emit("return");
+                if self.choices.oneIn(2) {
+                    self.expression(depth + 1);
+                }
+                self.emit(";\n");
+            }
+            4 => {
+                self.call(depth + 1);
+                self.emit(";\n");
+            }
+            _ => {
+                self.assignment(depth + 1);
+            }
+        }
+    }
+
+    fn assignment(&mut self, depth: usize) {
+        if self.choices.oneIn(3) {
+            self.emit("arr[");
+            self.expression(depth + 1);
+            self.emit("]");
+        } else {
+            self.name();
+        }
+        self.emit(":=");
+        self.expression(depth + 1);
+        self.emit(";\n");
+    }
+
+    fn expression(&mut self, depth: usize) {
+        self.operand(depth);
+        let operators = ["+", "-", "*", "/", "<", "<=", ">", ">=", "==", "!=", "&", "|", "not"];
+        while depth < MAX_DEPTH && self.choices.oneIn(3) {
+            let operator = operators[self.choices.choose(operators.len())];
+            self.emit(operator);
+            self.operand(depth + 1);
+        }
+    }
+
+    fn operand(&mut self, depth: usize) {
+        match self.choices.choose(5) {
+            1 if depth < MAX_DEPTH => {
+                self.emit("(");
+                self.expression(depth + 1);
+                self.emit(")");
+            }
+            2 if depth < MAX_DEPTH => {
+                self.call(depth + 1);
+            }
+            3 if depth < MAX_DEPTH => {
+                self.emit("arr[");
+                self.expression(depth + 1);
+                self.emit("]");
+            }
+            4 => {
+                self.name();
+            }
+            _ => {
+                self.literal();
+            }
+        }
+    }
+
+    fn call(&mut self, depth: usize) {
+        self.name();
+        self.emit("(");
+        if depth < MAX_DEPTH {
+            let count = self.choices.choose(4);
+            for i in 0..count {
+                if i > 0 {
+                    self.emit(",");
+                }
+                self.expression(depth + 1);
+            }
+        }
+        self.emit(")");
+    }
+
+    fn literal(&mut self) {
+        let literal = match self.choices.choose(11) {
+            8 => "0x7F".to_string(),
+            9 => "0b1010_1010".to_string(),
+            10 => "1.5e3".to_string(),
+            1 => "-3".to_string(),
+            2 => "2.5".to_string(),
+            3 => "\"text\"".to_string(),
+            4 => "true".to_string(),
+            5 => "false".to_string(),
+            6 => format!("{}", self.choices.choose(256) * 1000),
+            7 => "2147483647".to_string(),
+            _ => "1".to_string(),
+        };
+        self.emit(&literal);
+    }
+}
+
+///////////////////////// /FUZZING SECTION /////////////////////////
@@ -0,0 +1,868 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::{
+        parser::{Expr, NumberWidth, Operator, Stmt, VarType},
+        reporting::Reporting,
+    },
+    std::{
+        collections::HashMap,
+        fmt,
+        io::{self, Write},
+        rc::Rc,
+    },
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// INTERPRETER SECTION /////////////////////////
+//This section runs a checked program AST directly, without LLVM
+//It follows what the LLVM backend generates: integers are 32 bit, floats are 32 bit (64 bit with --wide-numbers), arithmetic with a float operand is done in float,
+//and procedures can only see their own locals and the globals (plus the procedures declared before them)
+
+//The deepest procedure recursion allowed before the interpreter reports a stack overflow
+//main runs the compiler on a thread with a large stack so this fits even in a debug build
+const MAX_CALL_DEPTH: usize = 10000;
+
+//A runtime value, numbers are held at 64 bits and cut to the number width after each operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String, i64),   //The characters and how many there are, counted once when the string is made so stringlength doesn't count them
+    IntArray(Vec<i64>),
+}
+impl Value {
+    //A string value, like a string of the compiled program it keeps its character count
+    pub fn string(text: String) -> Value {
+        let chars = funcLib::stringLength(&text);
+        return Value::Str(text, chars);
+    }
+
+    //The zero value for a declared variable, this is what the LLVM backend initializes variables with
+    pub fn default(varType: &VarType) -> Value {
+        match varType {
+            VarType::Int => Value::Int(0),
+            VarType::Float => Value::Float(0.0),
+            VarType::Bool => Value::Bool(false),
+            VarType::Str => Value::Str("".to_string(), 0),
+            VarType::IntArray(size) => Value::IntArray(vec![0; (*size).max(0) as usize]),
+        }
+    }
+}
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(val) => write!(f, "{}", val),
+            Value::Float(val) => write!(f, "{}", val),
+            Value::Bool(val) => write!(f, "{}", val),
+            Value::Str(val, _) => write!(f, "{}", val),
+            Value::IntArray(vals) => write!(f, "[{}]", vals.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")),
+        }
+    }
+}
+
+//What a statement did, used to unwind out of a procedure when a return is hit
+enum Flow {
+    Normal,
+    Return(Option<Value>),
+}
+
+//A declared procedure
+struct ProcDef {
+    name: String,
+    retType: VarType,
+    params: Vec<(String, VarType)>,             //The parameters in order (name, type)
+    header: Stmt,                               //The header block (local declarations and nested procedures)
+    body: Stmt,                                 //The body block
+    visibleProcs: Rc<HashMap<String, Rc<ProcDef>>>, //The procedures that were declared before this one in the same or an enclosing scope
+}
+
+//The variables and procedures of one procedure call (or of the main program)
+struct Frame {
+    vars: HashMap<String, Value>,                   //The local variables and parameters
+    procs: HashMap<String, Rc<ProcDef>>,            //The procedures declared in this frame's header
+    outerProcs: Rc<HashMap<String, Rc<ProcDef>>>,   //The procedures the running procedure can see from outside, shared so calls don't copy them
+    current: Option<Rc<ProcDef>>,                   //The running procedure, so it can call itself
+}
+impl Frame {
+    fn new() -> Frame {
+        Frame {
+            vars: HashMap::new(),
+            procs: HashMap::new(),
+            outerProcs: Rc::new(HashMap::new()),
+            current: None,
+        }
+    }
+
+    //Finds a procedure, the ones declared here first, then the running procedure, then the outer ones
+    fn findProc(&self, procName: &String) -> Option<Rc<ProcDef>> {
+        if let Some(procDef) = self.procs.get(procName) {
+            return Some(procDef.clone());
+        }
+        if let Some(procDef) = &self.current {
+            if &procDef.name == procName {
+                return Some(procDef.clone());
+            }
+        }
+        return self.outerProcs.get(procName).cloned();
+    }
+
+    //All of the procedures visible right now, given to a procedure when it is declared
+    fn visibleProcs(&self) -> HashMap<String, Rc<ProcDef>> {
+        let mut visible = (*self.outerProcs).clone();
+        if let Some(procDef) = &self.current {
+            visible.insert(procDef.name.clone(), procDef.clone());
+        }
+        for (procName, procDef) in self.procs.iter() {
+            visible.insert(procName.clone(), procDef.clone());
+        }
+        return visible;
+    }
+}
+
+//The master struct for the interpreter
+pub struct Interpreter {
+    programAst: Stmt,                   //The checked program AST
+    globals: HashMap<String, Value>,    //The global variables
+    callDepth: usize,                   //The current procedure call depth
+    externs: HashMap<String, VarType>,  //The external procedures that were declared, they can't be called here
+    curLine: String,                    //The line of the statement being run, used for runtime errors
+    exitStatus: Option<i32>,            //Set when the program calls exit, the run unwinds with an Err and ends with this status
+    pub reports: Reporting,             //The reporting structure, runtime errors are reported here
+    pub numberWidth: NumberWidth,       //The size of integer and float, the results are wrapped and rounded to it
+    pub checkedArith: bool,             //Integer overflow is a runtime error instead of wrapping (--checked-arith)
+}
+
+impl Interpreter {
+    //The constructor
+    pub fn new(programAst: Stmt) -> Interpreter {
+        Interpreter {
+            programAst,
+            globals: HashMap::new(),
+            callDepth: 0,
+            externs: HashMap::new(),
+            curLine: "0".to_string(),
+            exitStatus: None,
+            reports: Reporting::new(),
+            numberWidth: NumberWidth::Bits32,
+            checkedArith: false,
+        }
+    }
+
+    //Runs the program, returns the exit status of the program
+    //If there is a runtime error it is put in the reports and Err is returned
+    pub fn runProgram(&mut self) -> Result<i32, String> {
+        let mut result = self.runMain();
+        //The put builtins print through funcLib's buffer, a failed write at the end is a runtime error like in compiled code
+        if let Err(errMsg) = funcLib::flushOutput() {
+            if result.is_ok() || self.exitStatus.is_some() {
+                self.exitStatus = None;
+                result = Err(errMsg);
+            }
+        }
+        if let Some(status) = self.exitStatus {
+            return Ok(status);
+        }
+        match result {
+            Ok(status) => {
+                return Ok(status);
+            }
+            Err(errMsg) => {
+                let fullMsg = funcLib::errorMessage(&errMsg, self.curLine.parse::<i32>().unwrap_or(0));
+                self.reports.reportError(fullMsg.clone());
+                return Err(fullMsg);
+            }
+        }
+    }
+
+    //Runs the header and body of the program
+    fn runMain(&mut self) -> Result<i32, String> {
+        let (header, body) = match self.programAst.clone() {
+            Stmt::Program(name, header, body, lineNum, ..) => (*header, *body),
+            _ => {
+                return Err("Interpreter must be passed a Program AST".to_string());
+            }
+        };
+
+        let mut mainFrame = Frame::new();
+        let headerFlow = self.execStmt(&header, &mut mainFrame)?;
+        if let Flow::Return(value) = headerFlow {
+            return Ok(Interpreter::exitStatus(value));
+        }
+        match self.execStmt(&body, &mut mainFrame)? {
+            Flow::Return(value) => {
+                return Ok(Interpreter::exitStatus(value));
+            }
+            Flow::Normal => {
+                return Ok(0);
+            }
+        }
+    }
+
+    //A return in the main program ends it, an integer return value becomes the exit status
+    fn exitStatus(value: Option<Value>) -> i32 {
+        match value {
+            Some(Value::Int(status)) => status as i32,
+            Some(Value::Bool(status)) => status as i32,
+            _ => 0,
+        }
+    }
+
+    //Runs one statement
+    fn execStmt(&mut self, stmt: &Stmt, frame: &mut Frame) -> Result<Flow, String> {
+        match stmt {
+            Stmt::VarDecl(varName, varType, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                frame.vars.insert(varName.clone(), Value::default(varType));
+                return Ok(Flow::Normal);
+            }
+            Stmt::GlobVarDecl(varName, varType, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                self.globals.insert(varName.clone(), Value::default(varType));
+                return Ok(Flow::Normal);
+            }
+            Stmt::ProcDecl(retType, procName, params, header, body, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                //Gets the parameter names and types
+                let mut paramList: Vec<(String, VarType)> = Vec::new();
+                match &**params {
+                    Stmt::Block(paramStmts, _, ..) => {
+                        for param in paramStmts {
+                            if let Stmt::VarDecl(paramName, paramType, _, ..) = param {
+                                paramList.push((paramName.clone(), paramType.clone()));
+                            } else {
+                                return Err(format!("Parameters of procedure {} must be variable declarations", procName));
+                            }
+                        }
+                    }
+                    Stmt::VarDecl(paramName, paramType, _, ..) => {
+                        paramList.push((paramName.clone(), paramType.clone()));
+                    }
+                    _ => {
+                        return Err(format!("Parameters of procedure {} must be variable declarations", procName));
+                    }
+                }
+
+                let procDef = ProcDef {
+                    name: procName.clone(),
+                    retType: retType.clone(),
+                    params: paramList,
+                    header: *header.clone(),
+                    body: *body.clone(),
+                    visibleProcs: Rc::new(frame.visibleProcs()),
+                };
+                frame.procs.insert(procName.clone(), Rc::new(procDef));
+                return Ok(Flow::Normal);
+            }
+            Stmt::Assign(target, newValue, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                let value = self.evalExpr(newValue, frame)?;
+                self.assign(target, value, frame)?;
+                return Ok(Flow::Normal);
+            }
+            Stmt::Block(stmts, lineNum, ..) => {
+                for instr in stmts {
+                    let flow = self.execStmt(instr, frame)?;
+                    if let Flow::Return(_) = flow {
+                        return Ok(flow);
+                    }
+                }
+                return Ok(Flow::Normal);
+            }
+            Stmt::Expr(expr, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                let _ = self.evalExpr(expr, frame)?;
+                return Ok(Flow::Normal);
+            }
+            Stmt::If(condExpr, body, elseBody, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                let condVal = self.evalExpr(condExpr, frame)?;
+                if Interpreter::isTrue(&condVal)? {
+                    return self.execStmt(body, frame);
+                } else if let Some(elseStmt) = elseBody {
+                    return self.execStmt(elseStmt, frame);
+                }
+                return Ok(Flow::Normal);
+            }
+            Stmt::For(assignment, condExpr, body, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                //Runs the loop assignment once
+                let _ = self.execStmt(&**assignment, frame)?;
+                loop {
+                    self.curLine.clone_from(lineNum);
+                    let condVal = self.evalExpr(condExpr, frame)?;
+                    if !Interpreter::isTrue(&condVal)? {
+                        break;
+                    }
+                    let flow = self.execStmt(body, frame)?;
+                    if let Flow::Return(_) = flow {
+                        return Ok(flow);
+                    }
+                }
+                return Ok(Flow::Normal);
+            }
+            Stmt::Return(retExpr, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                //The parser uses an empty variable reference for a return without a value
+                if let Expr::VarRef(varName, ..) = retExpr {
+                    if varName == "" {
+                        return Ok(Flow::Return(None));
+                    }
+                }
+                let value = self.evalExpr(retExpr, frame)?;
+                return Ok(Flow::Return(Some(value)));
+            }
+            Stmt::StringLiteral(_, _, ..) => {
+                return Ok(Flow::Normal);
+            }
+            Stmt::Error(reporting, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                return Err(format!("Error statement reached the interpreter: {}", reporting));
+            }
+            Stmt::Program(_, _, _, _, ..) => {
+                return Err("Nested program statement".to_string());
+            }
+            //The imported modules are merged into the program before it is run (resolver::mergeUnits)
+            Stmt::Import(_, _, ..) => {
+                return Ok(Flow::Normal);
+            }
+            Stmt::Module(_, _, _, ..) => {
+                return Err("Nested module statement".to_string());
+            }
+            Stmt::ExternProcDecl(retType, procName, _, lineNum, ..) => {
+                self.curLine.clone_from(lineNum);
+                self.externs.insert(procName.clone(), retType.clone());
+                return Ok(Flow::Normal);
+            }
+        }
+    }
+
+    //Stores a value into a variable or array element, converting it to the variable's type
+    fn assign(&mut self, target: &Expr, value: Value, frame: &mut Frame) -> Result<(), String> {
+        match target {
+            Expr::VarRef(varName, ..) => {
+                let width = self.numberWidth;
+                let slot = self.lookupMut(varName, frame)?;
+                let converted = Interpreter::convertTo(width, slot, value)?;
+                *slot = converted;
+                return Ok(());
+            }
+            Expr::ArrayRef(arrName, indexExpr, ..) => {
+                let indexVal = self.evalExpr(indexExpr, frame)?;
+                let index = Interpreter::toInt(&indexVal)?;
+                let newInt = self.numberWidth.fitInt(Interpreter::toInt(&value)?);
+                let slot = self.lookupMut(arrName, frame)?;
+                match slot {
+                    Value::IntArray(vals) => {
+                        if index < 0 || index as usize >= vals.len() {
+                            return Err(format!("Index {} is out of bounds for array {} of size {}", index, arrName, vals.len()));
+                        }
+                        vals[index as usize] = newInt;
+                        return Ok(());
+                    }
+                    _ => {
+                        return Err(format!("Variable {} is not an array", arrName));
+                    }
+                }
+            }
+            _ => {
+                return Err("Cannot assign to a non variable".to_string());
+            }
+        }
+    }
+
+    //Finds a variable, locals first then globals
+    fn lookupMut<'f>(&'f mut self, varName: &String, frame: &'f mut Frame) -> Result<&'f mut Value, String> {
+        if let Some(value) = frame.vars.get_mut(varName) {
+            return Ok(value);
+        }
+        match self.globals.get_mut(varName) {
+            Some(value) => {
+                return Ok(value);
+            }
+            None => {
+                return Err(format!("Variable {} is not defined", varName));
+            }
+        }
+    }
+
+    //Finds a variable's value, locals first then globals
+    fn lookup<'f>(&'f self, varName: &String, frame: &'f Frame) -> Result<&'f Value, String> {
+        if let Some(value) = frame.vars.get(varName) {
+            return Ok(value);
+        }
+        match self.globals.get(varName) {
+            Some(value) => {
+                return Ok(value);
+            }
+            None => {
+                return Err(format!("Variable {} is not defined", varName));
+            }
+        }
+    }
+
+    //Evaluates an expression
+    fn evalExpr(&mut self, expr: &Expr, frame: &mut Frame) -> Result<Value, String> {
+        match expr {
+            Expr::IntLiteral(val, ..) => {
+                return Ok(Value::Int(*val));
+            }
+            Expr::FloatLiteral(val, ..) => {
+                return Ok(Value::Float(*val));
+            }
+            Expr::StringLiteral(val, ..) => {
+                return Ok(Value::string(val.clone()));
+            }
+            Expr::BoolLiteral(val, ..) => {
+                return Ok(Value::Bool(*val));
+            }
+            Expr::IntArrayLiteral(size, vals, ..) => {
+                return Ok(Value::IntArray(vals.iter().map(|v| self.numberWidth.fitInt(*v)).collect()));
+            }
+            Expr::VarRef(varName, ..) => {
+                return Ok(self.lookup(varName, frame)?.clone());
+            }
+            Expr::ArrayRef(arrName, indexExpr, ..) => {
+                let indexVal = self.evalExpr(indexExpr, frame)?;
+                let index = Interpreter::toInt(&indexVal)?;
+                //Only the element is copied, not the whole array
+                match self.lookup(arrName, frame)? {
+                    Value::IntArray(vals) => {
+                        if index < 0 || index as usize >= vals.len() {
+                            return Err(format!("Index {} is out of bounds for array {} of size {}", index, arrName, vals.len()));
+                        }
+                        return Ok(Value::Int(vals[index as usize]));
+                    }
+                    _ => {
+                        return Err(format!("Variable {} is not an array", arrName));
+                    }
+                }
+            }
+            Expr::ArthOp(op1, op, op2, ..) => {
+                let val1 = self.evalExpr(op1, frame)?;
+                let val2 = self.evalExpr(op2, frame)?;
+                return self.arthOp(&val1, op, &val2);
+            }
+            Expr::RelOp(op1, op, op2, ..) => {
+                let val1 = self.evalExpr(op1, frame)?;
+                let val2 = self.evalExpr(op2, frame)?;
+                return self.relOp(&val1, op, &val2);
+            }
+            Expr::LogOp(op1, op, op2, ..) => {
+                let val1 = self.evalExpr(op1, frame)?;
+                let val2 = self.evalExpr(op2, frame)?;
+                return Interpreter::logOp(&val1, op, &val2);
+            }
+            Expr::ProcRef(procName, params, ..) => {
+                //Evaluates the arguments first
+                let mut args: Vec<Value> = Vec::new();
+                if let Some(paramExprs) = params {
+                    for param in paramExprs {
+                        args.push(self.evalExpr(param, frame)?);
+                    }
+                }
+
+                //A procedure declared in the program wins over a builtin of the same name
+                let procCheck = frame.findProc(procName);
+                match procCheck {
+                    Some(procDef) => {
+                        return self.callProc(procDef, args);
+                    }
+                    None => {
+                        //There is no C code to call without the llvm backend
+                        if self.externs.contains_key(procName) {
+                            return Err(format!("External procedure {} can only be called from compiled code, use --backend=llvm", procName));
+                        }
+                        return self.callBuiltin(procName, args);
+                    }
+                }
+            }
+        }
+    }
+
+    //Calls a procedure declared in the program
+    fn callProc(&mut self, procDef: Rc<ProcDef>, args: Vec<Value>) -> Result<Value, String> {
+        if args.len() != procDef.params.len() {
+            return Err(format!("Procedure {} takes {} parameters but {} were given", procDef.name, procDef.params.len(), args.len()));
+        }
+        if self.callDepth >= MAX_CALL_DEPTH {
+            return Err(format!("Stack overflow, procedure calls nested deeper than {} in {}", MAX_CALL_DEPTH, procDef.name));
+        }
+
+        //Sets up the new frame with the parameters and the procedures it can see (including itself for recursion)
+        let mut procFrame = Frame::new();
+        procFrame.outerProcs = procDef.visibleProcs.clone();
+        procFrame.current = Some(procDef.clone());
+        for ((paramName, paramType), arg) in procDef.params.iter().zip(args.into_iter()) {
+            let converted = Interpreter::convertTo(self.numberWidth, &Value::default(paramType), arg)?;
+            procFrame.vars.insert(paramName.clone(), converted);
+        }
+
+        let callerLine = self.curLine.clone();
+        self.callDepth += 1;
+        let headerFlow = self.execStmt(&procDef.header, &mut procFrame);
+        let flow = match headerFlow {
+            Ok(Flow::Normal) => self.execStmt(&procDef.body, &mut procFrame),
+            other => other,
+        };
+        self.callDepth -= 1;
+        let flow = flow?;
+        self.curLine = callerLine;
+
+        //Converts the return value to the procedure's type, a procedure that falls off the end returns the zero value
+        let retDefault = Value::default(&procDef.retType);
+        match flow {
+            Flow::Return(Some(value)) => {
+                return Interpreter::convertTo(self.numberWidth, &retDefault, value);
+            }
+            _ => {
+                return Ok(retDefault);
+            }
+        }
+    }
+
+    //Calls one of the built in procedures, these behave like the ones in funcLib
+    //The ones that don't do I/O call funcLib (or its string helpers) so both backends give the same results
+    //With wide numbers they call the funcLib functions ending in 64, like the compiled code does
+    fn callBuiltin(&mut self, procName: &String, args: Vec<Value>) -> Result<Value, String> {
+        let wide = self.numberWidth == NumberWidth::Bits64;
+        match procName.as_str() {
+            "putinteger" => {
+                let val = Interpreter::toInt(Interpreter::onlyArg(procName, &args)?)?;
+                return Ok(Value::Bool(funcLib::writeLine(&val.to_string())));
+            }
+            "putfloat" => {
+                let val = Interpreter::toFloat(Interpreter::onlyArg(procName, &args)?)?;
+                return Ok(Value::Bool(funcLib::writeLine(&self.floatText(val))));
+            }
+            "putbool" => {
+                let val = Interpreter::isTrue(Interpreter::onlyArg(procName, &args)?)?;
+                return Ok(Value::Bool(funcLib::writeLine(&val.to_string())));
+            }
+            "putstring" => {
+                let val = Interpreter::onlyArg(procName, &args)?;
+                return Ok(Value::Bool(funcLib::writeLine(&val.to_string())));
+            }
+            //Bad input is handled by funcLib like in compiled code, as rt_setup said (--bad-input)
+            "getinteger" => {
+                let width = self.numberWidth;
+                let parse = |text: &str| text.trim().parse::<i64>().ok().filter(|val| width.fitInt(*val) == *val);
+                return Ok(Value::Int(funcLib::readInput("getinteger", "an integer", parse, 0)?));
+            }
+            "getfloat" => {
+                let parse = |text: &str| match wide {
+                    true => text.trim().parse::<f64>().ok(),
+                    false => text.trim().parse::<f32>().ok().map(|val| val as f64),
+                };
+                return Ok(Value::Float(funcLib::readInput("getfloat", "a float", parse, 0.0)?));
+            }
+            "getbool" => {
+                return Ok(Value::Bool(funcLib::readInput("getbool", "a bool", |text| text.trim().parse::<bool>().ok(), false)?));
+            }
+            "getstring" => {
+                let line = funcLib::readInput("getstring", "a string", |text| Some(text.to_string()), String::new())?;
+                return Ok(Value::string(line));
+            }
+            "sqrt" => {
+                let val = Interpreter::toInt(Interpreter::onlyArg(procName, &args)?)?;
+                if wide {
+                    return Ok(Value::Float(funcLib::rt_sqrt64(val)));
+                }
+                return Ok(Value::Float(funcLib::rt_sqrt(val as i32) as f64));
+            }
+            "abs" | "floor" | "sin" | "cos" | "exp" | "log" => {
+                let val = Interpreter::toFloat(Interpreter::onlyArg(procName, &args)?)?;
+                if wide {
+                    let mathFn: extern "C" fn(f64) -> f64 = match procName.as_str() {
+                        "abs" => funcLib::rt_abs64,
+                        "floor" => funcLib::rt_floor64,
+                        "sin" => funcLib::rt_sin64,
+                        "cos" => funcLib::rt_cos64,
+                        "exp" => funcLib::rt_exp64,
+                        _ => funcLib::rt_log64,
+                    };
+                    return Ok(Value::Float(mathFn(val)));
+                }
+                let mathFn: extern "C" fn(f32) -> f32 = match procName.as_str() {
+                    "abs" => funcLib::rt_abs,
+                    "floor" => funcLib::rt_floor,
+                    "sin" => funcLib::rt_sin,
+                    "cos" => funcLib::rt_cos,
+                    "exp" => funcLib::rt_exp,
+                    _ => funcLib::rt_log,
+                };
+                return Ok(Value::Float(mathFn(val as f32) as f64));
+            }
+            "pow" => {
+                Interpreter::argCount(procName, &args, 2)?;
+                let (base, exponent) = (Interpreter::toFloat(&args[0])?, Interpreter::toFloat(&args[1])?);
+                if wide {
+                    return Ok(Value::Float(funcLib::rt_pow64(base, exponent)));
+                }
+                return Ok(Value::Float(funcLib::rt_pow(base as f32, exponent as f32) as f64));
+            }
+            "stringlength" => {
+                match Interpreter::onlyArg(procName, &args)? {
+                    Value::Str(_, chars) => return Ok(Value::Int(*chars)),
+                    other => return Err(format!("Expected a string but found '{}'", other)),
+                }
+            }
+            "concat" => {
+                Interpreter::argCount(procName, &args, 2)?;
+                return Ok(Value::string(funcLib::concatStrings(&Interpreter::toStr(&args[0])?, &Interpreter::toStr(&args[1])?)));
+            }
+            "substring" => {
+                Interpreter::argCount(procName, &args, 3)?;
+                let val = Interpreter::toStr(&args[0])?;
+                return Ok(Value::string(funcLib::substring(&val, Interpreter::toInt(&args[1])?, Interpreter::toInt(&args[2])?)));
+            }
+            "stringcompare" => {
+                Interpreter::argCount(procName, &args, 2)?;
+                return Ok(Value::Int(funcLib::compareStrings(&Interpreter::toStr(&args[0])?, &Interpreter::toStr(&args[1])?) as i64));
+            }
+            "inttostring" => {
+                let val = Interpreter::toInt(Interpreter::onlyArg(procName, &args)?)?;
+                return Ok(Value::string(val.to_string()));
+            }
+            "floattostring" => {
+                let val = Interpreter::toFloat(Interpreter::onlyArg(procName, &args)?)?;
+                return Ok(Value::string(self.floatText(val)));
+            }
+            "stringtoint" => {
+                let val = Interpreter::toStr(Interpreter::onlyArg(procName, &args)?)?;
+                if wide {
+                    return Ok(Value::Int(funcLib::stringToInt64(&val)));
+                }
+                return Ok(Value::Int(funcLib::stringToInt(&val) as i64));
+            }
+            "stringtofloat" => {
+                let val = Interpreter::toStr(Interpreter::onlyArg(procName, &args)?)?;
+                if wide {
+                    return Ok(Value::Float(funcLib::stringToFloat64(&val)));
+                }
+                return Ok(Value::Float(funcLib::stringToFloat(&val) as f64));
+            }
+            "seedrandom" => {
+                let seed = Interpreter::toInt(Interpreter::onlyArg(procName, &args)?)?;
+                //A narrow seed or limit fits in an i32, the 64 bit functions give the same numbers for it
+                return Ok(Value::Bool(funcLib::rt_seedrandom64(seed)));
+            }
+            "random" => {
+                let limit = Interpreter::toInt(Interpreter::onlyArg(procName, &args)?)?;
+                return Ok(Value::Int(funcLib::rt_random64(limit)));
+            }
+            "time" => {
+                Interpreter::argCount(procName, &args, 0)?;
+                return Ok(Value::Int(self.numberWidth.fitInt(funcLib::rt_time64())));
+            }
+            "exit" => {
+                let code = Interpreter::toInt(Interpreter::onlyArg(procName, &args)?)? as i32;
+                self.exitStatus = Some(code);
+                return Err(format!("exit({})", code));
+            }
+            _ => {
+                return Err(format!("Procedure {} is not defined", procName));
+            }
+        }
+    }
+
+    //The text putfloat and floattostring give for a float, a narrow float prints like the f32 it is
+    fn floatText(&self, val: f64) -> String {
+        match self.numberWidth {
+            NumberWidth::Bits32 => return funcLib::formatFloat(val as f32),
+            NumberWidth::Bits64 => return funcLib::formatFloat64(val),
+        }
+    }
+
+    //Gets the single argument of a builtin
+    fn onlyArg<'v>(procName: &String, args: &'v Vec<Value>) -> Result<&'v Value, String> {
+        if args.len() != 1 {
+            return Err(format!("{} takes 1 parameter but {} were given", procName, args.len()));
+        }
+        return Ok(&args[0]);
+    }
+
+    //Checks the number of arguments a builtin was given
+    fn argCount(procName: &String, args: &Vec<Value>, count: usize) -> Result<(), String> {
+        if args.len() != count {
+            return Err(format!("{} takes {} parameters but {} were given", procName, count, args.len()));
+        }
+        return Ok(());
+    }
+
+    //Gets the characters of a string value
+    fn toStr(value: &Value) -> Result<String, String> {
+        match value {
+            Value::Str(val, _) => Ok(val.clone()),
+            _ => Err(format!("Expected a string but found '{}'", value)),
+        }
+    }
+
+    //Converts a value to an int, floats are truncated like fptosi
+    fn toInt(value: &Value) -> Result<i64, String> {
+        match value {
+            Value::Int(val) => Ok(*val),
+            Value::Float(val) => Ok(*val as i64),
+            Value::Bool(val) => Ok(*val as i64),
+            _ => Err(format!("Expected a number but found '{}'", value)),
+        }
+    }
+
+    //Converts a value to a float
+    fn toFloat(value: &Value) -> Result<f64, String> {
+        match value {
+            Value::Int(val) => Ok(*val as f64),
+            Value::Float(val) => Ok(*val),
+            Value::Bool(val) => Ok(*val as i64 as f64),
+            _ => Err(format!("Expected a number but found '{}'", value)),
+        }
+    }
+
+    //Checks if a condition value is true, anything that is not zero is true
+    fn isTrue(value: &Value) -> Result<bool, String> {
+        match value {
+            Value::Bool(val) => Ok(*val),
+            Value::Int(val) => Ok(*val != 0),
+            Value::Float(val) => Ok(*val != 0.0),
+            _ => Err(format!("Expected a condition but found '{}'", value)),
+        }
+    }
+
+    //Converts a value to the type of an existing value, used for assignment, parameters and returns
+    fn convertTo(width: NumberWidth, target: &Value, value: Value) -> Result<Value, String> {
+        match target {
+            Value::Int(_) => Ok(Value::Int(width.fitInt(Interpreter::toInt(&value)?))),
+            Value::Float(_) => Ok(Value::Float(width.fitFloat(Interpreter::toFloat(&value)?))),
+            Value::Bool(_) => Ok(Value::Bool(Interpreter::isTrue(&value)?)),
+            Value::Str(..) => match value {
+                Value::Str(val, chars) => Ok(Value::Str(val, chars)),
+                other => Err(format!("Cannot assign '{}' to a string", other)),
+            },
+            Value::IntArray(targetVals) => match value {
+                Value::IntArray(vals) => {
+                    if vals.len() != targetVals.len() {
+                        return Err(format!("Cannot assign an array of size {} to an array of size {}", vals.len(), targetVals.len()));
+                    }
+                    Ok(Value::IntArray(vals))
+                }
+                other => Err(format!("Cannot assign '{}' to an array", other)),
+            },
+        }
+    }
+
+    //Checks if either operand is a float, which makes the operation a float operation
+    fn isFloatOp(val1: &Value, val2: &Value) -> bool {
+        return matches!(val1, Value::Float(_)) || matches!(val2, Value::Float(_));
+    }
+
+    //Runs an arithmetic operation, the result is wrapped or rounded to the number width
+    //An integer operand of a float operation is rounded to a float first, like sitofp
+    fn arthOp(&self, val1: &Value, op: &Operator, val2: &Value) -> Result<Value, String> {
+        let width = self.numberWidth;
+        if Interpreter::isFloatOp(val1, val2) {
+            let f1 = width.fitFloat(Interpreter::toFloat(val1)?);
+            let f2 = width.fitFloat(Interpreter::toFloat(val2)?);
+            match op {
+                Operator::Add => Ok(Value::Float(width.fitFloat(f1 + f2))),
+                Operator::Sub => Ok(Value::Float(width.fitFloat(f1 - f2))),
+                Operator::Mul => Ok(Value::Float(width.fitFloat(f1 * f2))),
+                Operator::Div => Ok(Value::Float(width.fitFloat(f1 / f2))),
+                _ => Err(format!("Improper operator {} for arithmetic operation", op)),
+            }
+        } else {
+            //The exact result is worked out in an i128, a result the width can't hold wraps unless the arithmetic is checked
+            let i1 = Interpreter::toInt(val1)?;
+            let i2 = Interpreter::toInt(val2)?;
+            let exact = match op {
+                Operator::Add => i1 as i128 + i2 as i128,
+                Operator::Sub => i1 as i128 - i2 as i128,
+                Operator::Mul => i1 as i128 * i2 as i128,
+                Operator::Div => {
+                    if i2 == 0 {
+                        return Err(funcLib::arithFailure("/", i1, i2));
+                    }
+                    i1 as i128 / i2 as i128
+                }
+                _ => {
+                    return Err(format!("Improper operator {} for arithmetic operation", op));
+                }
+            };
+            let wrapped = width.fitInt(exact as i64);
+            if self.checkedArith && wrapped as i128 != exact {
+                return Err(funcLib::arithFailure(&op.to_string(), i1, i2));
+            }
+            Ok(Value::Int(wrapped))
+        }
+    }
+
+    //Runs a relational operation, strings are compared by their contents
+    fn relOp(&self, val1: &Value, op: &Operator, val2: &Value) -> Result<Value, String> {
+        if let (Value::Str(s1, _), Value::Str(s2, _)) = (val1, val2) {
+            match op {
+                Operator::Check_Equal => return Ok(Value::Bool(s1 == s2)),
+                Operator::Not_Equals => return Ok(Value::Bool(s1 != s2)),
+                _ => return Err(format!("Operator {} cannot be used on strings", op)),
+            }
+        }
+        let result = if Interpreter::isFloatOp(val1, val2) {
+            let f1 = self.numberWidth.fitFloat(Interpreter::toFloat(val1)?);
+            let f2 = self.numberWidth.fitFloat(Interpreter::toFloat(val2)?);
+            match op {
+                Operator::Greater => f1 > f2,
+                Operator::Greater_Equal => f1 >= f2,
+                Operator::Less => f1 < f2,
+                Operator::Less_Equal => f1 <= f2,
+                Operator::Check_Equal => f1 == f2,
+                Operator::Not_Equals => f1 != f2,
+                _ => return Err(format!("Improper operator {} for relational operation", op)),
+            }
+        } else {
+            let i1 = Interpreter::toInt(val1)?;
+            let i2 = Interpreter::toInt(val2)?;
+            match op {
+                Operator::Greater => i1 > i2,
+                Operator::Greater_Equal => i1 >= i2,
+                Operator::Less => i1 < i2,
+                Operator::Less_Equal => i1 <= i2,
+                Operator::Check_Equal => i1 == i2,
+                Operator::Not_Equals => i1 != i2,
+                _ => return Err(format!("Improper operator {} for relational operation", op)),
+            }
+        };
+        return Ok(Value::Bool(result));
+    }
+
+    //Runs a logical operation, these are bitwise on integers like the LLVM and/or
+    fn logOp(val1: &Value, op: &Operator, val2: &Value) -> Result<Value, String> {
+        if let (Value::Bool(b1), Value::Bool(b2)) = (val1, val2) {
+            match op {
+                Operator::And => return Ok(Value::Bool(*b1 && *b2)),
+                Operator::Or => return Ok(Value::Bool(*b1 || *b2)),
+                _ => return Err(format!("Improper operator {} for logical operation", op)),
+            }
+        }
+        let i1 = Interpreter::toInt(val1)?;
+        let i2 = Interpreter::toInt(val2)?;
+        match op {
+            Operator::And => Ok(Value::Int(i1 & i2)),
+            Operator::Or => Ok(Value::Int(i1 | i2)),
+            _ => Err(format!("Improper operator {} for logical operation", op)),
+        }
+    }
+}
+
+///////////////////////// /INTERPRETER SECTION /////////////////////////
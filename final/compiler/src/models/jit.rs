@@ -0,0 +1,150 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    std::{
+        ffi::{c_char, c_int, c_void, CStr, CString},
+        path::Path,
+    },
+    crate::models::builtins::runtimeAddresses,
+    inkwell::{
+        execution_engine::{ExecutionEngine, JitFunction},
+        module::Module,
+        targets::{InitializationConfig, Target},
+        OptimizationLevel,
+    },
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// JIT SECTION /////////////////////////
+//This section runs a compiled module in-process with the LLVM execution engine
+//The builtins are mapped to the funcLib functions that are linked into the compiler, so no clang or linker is needed
+
+//The signature of the generated main function
+type MainFunc = unsafe extern "C" fn() -> i32;
+
+//The dynamic loader, an external procedure is looked up in the compiler's process and the -l libraries
+extern "C" {
+    fn dlopen(fileName: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlerror() -> *mut c_char;
+}
+
+//The glibc values, the linker only targets x86_64 Linux too
+const RTLD_DEFAULT: *mut c_void = std::ptr::null_mut();
+const RTLD_NOW: c_int = 2;
+const RTLD_GLOBAL: c_int = 0x100;
+
+//JIT compiles the module and runs its main function, returns the exit code of the program
+//The -l libraries are loaded into the process so their functions can be called like they are in a linked executable
+pub fn runJit(module: &Module, optLevel: OptimizationLevel, libDirs: &Vec<String>, libs: &Vec<String>) -> Result<i32, String> {
+    //The JIT needs the native target to be set up
+    let initCheck = Target::initialize_native(&InitializationConfig::default());
+    if let Err(errMsg) = initCheck {
+        return Err(format!("Error initializing native target: {}", errMsg));
+    }
+
+    //Creates the execution engine
+    let engine: ExecutionEngine;
+    match module.create_jit_execution_engine(optLevel) {
+        Ok(ee) => {
+            engine = ee;
+        }
+        Err(errMsg) => {
+            return Err(format!("Error creating JIT execution engine: {}", errMsg.to_string()));
+        }
+    }
+
+    //Maps every builtin that the module declares to the function in funcLib
+    let builtinAddresses = runtimeAddresses();
+    for (name, address) in builtinAddresses.iter() {
+        if let Some(func) = module.get_function(name) {
+            //Only declarations are mapped, a procedure in the program with the same name keeps its body
+            if func.count_basic_blocks() == 0 {
+                engine.add_global_mapping(&func, *address);
+            }
+        }
+    }
+
+    for lib in libs.iter() {
+        loadLibrary(lib, libDirs)?;
+    }
+
+    //The other declarations are external procedures, they are found in libc, libm, the -l libraries or anything else loaded
+    //LLVM intrinsics (llvm.dbg.declare from -g, llvm.sadd.with.overflow from --checked-arith) are lowered by LLVM itself
+    for func in module.get_functions() {
+        if func.count_basic_blocks() == 0 {
+            let funcName = func.get_name().to_string_lossy().to_string();
+            let isMapped = builtinAddresses.iter().any(|(name, _)| *name == funcName);
+            if isMapped || funcName.starts_with("llvm.") {
+                continue;
+            }
+            match findSymbol(&funcName) {
+                Some(address) => {
+                    engine.add_global_mapping(&func, address);
+                }
+                None => {
+                    return Err(format!("Function '{}' is declared but wasn't found in the runtime, the C library or the -l libraries", funcName));
+                }
+            }
+        }
+    }
+
+    //Finds and runs main
+    let mainFunc: JitFunction<MainFunc>;
+    match unsafe { engine.get_function::<MainFunc>("main") } {
+        Ok(func) => {
+            mainFunc = func;
+        }
+        Err(errMsg) => {
+            return Err(format!("Error finding main in the JIT module: {:?}", errMsg));
+        }
+    }
+
+    let exitCode = unsafe { mainFunc.call() };
+    return Ok(exitCode);
+}
+
+//Loads lib<name>.so from the -L directories, or from where the dynamic loader looks
+fn loadLibrary(name: &str, libDirs: &Vec<String>) -> Result<(), String> {
+    let fileName = format!("lib{}.so", name);
+    let mut candidates: Vec<String> = libDirs.iter().map(|dir| Path::new(dir).join(&fileName)).filter(|path| path.is_file()).map(|path| path.display().to_string()).collect();
+    candidates.push(fileName.clone());
+    for candidate in candidates.iter() {
+        let path = CString::new(candidate.as_str()).map_err(|err| format!("Bad library name {}: {}", candidate, err))?;
+        //RTLD_GLOBAL so dlsym on the whole process finds its functions
+        let handle = unsafe { dlopen(path.as_ptr(), RTLD_NOW | RTLD_GLOBAL) };
+        if !handle.is_null() {
+            return Ok(());
+        }
+    }
+    let reason = unsafe {
+        let errMsg = dlerror();
+        if errMsg.is_null() { "not found".to_string() } else { CStr::from_ptr(errMsg).to_string_lossy().to_string() }
+    };
+    return Err(format!("Could not load -l{} for run: {}", name, reason));
+}
+
+//The address of a C function in the process, None if nothing loaded has it
+fn findSymbol(name: &str) -> Option<usize> {
+    let symbol = CString::new(name).ok()?;
+    let address = unsafe { dlsym(RTLD_DEFAULT, symbol.as_ptr()) };
+    if address.is_null() {
+        return None;
+    }
+    return Some(address as usize);
+}
+
+///////////////////////// /JIT SECTION /////////////////////////
@@ -29,6 +29,7 @@ use {
     utf8_chars::BufReadCharsExt,
     crate::tokenTypeEnum,
     crate::models::reporting::*,
+    serde::{Serialize, Serializer},
     std::io::prelude::*,
 
 };
@@ -48,6 +49,8 @@ pub struct Lexer {
     pub symTab: tokenTable,     //The table of tokens, seeded with keywords
     pub tokenList: Vec<Token>,  //The list of the tokens that the lexer processes. This is the output of the lexer
     pub reports: Reporting,     //This is a reporting structure, used to report errors and stuff
+    pub keepTrivia: bool,       //Keeps the whitespace and the comments as tokens, used to build the concrete syntax tree
+    pub rawTokens: Vec<Token>,  //Every token as scanned (before the second pass) with the whitespace and comments, when keepTrivia is set
 }
 
 //This is where all of the methods of the lexer struct are defined
@@ -69,19 +72,54 @@ impl Lexer{
             symTab: symTable,
             tokenList: Vec::new(),
             reports: report,
+            keepTrivia: false,
+            rawTokens: Vec::new(),
         }
     }
     
+    //Creates a lexer over source that is already in memory instead of reading a file
+    pub fn newFromString(fileName: &str, contents: String) -> Lexer {
+        Lexer {
+            inputFile: inFile::newFromString(fileName, contents),
+            symTab: tokenTable::new(),
+            tokenList: Vec::new(),
+            reports: Reporting::new(),
+            keepTrivia: false,
+            rawTokens: Vec::new(),
+        }
+    }
+
     //The main function of the lexer
     //Returns one Token
     fn scan(&mut self) -> Token{
         //Gets the next character
         let mut currChar = self.inputFile.getChar();
 
+        //When trivia is kept, a run of filler characters is returned as one whitespace token
+        if self.keepTrivia && currChar.map(isFiller).unwrap_or(false) {
+            self.inputFile.markTokenStart();
+            let whitespaceLine = self.inputFile.lineCnt;
+            let mut whitespace: String = "".to_string();
+            while let Some(c) = currChar {
+                if isFiller(c) {
+                    whitespace.push(c);
+                    if c == '\n' {
+                        self.inputFile.incLineCnt();
+                    }
+                    currChar = self.inputFile.getChar();
+                } else {
+                    self.inputFile.unGetChar();
+                    break;
+                }
+            }
+            let newToken = Token::new(crate::tokenTypeEnum::WHITESPACE, whitespace, whitespaceLine.to_string(), tokenGroup::OTHER);
+            return newToken;
+        }
+
         //Looks for the filler characters and removes them
         while let Some(c) = currChar {            
             //Defines the filler characters, finds them, passes them or marks whatever is necessary
-            if c == '\n' || c == '\t' || c == '\r' || c == ' ' || c == '\u{0009}' {
+            if isFiller(c) {
                 // println!("Filler character found: '{}'", c);
                 
                 if c == '\n' {
@@ -93,45 +131,58 @@ impl Lexer{
             }
         }
 
-        //This section parses and ignores comments by looking for the comment identifiers and then skipping until finding the end
+        //This section parses comments by looking for the comment identifiers and then skipping until finding the end
+        //The comment is returned as a token when trivia is kept, otherwise the next token is scanned
         if let Some('/') = currChar {
+            self.inputFile.markTokenStart();
+            let commentLine = self.inputFile.lineCnt;
+            let mut commentString: String = "/".to_string();
             currChar = self.inputFile.getChar();
-            let Some(c) = currChar else { todo!() };
+            //A / at the end of the file is a divide
+            let c = currChar.unwrap_or('\0');
             //Two /s in a row, single line comment
             if c == '/' {
                 // println!("Comment line found");
                 while let Some(c) = currChar {
-                    if c == '\n' {
-                        self.inputFile.incLineCnt();
+                    //Leaves the line ending for the next scan, which counts the line
+                    if c == '\n' || c == '\r' {
+                        self.inputFile.unGetChar();
                         break;
                     } else {
+                        commentString.push(c);
                         currChar = self.inputFile.getChar();
                     }
                 }
+                return self.endComment(commentString, commentLine);
             } 
             //This identifies a multiline comment
             else if c == '*' {
                 // println!("multiline comment");
+                let commentCol = self.inputFile.tokenCol;
                 let mut nested: usize = 1;
-                // println!("Comment line found");
+                //Skips the opening * so that /*/ doesn't close the comment
+                commentString.push('*');
+                currChar = self.inputFile.getChar();
                 //Finds the end of the multiline comment
                 while let Some(c) = currChar {
+                    commentString.push(c);
                     //If a nested multiline comment is found, increases scope
                     if c == '/' {
                         // println!("scope +1 nested");
                         currChar = self.inputFile.getChar();
-                        let Some(ch) = currChar else { todo!() };
+                        let Some(ch) = currChar else { break };
                         if ch == '*' {
+                            commentString.push(ch);
                             nested += 1;
                             currChar = self.inputFile.getChar();
                         }
                     } else if c == '*' {
                         currChar = self.inputFile.getChar();
-                        let Some(ch) = currChar else { todo!() };
+                        let Some(ch) = currChar else { break };
                         if ch == '/' {
+                            commentString.push(ch);
                             nested -= 1;
                             if nested == 0 {
-                                currChar = self.inputFile.getChar();
                                 // println!("End of nested comment");
                                 break;
                             } else {
@@ -142,15 +193,20 @@ impl Lexer{
                     } else if c == '\n' {
                         self.inputFile.incLineCnt();
                         currChar = self.inputFile.getChar();
-
-                          
                     } else {
                         currChar = self.inputFile.getChar();
                     }
                 }
+                //The file ended inside the comment, the error points at where it was opened
+                if nested > 0 {
+                    let levels = if nested == 1 { "1 comment".to_string() } else { format!("{} nested comments", nested) };
+                    self.reports.reportErrorAt(format!("Error on line {}, column {}: this comment is never closed, the file ends inside {}", commentLine, commentCol + 1, levels), Span::onLine(commentLine, commentCol, commentCol + 2));
+                }
+                return self.endComment(commentString, commentLine);
             } 
-            //This is for if the / has a space after it meaning its a divide not a comment
-            else if c == ' ' {
+            //Anything else after the / means its a divide not a comment, the next character is scanned again
+            else {
+                self.inputFile.unGetChar();
                 let tokenString = '/';
                 let newToken = Token::new(crate::tokenTypeEnum::DIVIDE,tokenString.to_string(), self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
                 return newToken;
@@ -158,6 +214,9 @@ impl Lexer{
         }
 
         //A switch case to handle all of the different characters the lexer could find
+        if currChar.is_some() {
+            self.inputFile.markTokenStart();
+        }
         let mut tokenString: String = "".to_string();
         match currChar {
             //If the character is a lette, iterates through until no more letters or numbers are found, turns all of that into a word then checks if it is a predefined keyword token, creating a new token if not
@@ -179,24 +238,72 @@ impl Lexer{
                 return newToken;
             }
 
-            //If the character is a number, iterates through until end of number, if a . is found, it creates a float token
+            //If the character is a number, reads a whole number literal, a . or an exponent makes it a float
+            //0x and 0b start hex and binary integers, and a _ can go between any two digits
+            //The token keeps the literal as it is written, the parser works out its value
             Some(ch) if ch.is_ascii_digit() => {
                 let mut tokType: tokenTypeEnum = tokenTypeEnum::INT;
-                //Iterates through until it stops finding numbers
+                let numberCol = self.inputFile.tokenCol;
+                let mut valid: bool;
+                let prefix = if ch == '0' { self.inputFile.getChar() } else { None };
+                match prefix {
+                    Some(p) if p == 'x' || p == 'X' || p == 'b' || p == 'B' => {
+                        tokenString.push(ch);
+                        tokenString.push(p);
+                        let radix = if p == 'x' || p == 'X' { 16 } else { 2 };
+                        valid = self.scanDigits(radix, &mut tokenString);
+                    }
+                    _ => {
+                        if ch == '0' {
+                            self.inputFile.unGetChar();
+                        }
+                        self.inputFile.unGetChar();
+                        valid = self.scanDigits(10, &mut tokenString);
+                        //If the number has a decimal, meaning its a float, the digits after it can be left off
+                        currChar = self.inputFile.getChar();
+                        if currChar == Some('.') {
+                            tokenString.push('.');
+                            tokType = tokenTypeEnum::FLOAT;
+                            currChar = self.inputFile.getChar();
+                            if currChar.map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                                self.inputFile.unGetChar();
+                                valid = self.scanDigits(10, &mut tokenString) && valid;
+                                currChar = self.inputFile.getChar();
+                            }
+                        }
+                        //An exponent also makes it a float
+                        if currChar == Some('e') || currChar == Some('E') {
+                            tokenString.push(currChar.unwrap_or('e'));
+                            tokType = tokenTypeEnum::FLOAT;
+                            currChar = self.inputFile.getChar();
+                            if currChar == Some('+') || currChar == Some('-') {
+                                tokenString.push(currChar.unwrap_or('+'));
+                            } else {
+                                self.inputFile.unGetChar();
+                            }
+                            valid = self.scanDigits(10, &mut tokenString) && valid;
+                        } else {
+                            self.inputFile.unGetChar();
+                        }
+                    }
+                }
+                //Letters right after a number are part of a bad number, not a name
+                currChar = self.inputFile.getChar();
                 while let Some(numC) = currChar {
-                    if numC.is_ascii_digit() {
+                    if numC.is_ascii_alphanumeric() || numC == '_' {
                         tokenString.push(numC);
-                        currChar = self.inputFile.getChar();
-                    //If the number has a decimal, meaning its a float
-                    } else if numC == '.' {
-                        tokenString.push('.');
-                        tokType = tokenTypeEnum::FLOAT;
+                        valid = false;
                         currChar = self.inputFile.getChar();
                     } else {
                         break;
                     }
                 }
                 self.inputFile.unGetChar();
+                if !valid {
+                    self.reports.reportErrorAt(format!("Error on line {}, column {}: '{}' is not a valid number, {}", self.inputFile.lineCnt, numberCol + 1, tokenString, numberHint(&tokenString)), Span::onLine(self.inputFile.lineCnt, numberCol, numberCol + tokenString.chars().count()));
+                    let newToken = Token::new(crate::tokenTypeEnum::ERROR, tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OTHER);
+                    return newToken;
+                }
                 let newToken: Token = Token::new(tokType,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::CONSTANT);
                 return newToken;
             }
@@ -205,7 +312,8 @@ impl Lexer{
             Some('<') => {
                 let mut nextNextChar = self.inputFile.getChar();
                 tokenString.push('<');
-                let Some(nextC) = nextNextChar else { todo!() };
+                //The end of the file is treated like any character that doesn't continue the operator
+                let nextC = nextNextChar.unwrap_or('\0');
                 if nextC == '=' {
                     tokenString.push('=');
                     let newToken = Token::new(crate::tokenTypeEnum::LESS_EQUALS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
@@ -221,7 +329,7 @@ impl Lexer{
             Some('>') => {
                 tokenString.push('>');
                 let mut nextNextChar = self.inputFile.getChar();
-                let Some(nextC) = nextNextChar else { todo!() };
+                let nextC = nextNextChar.unwrap_or('\0');
                 if nextC == '=' {
                     tokenString.push('=');
                     let newToken = Token::new(crate::tokenTypeEnum::GREATER_EQUALS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
@@ -237,7 +345,7 @@ impl Lexer{
             Some('=') => {
                 tokenString.push('=');
                 let mut nextNextChar = self.inputFile.getChar();
-                let Some(nextC) = nextNextChar else { todo!() };
+                let nextC = nextNextChar.unwrap_or('\0');
                 if nextC == '=' {
                     tokenString.push('=');
                     let newToken = Token::new(crate::tokenTypeEnum::CHECK_EQUALS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
@@ -247,8 +355,8 @@ impl Lexer{
                     let newToken = Token::new(crate::tokenTypeEnum::SET_EQUALS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
                     return newToken;
                 } else {
-                    //If there is an unknown next character, creates an error token, this will be turned into an error given to the user in the second pass
-                    println!("ERROR");
+                    //If there is an unknown next character, creates an error token, it is reported here and left out on the second pass
+                    self.reports.reportErrorAt(format!("Error on line {}, column {}: '=' is not an operator, use ':=' to assign or '==' to compare", self.inputFile.lineCnt, self.inputFile.tokenCol + 1), Span::onLine(self.inputFile.lineCnt, self.inputFile.tokenCol, self.inputFile.tokenCol + 1));
                     self.inputFile.unGetChar();
                     let newToken = Token::new(crate::tokenTypeEnum::ERROR,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OTHER);
                     return newToken;
@@ -259,12 +367,13 @@ impl Lexer{
             Some('!') => {
                 tokenString.push('!');
                 let mut nextNextChar = self.inputFile.getChar();
-                let Some(nextC) = nextNextChar else { todo!() };
+                let nextC = nextNextChar.unwrap_or('\0');
                 if nextC == '=' {
                     tokenString.push('=');
                     let newToken = Token::new(crate::tokenTypeEnum::NOT_EQUALS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
                     return newToken;
                 } else {
+                    self.reports.reportErrorAt(format!("Error on line {}, column {}: '!' is not an operator, use '!=' for not equals", self.inputFile.lineCnt, self.inputFile.tokenCol + 1), Span::onLine(self.inputFile.lineCnt, self.inputFile.tokenCol, self.inputFile.tokenCol + 1));
                     self.inputFile.unGetChar();
                     let newToken = Token::new(crate::tokenTypeEnum::ERROR,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OTHER);
                     return newToken;
@@ -282,7 +391,7 @@ impl Lexer{
             Some(':') => {
                 tokenString.push(':');
                 let mut nextNextChar = self.inputFile.getChar();
-                let Some(nextC) = nextNextChar else { todo!() };
+                let nextC = nextNextChar.unwrap_or('\0');
                 if nextC == '=' {
                     tokenString.push('=');
                     let newToken = Token::new(crate::tokenTypeEnum::SET_EQUALS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
@@ -333,7 +442,7 @@ impl Lexer{
             Some('-') => {
                 tokenString.push('-');
                 let mut nextNextChar = self.inputFile.getChar();
-                let Some(nextC) = nextNextChar else { todo!() };
+                let nextC = nextNextChar.unwrap_or('\0');
                 self.inputFile.unGetChar();
                 let newToken = Token::new(crate::tokenTypeEnum::MINUS,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OPERATOR);
                 return newToken;
@@ -380,27 +489,37 @@ impl Lexer{
             }
 
             //If the character is a ", finds the end of the string, creates a string character
+            //A string can't go past the end of its line, escapes are decoded here so the token holds the real characters
             Some('"') => {
+                let stringLine = self.inputFile.lineCnt;
+                let stringCol = self.inputFile.tokenCol;
                 currChar = self.inputFile.getChar();
-                let mut tokType: tokenTypeEnum = tokenTypeEnum::WORD;
+                let mut closed = false;
                 while let Some(numC) = currChar {
                     if numC == '"' {
+                        closed = true;
+                        break;
+                    } else if numC == '\n' || numC == '\r' {
+                        //Leaves the line ending for the next scan, which counts the line
+                        self.inputFile.unGetChar();
                         break;
+                    } else if numC == '\\' {
+                        let escapeCol = self.inputFile.charCol();
+                        match self.scanEscape(stringLine, escapeCol) {
+                            Some(c) => tokenString.push(c),
+                            None => {}
+                        }
+                    } else if numC.is_control() && numC != '\t' {
+                        self.reports.reportErrorAt(format!("Error on line {}, column {}: invalid character {:?} in a string", stringLine, self.inputFile.charCol() + 1, numC), Span::onLine(stringLine, self.inputFile.charCol(), self.inputFile.charCol() + 1));
                     } else {
                         tokenString.push(numC);
                     }
                     currChar = self.inputFile.getChar();
-
                 }
-                while tokenString.len() < 64 {
-                    tokenString.push(' ');
-                }
-                tokenString.push('\0');  // Null terminator
-                let mut newToken = self.symTab.hashLook(tokenString, self.inputFile.lineCnt.to_string());
-                newToken.lineNum = self.inputFile.lineCnt.to_string();
-                if newToken.tt != tokenTypeEnum::STRING {
-                    newToken.tt = tokenTypeEnum::STRING;
+                if !closed {
+                    self.reports.reportErrorAt(format!("Error on line {}, column {}: this string is never closed, it needs a '\"' before the end of the line", stringLine, stringCol + 1), Span::onLine(stringLine, stringCol, stringCol + 1));
                 }
+                let newToken = Token::new(tokenTypeEnum::STRING, tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::VARIABLE);
                 return newToken;
             }
             
@@ -410,9 +529,10 @@ impl Lexer{
                 return newToken;
             }
             
-            //Unaccounted character, this will create the unaccounted token which throws an error on the second pass
+            //Unaccounted character, it is reported and the token is dropped on the second pass, so lexing goes on
             Some(c) => {
                 // println!("This character is unaccounted for '{}'", c);
+                self.reports.reportErrorAt(format!("Error on line {}, column {}: unknown character {:?}", self.inputFile.lineCnt, self.inputFile.tokenCol + 1, c), Span::onLine(self.inputFile.lineCnt, self.inputFile.tokenCol, self.inputFile.tokenCol + 1));
                 tokenString.push(c);
                 let newToken = Token::new(crate::tokenTypeEnum::UNACCOUNTED,tokenString, self.inputFile.lineCnt.to_string(), tokenGroup::OTHER);
                 return newToken;
@@ -427,6 +547,88 @@ impl Lexer{
         }
     }
     
+    //Reads a run of digits in the radix and the _ between them onto the token
+    //Returns false if there are no digits or a _ isn't between two digits
+    fn scanDigits(&mut self, radix: u32, tokenString: &mut String) -> bool {
+        let mut valid = true;
+        let mut digits = 0;
+        let mut lastC = '_';
+        let mut currChar = self.inputFile.getChar();
+        while let Some(numC) = currChar {
+            if numC.is_digit(radix) {
+                digits += 1;
+            } else if numC == '_' {
+                if lastC == '_' {
+                    valid = false;
+                }
+            } else {
+                break;
+            }
+            tokenString.push(numC);
+            lastC = numC;
+            currChar = self.inputFile.getChar();
+        }
+        self.inputFile.unGetChar();
+        return valid && digits > 0 && lastC != '_';
+    }
+
+    //Reads the escape after a \ in a string, returns the character it stands for
+    //A bad escape is reported and gives no character, a line ending after the \ is left for the string to find
+    fn scanEscape(&mut self, stringLine: usize, escapeCol: usize) -> Option<char> {
+        match self.inputFile.getChar() {
+            Some('n') => return Some('\n'),
+            Some('t') => return Some('\t'),
+            Some('"') => return Some('"'),
+            Some('\\') => return Some('\\'),
+            Some('u') => {
+                let mut digits = String::new();
+                let mut closed = false;
+                if self.inputFile.getChar() == Some('{') {
+                    loop {
+                        match self.inputFile.getChar() {
+                            Some('}') => {
+                                closed = true;
+                                break;
+                            }
+                            Some(c) if c.is_ascii_hexdigit() && digits.len() < 6 => digits.push(c),
+                            _ => {
+                                self.inputFile.unGetChar();
+                                break;
+                            }
+                        }
+                    }
+                } else {
+                    self.inputFile.unGetChar();
+                }
+                let decoded = u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32);
+                match decoded {
+                    Some(c) if closed => return Some(c),
+                    _ => {
+                        self.reports.reportErrorAt(format!("Error on line {}, column {}: invalid unicode escape, use \\u{{...}} with the 1 to 6 hex digits of a character", stringLine, escapeCol + 1), Span::onLine(stringLine, escapeCol, escapeCol + 2));
+                        return None;
+                    }
+                }
+            }
+            Some(c) if c != '\n' && c != '\r' => {
+                self.reports.reportErrorAt(format!("Error on line {}, column {}: '\\{}' is not an escape, use \\n, \\t, \\\", \\\\ or \\u{{...}}", stringLine, escapeCol + 1, c), Span::onLine(stringLine, escapeCol, escapeCol + 2));
+                return None;
+            }
+            _ => {
+                self.inputFile.unGetChar();
+                return None;
+            }
+        }
+    }
+
+    //Finishes a comment, returns it as a token if trivia is kept, otherwise returns the token after it
+    fn endComment(&mut self, commentString: String, commentLine: usize) -> Token {
+        if self.keepTrivia {
+            let newToken = Token::new(crate::tokenTypeEnum::COMMENT, commentString, commentLine.to_string(), tokenGroup::OTHER);
+            return newToken;
+        }
+        return self.scan();
+    }
+
     //Prints all of the tokens, used for debugging
     fn printTokenList(&mut self){
         for token in &self.tokenList {
@@ -447,22 +649,41 @@ impl Lexer{
                     let nextToken = &self.tokenList[i+1];
                     if nextToken.tt == tokenTypeEnum::PROGRAM {
                         // println!("Combining end and program");
-                        let newToken = Token::new(crate::tokenTypeEnum::END_PROGRAM,"END_PROGRAM".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        let mut newToken = Token::new(crate::tokenTypeEnum::END_PROGRAM,"END_PROGRAM".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
+                        newTokList.push(newToken.clone());
+                        i = i + 1;
+                    } else if nextToken.tt == tokenTypeEnum::MODULE {
+                        let mut newToken = Token::new(crate::tokenTypeEnum::END_MODULE,"END_MODULE".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
                         newTokList.push(newToken.clone());
                         i = i + 1;
                     } else if nextToken.tt == tokenTypeEnum::PROCEDURE {
                         // println!("Combining end and procedure");
-                        let newToken = Token::new(crate::tokenTypeEnum::END_PROCEDURE,"END_PROCEDURE".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        let mut newToken = Token::new(crate::tokenTypeEnum::END_PROCEDURE,"END_PROCEDURE".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
                         newTokList.push(newToken.clone());
                         i = i + 1;
                     } else if nextToken.tt == tokenTypeEnum::IF {
                         // println!("Combining end and if");
-                        let newToken = Token::new(crate::tokenTypeEnum::END_IF,"END_IF".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        let mut newToken = Token::new(crate::tokenTypeEnum::END_IF,"END_IF".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
                         newTokList.push(newToken.clone());
                         i = i + 1;
                     } else if nextToken.tt == tokenTypeEnum::FOR {
                         // println!("Combining end and if");
-                        let newToken = Token::new(crate::tokenTypeEnum::END_FOR,"END_FOR".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        let mut newToken = Token::new(crate::tokenTypeEnum::END_FOR,"END_FOR".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
                         newTokList.push(newToken.clone());
                         i = i + 1;
                     } else {
@@ -471,12 +692,29 @@ impl Lexer{
 
                     }
                 }
+                //Turns external procedure into one token, so it isn't counted as a procedure that needs an end procedure
+                tokenTypeEnum::EXTERNAL => {
+                    let nextToken = &self.tokenList[i+1];
+                    if nextToken.tt == tokenTypeEnum::PROCEDURE {
+                        let mut newToken = Token::new(crate::tokenTypeEnum::EXTERNAL_PROCEDURE,"EXTERNAL_PROCEDURE".to_string(), nextToken.lineNum.to_string(), tokenGroup::OTHER);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
+                        newTokList.push(newToken.clone());
+                        i = i + 1;
+                    } else {
+                        newTokList.push(token.clone());
+                    }
+                }
                 //Turns identifiers into procedure calls if that's what it is
                 tokenTypeEnum::IDENTIFIER => {
                     let nextToken = &self.tokenList[i+1];
                     if nextToken.tt == tokenTypeEnum::L_PAREN {
                         // println!("Combining end and if");
-                        let newToken = Token::new(crate::tokenTypeEnum::PROCEDURE_CALL, token.tokenString.clone(), nextToken.lineNum.to_string(), tokenGroup::SYMBOL);
+                        let mut newToken = Token::new(crate::tokenTypeEnum::PROCEDURE_CALL, token.tokenString.clone(), nextToken.lineNum.to_string(), tokenGroup::SYMBOL);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
                         newTokList.push(newToken.clone());
                         i = i + 1;
                     } else {
@@ -485,36 +723,21 @@ impl Lexer{
 
                     }
                 }
-                //The unaccounted token, throws an error
-                tokenTypeEnum::UNACCOUNTED => {
-                    if(token.tokenString == "\r"){
-                        // println!("Skipping unaccounted");
-                        // println!("Unaccounted: {}", token.tokenString);
-                        let nextToken = &self.tokenList[i+1];
-                        // println!("Next token: {}", nextToken.tokenString);
-                        newTokList.push(nextToken.clone());
-                        i = i + 1;
-                    } else {
-                        let errMsg = format!("Unaccounted token '{}' found on line {}", token.tokenString.clone(), token.lineNum.clone());
-                        self.reports.reportError(errMsg.clone());
-                        // println!("Skipping unaccounted");
-                        // println!("Unaccounted: {}", token.tokenString);
-                        let nextToken = &self.tokenList[i+1];
-                        // println!("Next token: {}", nextToken.tokenString);
-                        newTokList.push(nextToken.clone());
-                        i = i + 1;
-                    }
-                    
-                }
+                //The unaccounted and error tokens were reported when they were scanned, they are left out
+                tokenTypeEnum::UNACCOUNTED | tokenTypeEnum::ERROR => {}
                 //Checks - tokens for if they are neg numbers or minus operators
                 tokenTypeEnum::MINUS => {
                     let nextToken = &self.tokenList[i+1];
-                    let prevToken = &self.tokenList[i-1];
+                    //A - at the start of the file has nothing before it
+                    let afterOperator = (i > 0) && ((self.tokenList[i-1].tg == tokenGroup::OPERATOR) || (self.tokenList[i-1].tt == tokenTypeEnum::SET_EQUALS));
                     //Defines a negative number
-                    if ((nextToken.tg == tokenGroup::VARIABLE) || (nextToken.tg == tokenGroup::CONSTANT)) && ((prevToken.tg == tokenGroup::OPERATOR) || (prevToken.tt == tokenTypeEnum::SET_EQUALS)) {
+                    if ((nextToken.tg == tokenGroup::VARIABLE) || (nextToken.tg == tokenGroup::CONSTANT)) && afterOperator {
                         // println!("Found a neg number");
                         let newString = format!("-{}", nextToken.tokenString.clone());
-                        let newToken = Token::new(nextToken.tt.clone(), newString, nextToken.lineNum.to_string(), tokenGroup::CONSTANT);
+                        let mut newToken = Token::new(nextToken.tt.clone(), newString, nextToken.lineNum.to_string(), tokenGroup::CONSTANT);
+                        newToken.col = token.col;
+                        newToken.start = token.start;
+                        newToken.end = nextToken.end;
                         newTokList.push(newToken.clone());
                         i = i + 1;
                     //This is just a minus operator
@@ -541,14 +764,22 @@ impl Lexer{
 
         //Scans the first token and initializes the newToken variable
         let mut newToken: Token = self.scan();
+        self.inputFile.markTokenEnd(&mut newToken);
         self.tokenList.push(newToken.clone());
 
         //Goes through the inputfile and calls scan() which returns each token until the EOF is reached
         while newToken.tokenString != "EOF".to_string(){
             newToken = self.scan();
+            self.inputFile.markTokenEnd(&mut newToken);
             self.tokenList.push(newToken.clone());
         };
 
+        //Takes the trivia out before the second pass, so they can't split up the tokens it combines
+        if self.keepTrivia {
+            self.rawTokens = self.tokenList.clone();
+            self.tokenList.retain(|token| token.tt != tokenTypeEnum::WHITESPACE && token.tt != tokenTypeEnum::COMMENT);
+        }
+
         //Runs the tokenString through a second pass, this will conclude the lexer
         let newTokList = self.secondPass();
         self.tokenList = newTokList;
@@ -562,17 +793,25 @@ pub struct inFile{
     attatchFile: bool,
     pub fileName: String,
     fileContents: String,
+    fileChars: Vec<char>,       //The characters of the file, so getChar doesn't walk the string from the start
     lineCnt: usize,
     pub numChars: usize,
     pub totalLines: usize,
-    pub file : BufReader <File>,
     pub currentCharIndex: usize,
+    lineStart: usize,           //The index of the first character of the current line
+    tokenCol: usize,            //The column of the token being scanned
+    tokenStart: usize,          //The index of the first character of the token being scanned
+    pastEnd: bool,              //If the last getChar was past the end of the file
 }
 impl inFile {
     //Constructor, imports and opens the file
     fn new(fileName: &str) -> inFile {
-        let mut newFile = BufReader::new(File::open(fileName).unwrap());
         let fileContentsString = std::fs::read_to_string(fileName).expect("Unable to read file");
+        return inFile::newFromString(fileName, fileContentsString);
+    }
+
+    //Constructor for source that is already in memory (an editor buffer), the file name is only used for reporting
+    fn newFromString(fileName: &str, fileContentsString: String) -> inFile {
         let numChars = fileContentsString.len();
         // println!("Creating the inFile structure");
         
@@ -582,9 +821,13 @@ impl inFile {
             lineCnt: 1,
             currentCharIndex: 0,
             totalLines: 0,
-            file: newFile,
+            fileChars: fileContentsString.chars().collect(),
             fileContents: fileContentsString,
             numChars: numChars,
+            lineStart: 0,
+            tokenCol: 0,
+            tokenStart: 0,
+            pastEnd: false,
         }
 
     }
@@ -597,33 +840,70 @@ impl inFile {
 
     //Gets the next character in the file string
     fn getChar(&mut self) -> Option<char> {
-        if let Some(current_char) = self.fileContents.chars().nth(self.currentCharIndex) {
+        if let Some(current_char) = self.fileChars.get(self.currentCharIndex) {
             self.currentCharIndex += 1;
-            Some(current_char)
+            self.pastEnd = false;
+            Some(*current_char)
         } else {
+            self.pastEnd = true;
             None
         }
     }
     
     //"ungets" the next character by decrementing the current index. Used for looking ahead then going back
+    //Reading past the end of the file doesn't move the index, so there is nothing to unget
     fn unGetChar(&mut self) {
-        self.currentCharIndex -= 1;
+        if self.pastEnd {
+            self.pastEnd = false;
+            return;
+        }
+        self.currentCharIndex = self.currentCharIndex.saturating_sub(1);
     }
 
     //A function to increment the current line
     fn incLineCnt(&mut self){
         self.lineCnt += 1;
+        self.lineStart = self.currentCharIndex;
+    }
+
+    //The column of the character that was just read (0 based)
+    fn charCol(&self) -> usize {
+        return self.currentCharIndex.saturating_sub(1).saturating_sub(self.lineStart);
+    }
+
+    //Marks the character that was just read as the start of a token, saving its column
+    fn markTokenStart(&mut self) {
+        self.tokenStart = self.currentCharIndex.saturating_sub(1);
+        self.tokenCol = self.tokenStart.saturating_sub(self.lineStart);
+    }
+
+    //Saves where a token that was just scanned is in the file, the end is the character after the token
+    fn markTokenEnd(&mut self, token: &mut Token) {
+        if token.tt == tokenTypeEnum::EOF {
+            self.tokenStart = self.currentCharIndex;
+            self.tokenCol = self.currentCharIndex.saturating_sub(self.lineStart);
+        }
+        token.col = self.tokenCol;
+        token.start = self.tokenStart;
+        token.end = self.currentCharIndex;
     }
 
 }
 
 //Token class, this is where tokens are defined and setup
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct Token{
+    #[serde(rename = "type")]
     pub tt: tokenTypeEnum,
+    #[serde(rename = "text")]
     pub tokenString: String,
+    #[serde(rename = "group")]
     pub tg: tokenGroup,
+    #[serde(rename = "line", serialize_with = "serializeLineNum")]
     pub lineNum: String,
+    pub col: usize,             //The column the token starts at on its line (0 based, in characters)
+    pub start: usize,           //The index of the first character of the token in the file
+    pub end: usize,             //The index of the character after the token, the token's text is the characters from start to end
     //To be completed later when I understand
     //tm: tokenMark,
 }
@@ -635,6 +915,9 @@ impl Token{
             tokenString: tokenString,
             lineNum: line,
             tg: group,
+            col: 0,
+            start: 0,
+            end: 0,
         }
     }
     
@@ -647,6 +930,37 @@ impl Token{
     fn printToken(&mut self){
         println!("< \"{}\" , {} >", self.tokenString, self.tt.to_string());
     }
+
+    //Where the token is in the source, a joined token like end if goes from its first word to the end of its last
+    pub fn span(&self) -> Span {
+        let line = self.lineNum.parse::<usize>().unwrap_or(0);
+        return Span {
+            startLine: line,
+            startCol: self.col,
+            endLine: line,
+            endCol: self.col + (self.end - self.start),
+        };
+    }
+
+    //Where the name in the token is, a procedure call token also has the ( after the name
+    pub fn nameSpan(&self) -> Span {
+        let mut span = self.span();
+        span.endCol = self.col + self.tokenString.chars().count();
+        return span;
+    }
+}
+
+//Line numbers are kept as strings in the tokens and the AST, this writes them to JSON as numbers
+//Used with serialize_with by Token and Stmt
+pub fn serializeLineNum<S: Serializer>(lineNum: &String, serializer: S) -> Result<S::Ok, S::Error> {
+    match lineNum.parse::<u64>() {
+        Ok(line) => {
+            return serializer.serialize_u64(line);
+        }
+        Err(_) => {
+            return serializer.serialize_u64(0);
+        }
+    }
 }
 
 //The structure for the tokenTable, which defines keywords and such, contains a hashmap that uses the string as key and the token as a value
@@ -674,6 +988,9 @@ impl tokenTable{
             ("then", Token::new(tokenTypeEnum::THEN, "then".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
             ("end", Token::new(tokenTypeEnum::END, "end".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
             ("program", Token::new(tokenTypeEnum::PROGRAM, "program".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
+            ("module", Token::new(tokenTypeEnum::MODULE, "module".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
+            ("import", Token::new(tokenTypeEnum::IMPORT, "import".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
+            ("external", Token::new(tokenTypeEnum::EXTERNAL, "external".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
             ("return", Token::new(tokenTypeEnum::RETURN, "return".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
             ("for", Token::new(tokenTypeEnum::FOR, "for".to_string(), "0".to_string(), tokenGroup::KEYWORD)),
             ("not", Token::new(tokenTypeEnum::NOT, "not".to_string(), "0".to_string(), tokenGroup::OPERATOR)),
@@ -714,7 +1031,7 @@ impl tokenTable{
 
 //An enum used in conjunction with tokenType for parsing purposes
 //Defines the tokens into groups like constants, operators, etc.
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
 pub enum tokenGroup{
     OPERATOR,
     KEYWORD,
@@ -740,6 +1057,42 @@ impl fmt::Display for tokenGroup {
     }
 }
 
+//Writes a string back the way it is written in the source, the escapes scan decodes are put back
+pub fn escapeString(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+//Says what is wrong with a number that didn't lex
+fn numberHint(text: &str) -> &'static str {
+    let lower = text.to_ascii_lowercase();
+    if lower.starts_with("0x") {
+        return "a hex number is 0x and the digits 0-9 and a-f";
+    } else if lower.starts_with("0b") {
+        return "a binary number is 0b and the digits 0 and 1";
+    } else if text.contains("_") && (text.contains("__") || text.contains("_.") || text.contains("._") || text.ends_with('_') || lower.contains("e_") || lower.contains("_e")) {
+        return "a '_' has to be between two digits";
+    } else if lower.contains('e') && !lower.chars().skip_while(|c| *c != 'e').skip(1).any(|c| c.is_ascii_digit()) {
+        return "an exponent needs digits after the e";
+    }
+    return "a number can't have letters in it";
+}
+
+//The characters that separate tokens
+fn isFiller(c: char) -> bool {
+    return c == '\n' || c == '\t' || c == '\r' || c == ' ' || c == '\u{0009}';
+}
+
 //Used to print an entire list of tokens (This is for debugging)
 pub fn printTokList(tokList: &Vec<Token>){
     for token in tokList {
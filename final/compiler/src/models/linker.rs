@@ -0,0 +1,340 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::reporting::Reporting,
+    std::{
+        env,
+        fs,
+        path::{Path, PathBuf},
+        process::Command,
+    },
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// LINKER SECTION /////////////////////////
+//This section links the object file the compiler writes with the runtime library (funcLib) into an executable
+//It finds the runtime and a system linker by itself so the compiler can be run from any directory
+
+//The name of the runtime library archive
+const RUNTIME_LIB: &str = "libfuncLib.a";
+
+//The system libraries a Rust staticlib needs when it is linked into a C program
+const RUNTIME_SYS_LIBS: [&str; 4] = ["pthread", "dl", "m", "c"];
+
+//The linker drivers that are tried in order when no --linker is given
+const LINKER_CANDIDATES: [&str; 4] = ["cc", "clang", "gcc", "ld"];
+
+//The directories searched for the C runtime start files (crt1.o, crti.o, crtn.o) when linking with ld
+const CRT_DIRS: [&str; 5] = [
+    "/usr/lib/x86_64-linux-gnu",
+    "/usr/lib64",
+    "/usr/lib",
+    "/lib/x86_64-linux-gnu",
+    "/lib64",
+];
+
+//The compiler drivers asked where libgcc_s is when linking with ld directly, ld doesn't search the gcc directory itself
+const GCC_DRIVERS: [&str; 3] = ["cc", "gcc", "clang"];
+
+//The directories with a directory for each gcc version, searched for libgcc_s when no driver knows where it is
+const GCC_LIB_ROOTS: [&str; 3] = [
+    "/usr/lib/gcc/x86_64-linux-gnu",
+    "/usr/lib/gcc/x86_64-pc-linux-gnu",
+    "/usr/lib64/gcc/x86_64-pc-linux-gnu",
+];
+
+//The dynamic loader used when linking with ld directly
+const DYNAMIC_LOADERS: [&str; 2] = ["/lib64/ld-linux-x86-64.so.2", "/lib/ld-linux-x86-64.so.2"];
+
+//The master struct for the linker
+pub struct Linker {
+    pub linker: Option<String>,     //The linker given with --linker, discovered if None
+    pub libDirs: Vec<String>,       //The extra library directories (-L)
+    pub libs: Vec<String>,          //The extra libraries (-l)
+    pub reports: Reporting,         //The reporting structure, link errors are reported here
+}
+
+impl Linker {
+    //The constructor
+    pub fn new(linker: Option<String>, libDirs: Vec<String>, libs: Vec<String>) -> Linker {
+        Linker {
+            linker,
+            libDirs,
+            libs,
+            reports: Reporting::new(),
+        }
+    }
+
+    //Links the object file into the output executable
+    //Returns true if the link worked, the errors are in self.reports if not
+    pub fn link(&mut self, objectPath: &Path, outputPath: &Path) -> bool {
+        //Finds the runtime library
+        let runtimeLib: PathBuf;
+        match self.findRuntimeLib() {
+            Some(path) => {
+                runtimeLib = path;
+            }
+            None => {
+                let errMsg = format!("Could not find the runtime library {}. The compiler's build puts it next to the compiler, or pass its directory with -L", RUNTIME_LIB);
+                self.reports.reportError(errMsg);
+                return false;
+            }
+        }
+
+        //Finds the linker
+        let linker: String;
+        match self.findLinker() {
+            Some(found) => {
+                linker = found;
+            }
+            None => {
+                let errMsg = format!("Could not find a linker, tried {}. Pass one with --linker", LINKER_CANDIDATES.join(", "));
+                self.reports.reportError(errMsg);
+                return false;
+            }
+        }
+
+        //Builds the arguments, ld needs the C runtime given to it by hand while the drivers find it themselves
+        let linkArgs: Vec<String>;
+        if Linker::isBareLd(&linker) {
+            match self.ldArgs(objectPath, &runtimeLib, outputPath) {
+                Some(args) => {
+                    linkArgs = args;
+                }
+                None => {
+                    return false;
+                }
+            }
+        } else {
+            linkArgs = self.driverArgs(objectPath, &runtimeLib, outputPath);
+        }
+
+        //Runs the linker
+        let output = Command::new(&linker).args(&linkArgs).output();
+        match output {
+            Ok(out) => {
+                if out.status.success() {
+                    return true;
+                }
+                //Reports every line the linker printed as its own error
+                let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+                let errMsg = format!("Linking with '{}' failed ({})", linker, out.status);
+                self.reports.reportError(errMsg);
+                for line in stderr.lines() {
+                    if line.trim() != "" {
+                        self.reports.reportError(format!("{}: {}", linker, line.trim()));
+                    }
+                }
+                return false;
+            }
+            Err(err) => {
+                let errMsg = format!("Failed to run linker '{}': {}", linker, err);
+                self.reports.reportError(errMsg);
+                return false;
+            }
+        }
+    }
+
+    //Finds the runtime library archive
+    //Looks in the -L directories, then FUNCLIB_DIR, then next to the compiler executable where build.rs puts it
+    pub fn findRuntimeLib(&self) -> Option<PathBuf> {
+        let mut searchDirs: Vec<PathBuf> = Vec::new();
+        for dir in self.libDirs.iter() {
+            searchDirs.push(PathBuf::from(dir));
+        }
+        if let Ok(dir) = env::var("FUNCLIB_DIR") {
+            searchDirs.push(PathBuf::from(dir));
+        }
+        if let Ok(exePath) = env::current_exe() {
+            if let Some(exeDir) = exePath.parent() {
+                searchDirs.push(exeDir.to_path_buf());
+            }
+        }
+
+        for dir in searchDirs {
+            let candidate = dir.join(RUNTIME_LIB);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        return None;
+    }
+
+    //Finds the linker, the --linker option wins, otherwise the first candidate on the PATH
+    pub fn findLinker(&self) -> Option<String> {
+        if let Some(linker) = self.linker.clone() {
+            return Some(linker);
+        }
+        for candidate in LINKER_CANDIDATES {
+            if Linker::onPath(candidate) {
+                return Some(candidate.to_string());
+            }
+        }
+        return None;
+    }
+
+    //Checks if a program is in one of the PATH directories
+    fn onPath(program: &str) -> bool {
+        match env::var_os("PATH") {
+            Some(paths) => {
+                for dir in env::split_paths(&paths) {
+                    if dir.join(program).is_file() {
+                        return true;
+                    }
+                }
+                return false;
+            }
+            None => {
+                return false;
+            }
+        }
+    }
+
+    //Checks if the linker is ld itself instead of a compiler driver
+    pub fn isBareLd(linker: &str) -> bool {
+        let fileName = Path::new(linker).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        return fileName == "ld" || fileName.starts_with("ld.");
+    }
+
+    //The arguments for a compiler driver (cc, clang, gcc), it adds the C runtime itself
+    pub fn driverArgs(&self, objectPath: &Path, runtimeLib: &Path, outputPath: &Path) -> Vec<String> {
+        let mut args: Vec<String> = Vec::new();
+        args.push("-o".to_string());
+        args.push(outputPath.to_string_lossy().to_string());
+        args.push(objectPath.to_string_lossy().to_string());
+        for dir in self.libDirs.iter() {
+            args.push(format!("-L{}", dir));
+        }
+        //The user's libraries come before the runtime archive so a C helper can call back into it (rt_panic, putstring)
+        for lib in self.libs.iter() {
+            args.push(format!("-l{}", lib));
+        }
+        args.push(runtimeLib.to_string_lossy().to_string());
+        for lib in RUNTIME_SYS_LIBS {
+            args.push(format!("-l{}", lib));
+        }
+        return args;
+    }
+
+    //The directory with the C runtime start files, None if they aren't installed
+    pub fn findCrtDir() -> Option<PathBuf> {
+        for dir in CRT_DIRS {
+            let dirPath = PathBuf::from(dir);
+            if dirPath.join("crt1.o").is_file() && dirPath.join("crti.o").is_file() && dirPath.join("crtn.o").is_file() {
+                return Some(dirPath);
+            }
+        }
+        return None;
+    }
+
+    //The directory with libgcc_s, a driver knows it with -print-file-name, otherwise the newest gcc version directory
+    pub fn findGccLibDir() -> Option<PathBuf> {
+        for driver in GCC_DRIVERS {
+            if !Linker::onPath(driver) {
+                continue;
+            }
+            if let Ok(out) = Command::new(driver).arg("-print-file-name=libgcc_s.so").output() {
+                //A driver that doesn't know the file prints the name back without a directory
+                let found = PathBuf::from(String::from_utf8_lossy(&out.stdout).trim());
+                if found.is_absolute() && found.exists() {
+                    return found.parent().map(|dir| dir.to_path_buf());
+                }
+            }
+        }
+        let mut versionDirs: Vec<(u32, PathBuf)> = Vec::new();
+        for root in GCC_LIB_ROOTS {
+            if let Ok(entries) = fs::read_dir(root) {
+                for entry in entries.filter_map(|entry| entry.ok()) {
+                    let major = entry.file_name().to_string_lossy().split('.').next().and_then(|num| num.parse::<u32>().ok());
+                    if let Some(major) = major {
+                        if entry.path().join("libgcc_s.so").exists() {
+                            versionDirs.push((major, entry.path()));
+                        }
+                    }
+                }
+            }
+        }
+        versionDirs.sort();
+        return versionDirs.pop().map(|(_, dir)| dir);
+    }
+
+    //The arguments for linking with ld directly, the crt start files, libgcc_s and the dynamic loader are found by hand
+    pub fn ldArgs(&mut self, objectPath: &Path, runtimeLib: &Path, outputPath: &Path) -> Option<Vec<String>> {
+        let crtDir = match Linker::findCrtDir() {
+            Some(dir) => dir,
+            None => {
+                let errMsg = format!("Could not find crt1.o, crti.o and crtn.o in {}. Use --linker cc instead", CRT_DIRS.join(", "));
+                self.reports.reportError(errMsg);
+                return None;
+            }
+        };
+
+        //The Rust runtime unwinds through libgcc_s, which is only in the gcc directory on some systems (Debian)
+        let gccDir = match Linker::findGccLibDir() {
+            Some(dir) => dir,
+            None => {
+                let errMsg = format!("Could not find libgcc_s.so with {} or in {}. Use --linker cc instead", GCC_DRIVERS.join(", "), GCC_LIB_ROOTS.join(", "));
+                self.reports.reportError(errMsg);
+                return None;
+            }
+        };
+
+        //Finds the dynamic loader
+        let mut loader: Option<String> = None;
+        for candidate in DYNAMIC_LOADERS {
+            if Path::new(candidate).exists() {
+                loader = Some(candidate.to_string());
+                break;
+            }
+        }
+        let loader = match loader {
+            Some(path) => path,
+            None => {
+                let errMsg = format!("Could not find the dynamic loader, tried {}", DYNAMIC_LOADERS.join(", "));
+                self.reports.reportError(errMsg);
+                return None;
+            }
+        };
+
+        let mut args: Vec<String> = Vec::new();
+        args.push("-o".to_string());
+        args.push(outputPath.to_string_lossy().to_string());
+        args.push("-dynamic-linker".to_string());
+        args.push(loader);
+        args.push(crtDir.join("crt1.o").to_string_lossy().to_string());
+        args.push(crtDir.join("crti.o").to_string_lossy().to_string());
+        args.push(objectPath.to_string_lossy().to_string());
+        args.push(format!("-L{}", crtDir.to_string_lossy()));
+        args.push(format!("-L{}", gccDir.to_string_lossy()));
+        for dir in self.libDirs.iter() {
+            args.push(format!("-L{}", dir));
+        }
+        //Before the runtime archive, like in driverArgs
+        for lib in self.libs.iter() {
+            args.push(format!("-l{}", lib));
+        }
+        args.push(runtimeLib.to_string_lossy().to_string());
+        //The Rust runtime unwinds through libgcc_s, which the drivers add on their own
+        args.push("-lgcc_s".to_string());
+        for lib in RUNTIME_SYS_LIBS {
+            args.push(format!("-l{}", lib));
+        }
+        args.push(crtDir.join("crtn.o").to_string_lossy().to_string());
+        return Some(args);
+    }
+}
+
+///////////////////////// /LINKER SECTION /////////////////////////
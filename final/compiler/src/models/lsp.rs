@@ -0,0 +1,648 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::{
+        frontend::{runFrontEnd, Stage},
+        parser::{Expr, Stmt, VarType},
+        reporting::Span,
+        typechecker::{HashItem, HashItemType, SymbolTable},
+    },
+    serde_json::{json, Value},
+    std::{
+        collections::HashMap,
+        io::{BufRead, Write},
+    },
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// ANALYSIS SECTION /////////////////////////
+//This section runs the front end (lexer, parser, SyntaxChecker) over a document and builds the index the language server answers from
+//The positions come from the spans in the AST, what a name refers to comes from the SymbolTables the checker filled in
+
+//An error for the editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub span: Option<Span>,     //Where the error is, None for errors that aren't about one place in the source
+    pub message: String,
+}
+
+//A name in the source and the SymbolTable entry it refers to, a declaration is a use of the name it declares
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameUse {
+    pub span: Span,             //Where the name is
+    pub item: HashItem,         //The entry the checker made for the declaration
+    pub global: bool,           //If the entry is in the global table
+}
+
+//A procedure the checker accepted, with the table of its scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcScope {
+    pub item: HashItem,         //The procedure's entry, its table has the parameters, locals and nested procedures
+    pub span: Span,             //The whole declaration
+    pub nameSpan: Span,         //The name in the declaration
+    pub children: Vec<ProcScope>, //The procedures declared in it
+}
+
+//The result of analyzing one document
+pub struct Analysis {
+    pub diagnostics: Vec<Diagnostic>,
+    pub globalTable: SymbolTable,       //The table filled in by the checker
+    pub procs: Vec<ProcScope>,          //The procedures declared in the program, the nested ones are their children
+    pub uses: Vec<NameUse>,             //Every name that resolved to an entry
+}
+
+impl Analysis {
+    //Runs the front end over the text of a document
+    pub fn new(fileName: &str, text: &str) -> Analysis {
+        let frontEnd = runFrontEnd(fileName, text, Stage::Check);
+        let mut analysis = Analysis {
+            diagnostics: Vec::new(),
+            globalTable: frontEnd.globalTable,
+            procs: Vec::new(),
+            uses: Vec::new(),
+        };
+        for (message, span) in frontEnd.errors.iter().zip(frontEnd.errorSpans.iter()) {
+            analysis.diagnostics.push(Diagnostic { span: *span, message: message.trim().to_string() });
+        }
+
+        //A program has its header and body, a module only a header
+        let stmts: Vec<Stmt> = match frontEnd.ast {
+            Some(Stmt::Program(_, header, body, ..)) => vec![*header, *body],
+            Some(Stmt::Module(_, header, ..)) => vec![*header],
+            _ => Vec::new(),
+        };
+        let mut uses: Vec<NameUse> = Vec::new();
+        analysis.procs = analysis.indexStmts(&stmts, None, &mut uses);
+        analysis.uses = uses;
+        return analysis;
+    }
+
+    //Walks statements in a scope, adding the names they use, returns the procedures declared in them
+    //The scope is the procedure the statements are in, None for the program
+    fn indexStmts(&self, stmts: &[Stmt], scope: Option<&HashItem>, uses: &mut Vec<NameUse>) -> Vec<ProcScope> {
+        let mut procs: Vec<ProcScope> = Vec::new();
+        for stmt in stmts {
+            match stmt {
+                Stmt::Block(instrs, ..) => {
+                    procs.extend(self.indexStmts(instrs, scope, uses));
+                }
+                Stmt::VarDecl(varName, _, _, _, nameSpan) | Stmt::GlobVarDecl(varName, _, _, _, nameSpan) => {
+                    self.addUse(varName, *nameSpan, false, scope, uses);
+                }
+                Stmt::ProcDecl(_, procName, params, header, body, _, span, nameSpan) => {
+                    //The checker only keeps a procedure that checked, the names in one that didn't aren't indexed
+                    let item = match self.declared(procName, *nameSpan, scope) {
+                        Some(item) => item,
+                        None => continue,
+                    };
+                    self.addUse(procName, *nameSpan, false, scope, uses);
+                    self.indexStmts(&[*params.clone()], Some(&item), uses);
+                    let children = self.indexStmts(&[*header.clone(), *body.clone()], Some(&item), uses);
+                    procs.push(ProcScope { item, span: *span, nameSpan: *nameSpan, children });
+                }
+                Stmt::ExternProcDecl(_, procName, params, _, span, nameSpan) => {
+                    let item = match self.declared(procName, *nameSpan, scope) {
+                        Some(item) => item,
+                        None => continue,
+                    };
+                    self.addUse(procName, *nameSpan, false, scope, uses);
+                    self.indexStmts(&[*params.clone()], Some(&item), uses);
+                    procs.push(ProcScope { item, span: *span, nameSpan: *nameSpan, children: Vec::new() });
+                }
+                Stmt::Assign(destination, value, ..) => {
+                    self.indexExpr(destination, scope, uses);
+                    self.indexExpr(value, scope, uses);
+                }
+                Stmt::Expr(expr, ..) | Stmt::Return(expr, ..) => {
+                    self.indexExpr(expr, scope, uses);
+                }
+                Stmt::If(condition, body, elseBody, ..) => {
+                    self.indexExpr(condition, scope, uses);
+                    self.indexStmts(&[*body.clone()], scope, uses);
+                    if let Some(elseBody) = elseBody {
+                        self.indexStmts(&[*elseBody.clone()], scope, uses);
+                    }
+                }
+                Stmt::For(forDecl, condition, body, ..) => {
+                    self.indexStmts(&[(**forDecl).clone(), *body.clone()], scope, uses);
+                    self.indexExpr(condition, scope, uses);
+                }
+                _ => {}
+            }
+        }
+        return procs;
+    }
+
+    //Adds the names used in an expression
+    fn indexExpr(&self, expr: &Expr, scope: Option<&HashItem>, uses: &mut Vec<NameUse>) {
+        match expr {
+            Expr::VarRef(varName, span) => {
+                self.addUse(varName, *span, false, scope, uses);
+            }
+            //An index or a call starts with the name
+            Expr::ArrayRef(varName, index, span) => {
+                self.addUse(varName, nameAt(span, varName), false, scope, uses);
+                self.indexExpr(index, scope, uses);
+            }
+            Expr::ProcRef(procName, params, span) => {
+                self.addUse(procName, nameAt(span, procName), true, scope, uses);
+                for param in params.iter().flatten() {
+                    self.indexExpr(param, scope, uses);
+                }
+            }
+            Expr::ArthOp(left, _, right, _) | Expr::RelOp(left, _, right, _) | Expr::LogOp(left, _, right, _) => {
+                self.indexExpr(left, scope, uses);
+                self.indexExpr(right, scope, uses);
+            }
+            _ => {}
+        }
+    }
+
+    //Adds a use of a name if it resolves
+    fn addUse(&self, name: &String, span: Span, call: bool, scope: Option<&HashItem>, uses: &mut Vec<NameUse>) {
+        if let Some((item, global)) = self.resolve(name, call, scope) {
+            uses.push(NameUse { span, item, global });
+        }
+    }
+
+    //Finds the entry a name refers to the way the checker does
+    //A call to the procedure being checked is to itself, then the procedure's own table is searched, then the global table
+    //A nested procedure doesn't see the locals of the procedures around it
+    pub fn resolve(&self, name: &String, call: bool, scope: Option<&HashItem>) -> Option<(HashItem, bool)> {
+        if let Some(procItem) = scope {
+            if call && &procItem.getName() == name {
+                return Some((procItem.clone(), false));
+            }
+            if let HashItemType::Procedure(_, _, procTable) = procItem.getHashType() {
+                if let Some(item) = procTable.get(name) {
+                    return Some((item.clone(), false));
+                }
+            }
+        }
+        return self.globalTable.get(name).map(|item| (item.clone(), true));
+    }
+
+    //The entry for a procedure declared in a scope, the checker puts the ones in the program in the global table
+    fn declared(&self, procName: &String, nameSpan: Span, scope: Option<&HashItem>) -> Option<HashItem> {
+        let (item, _) = self.resolve(procName, false, scope)?;
+        match (item.getHashType(), item.getSpan()) {
+            (HashItemType::Procedure(..), Some(span)) if span.samePlace(&nameSpan) => Some(item),
+            _ => None,
+        }
+    }
+
+    //The name under a position, the position is 0 based like the protocol
+    pub fn useAt(&self, line: usize, col: usize) -> Option<&NameUse> {
+        return self.uses.iter().find(|nameUse| nameUse.span.contains(line + 1, col));
+    }
+
+    //The uses of the entry declared at a span, the declaration is one of them
+    pub fn usesOf(&self, declared: &Span) -> Vec<&NameUse> {
+        return self.uses.iter().filter(|nameUse| nameUse.item.getSpan().map_or(false, |span| span.samePlace(declared))).collect();
+    }
+
+    //The procedures a position is in, outermost first
+    pub fn procsAt(&self, line: usize, col: usize) -> Vec<&ProcScope> {
+        let mut found: Vec<&ProcScope> = Vec::new();
+        let mut procs = &self.procs;
+        while let Some(proc) = procs.iter().find(|proc| proc.span.contains(line + 1, col)) {
+            found.push(proc);
+            procs = &proc.children;
+        }
+        return found;
+    }
+
+    //The entries that can be used at a position, innermost first, each name once
+    //Those are the entries of the table of the procedure the position is in and the global table, like the checker sees them
+    pub fn visibleAt(&self, line: usize, col: usize) -> Vec<(HashItem, bool)> {
+        let mut visible: Vec<(HashItem, bool)> = Vec::new();
+        if let Some(proc) = self.procsAt(line, col).last() {
+            visible.push((proc.item.clone(), false));
+            if let HashItemType::Procedure(_, _, procTable) = proc.item.getHashType() {
+                visible.extend(procTable.getItems().into_iter().map(|item| (item, false)));
+            }
+        }
+        visible.extend(self.globalTable.getItems().into_iter().map(|item| (item, true)));
+
+        let mut seen: Vec<String> = Vec::new();
+        visible.retain(|(item, _)| {
+            if seen.contains(&item.getName()) {
+                return false;
+            }
+            seen.push(item.getName());
+            return true;
+        });
+        return visible;
+    }
+
+    //The signature of an entry as it would be written in the source
+    pub fn describe(item: &HashItem, global: bool) -> String {
+        let mut item = item.clone();
+        let name = item.getName().to_lowercase();
+        let itemType = item.getType();
+        match item.getHashType() {
+            HashItemType::Procedure(_, paramNames, procTable) => {
+                let mut params: Vec<String> = Vec::new();
+                for paramName in paramNames.iter() {
+                    let paramType = procTable.get(paramName).map(|param| typeName(&param.clone().getType())).unwrap_or("?".to_string());
+                    params.push(format!("variable {} : {}", paramName, paramType));
+                }
+                let signature = format!("procedure {} : {}({})", name, typeName(&itemType), params.join(", "));
+                //The builtins aren't declared in the source
+                if item.getSpan().is_none() {
+                    return format!("{} (built in)", signature);
+                }
+                return signature;
+            }
+            HashItemType::Variable => {
+                let prefix = if global { "global variable" } else { "variable" };
+                return format!("{} {} : {}", prefix, name, typeName(&itemType));
+            }
+        }
+    }
+}
+
+//The span of the name at the start of an index or a call
+fn nameAt(span: &Span, name: &String) -> Span {
+    return Span::onLine(span.startLine, span.startCol, span.startCol + name.chars().count());
+}
+
+//The name of a type as it is written in the source
+pub fn typeName(varType: &VarType) -> String {
+    match varType {
+        VarType::Int => "integer".to_string(),
+        VarType::Float => "float".to_string(),
+        VarType::Bool => "bool".to_string(),
+        VarType::Str => "string".to_string(),
+        VarType::IntArray(size) => format!("integer[{}]", size),
+    }
+}
+
+///////////////////////// /ANALYSIS SECTION /////////////////////////
+
+
+
+///////////////////////// PROTOCOL SECTION /////////////////////////
+//This section speaks the language server protocol (JSON-RPC with Content-Length headers) over any reader and writer
+//The binary uses stdin and stdout, a scripted client can use pipes or in-memory buffers
+
+//The LSP symbol and completion kinds that are used
+const SYMBOL_KIND_FUNCTION: u32 = 12;
+const COMPLETION_KIND_FUNCTION: u32 = 3;
+const COMPLETION_KIND_VARIABLE: u32 = 6;
+const DIAGNOSTIC_ERROR: u32 = 1;
+
+//The JSON-RPC error for a method the server doesn't handle
+const METHOD_NOT_FOUND: i64 = -32601;
+
+//An open document
+struct Document {
+    text: String,
+    version: i64,
+    analysis: Analysis,
+}
+
+//The master struct for the language server
+pub struct LanguageServer {
+    documents: HashMap<String, Document>,   //The open documents by uri
+    shutdownRequested: bool,                //Set by the shutdown request, exit then ends with status 0
+}
+
+impl LanguageServer {
+    //The constructor
+    pub fn new() -> LanguageServer {
+        LanguageServer {
+            documents: HashMap::new(),
+            shutdownRequested: false,
+        }
+    }
+
+    //Runs the server until the exit notification or the end of the input, returns the exit status
+    pub fn run<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> i32 {
+        loop {
+            let message = match readMessage(&mut input) {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    //The client went away
+                    return if self.shutdownRequested { 0 } else { 1 };
+                }
+                Err(errMsg) => {
+                    eprintln!("Language server: {}", errMsg);
+                    continue;
+                }
+            };
+
+            match self.handleMessage(&message, &mut output) {
+                Ok(Some(exitCode)) => {
+                    return exitCode;
+                }
+                Ok(None) => {}
+                Err(errMsg) => {
+                    eprintln!("Language server: {}", errMsg);
+                    return 1;
+                }
+            }
+        }
+    }
+
+    //Handles one message, returns the exit status once the exit notification arrives
+    fn handleMessage<W: Write>(&mut self, message: &Value, output: &mut W) -> Result<Option<i32>, String> {
+        let method = message["method"].as_str().unwrap_or("").to_string();
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+
+        //Notifications have no id and get no response
+        match method.as_str() {
+            "initialized" | "$/cancelRequest" | "textDocument/didSave" => {
+                return Ok(None);
+            }
+            "exit" => {
+                return Ok(Some(if self.shutdownRequested { 0 } else { 1 }));
+            }
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+                let text = params["textDocument"]["text"].as_str().unwrap_or("").to_string();
+                let version = params["textDocument"]["version"].as_i64().unwrap_or(0);
+                self.updateDocument(&uri, text, version);
+                self.publishDiagnostics(&uri, output)?;
+                return Ok(None);
+            }
+            "textDocument/didChange" => {
+                //The server asks for full syncs, so the last change has the whole text
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+                let version = params["textDocument"]["version"].as_i64().unwrap_or(0);
+                if let Some(changes) = params["contentChanges"].as_array() {
+                    if let Some(text) = changes.last().and_then(|change| change["text"].as_str()) {
+                        self.updateDocument(&uri, text.to_string(), version);
+                        self.publishDiagnostics(&uri, output)?;
+                    }
+                }
+                return Ok(None);
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+                self.documents.remove(&uri);
+                //Clears the diagnostics of the closed document
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                });
+                writeMessage(output, &notification)?;
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        //Everything else is a request
+        let id = match id {
+            Some(id) => id,
+            None => {
+                //An unknown notification is ignored
+                return Ok(None);
+            }
+        };
+        let result: Result<Value, (i64, String)> = match method.as_str() {
+            "initialize" => Ok(self.initialize()),
+            "shutdown" => {
+                self.shutdownRequested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => Ok(self.definition(params)),
+            "textDocument/references" => Ok(self.references(params)),
+            "textDocument/hover" => Ok(self.hover(params)),
+            "textDocument/completion" => Ok(self.completion(params)),
+            "textDocument/documentSymbol" => Ok(self.documentSymbols(params)),
+            _ => Err((METHOD_NOT_FOUND, format!("Method '{}' is not supported", method))),
+        };
+
+        let response = match result {
+            Ok(value) => json!({ "jsonrpc": "2.0", "id": id, "result": value }),
+            Err((code, errMsg)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": errMsg } }),
+        };
+        writeMessage(output, &response)?;
+        return Ok(None);
+    }
+
+    //The capabilities of the server
+    fn initialize(&self) -> Value {
+        return json!({
+            "capabilities": {
+                "textDocumentSync": 1,
+                "definitionProvider": true,
+                "referencesProvider": true,
+                "hoverProvider": true,
+                "completionProvider": { "triggerCharacters": [] },
+                "documentSymbolProvider": true,
+            },
+            "serverInfo": { "name": "compiler-lsp", "version": env!("CARGO_PKG_VERSION") },
+        });
+    }
+
+    //Stores the new text of a document and analyzes it
+    fn updateDocument(&mut self, uri: &String, text: String, version: i64) {
+        let fileName = uri.trim_start_matches("file://").to_string();
+        let analysis = Analysis::new(&fileName, &text);
+        self.documents.insert(uri.clone(), Document { text, version, analysis });
+    }
+
+    //Sends the diagnostics of a document to the client
+    fn publishDiagnostics<W: Write>(&self, uri: &String, output: &mut W) -> Result<(), String> {
+        let document = match self.documents.get(uri) {
+            Some(document) => document,
+            None => {
+                return Ok(());
+            }
+        };
+        let mut diagnostics: Vec<Value> = Vec::new();
+        for diagnostic in document.analysis.diagnostics.iter() {
+            //An error that isn't about one place is shown at the start of the document
+            let errorRange = match diagnostic.span {
+                Some(span) => spanRange(&span),
+                None => range(0, 0, 0, 0),
+            };
+            diagnostics.push(json!({
+                "range": errorRange,
+                "severity": DIAGNOSTIC_ERROR,
+                "source": "compiler",
+                "message": diagnostic.message,
+            }));
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "version": document.version, "diagnostics": diagnostics },
+        });
+        return writeMessage(output, &notification);
+    }
+
+    //Gets the document and the position from the params of a position request
+    fn position(&self, params: &Value) -> Option<(String, &Analysis, usize, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?.to_string();
+        let line = params["position"]["line"].as_u64()? as usize;
+        let col = params["position"]["character"].as_u64()? as usize;
+        let document = self.documents.get(&uri)?;
+        return Some((uri, &document.analysis, line, col));
+    }
+
+    //Go to definition
+    fn definition(&self, params: &Value) -> Value {
+        let (uri, analysis, line, col) = match self.position(params) {
+            Some(found) => found,
+            None => return Value::Null,
+        };
+        match analysis.useAt(line, col).and_then(|nameUse| nameUse.item.getSpan()) {
+            Some(declared) => {
+                return json!({ "uri": uri, "range": spanRange(&declared) });
+            }
+            None => {
+                return Value::Null;
+            }
+        }
+    }
+
+    //Find references
+    fn references(&self, params: &Value) -> Value {
+        let (uri, analysis, line, col) = match self.position(params) {
+            Some(found) => found,
+            None => return json!([]),
+        };
+        let includeDecl = params["context"]["includeDeclaration"].as_bool().unwrap_or(true);
+        let declared = match analysis.useAt(line, col).and_then(|nameUse| nameUse.item.getSpan()) {
+            Some(declared) => declared,
+            None => return json!([]),
+        };
+        let mut locations: Vec<Value> = Vec::new();
+        for nameUse in analysis.usesOf(&declared) {
+            if nameUse.span.samePlace(&declared) && !includeDecl {
+                continue;
+            }
+            locations.push(json!({ "uri": uri, "range": spanRange(&nameUse.span) }));
+        }
+        return json!(locations);
+    }
+
+    //Hover, shows the type of a variable or the signature of a procedure
+    fn hover(&self, params: &Value) -> Value {
+        let (uri, analysis, line, col) = match self.position(params) {
+            Some(found) => found,
+            None => return Value::Null,
+        };
+        let nameUse = match analysis.useAt(line, col) {
+            Some(nameUse) => nameUse,
+            None => return Value::Null,
+        };
+        let contents = Analysis::describe(&nameUse.item, nameUse.global);
+        return json!({
+            "contents": { "kind": "markdown", "value": format!("```\n{}\n```", contents) },
+            "range": spanRange(&nameUse.span),
+        });
+    }
+
+    //Completion of the variables and procedures in scope, the builtins are in every table
+    fn completion(&self, params: &Value) -> Value {
+        let (uri, analysis, line, col) = match self.position(params) {
+            Some(found) => found,
+            None => return json!([]),
+        };
+        let mut items: Vec<Value> = Vec::new();
+        for (item, global) in analysis.visibleAt(line, col) {
+            let kind = match item.getHashType() {
+                HashItemType::Procedure(..) => COMPLETION_KIND_FUNCTION,
+                HashItemType::Variable => COMPLETION_KIND_VARIABLE,
+            };
+            items.push(json!({ "label": item.getName().to_lowercase(), "kind": kind, "detail": Analysis::describe(&item, global) }));
+        }
+        return json!(items);
+    }
+
+    //The procedures of a document, nested procedures are children of the one they are declared in
+    fn documentSymbols(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+        let analysis = match self.documents.get(&uri) {
+            Some(document) => &document.analysis,
+            None => return json!([]),
+        };
+        return json!(LanguageServer::procSymbols(&analysis.procs));
+    }
+
+    //The document symbols of procedures and the ones nested in them
+    fn procSymbols(procs: &Vec<ProcScope>) -> Vec<Value> {
+        let mut symbols: Vec<Value> = Vec::new();
+        for proc in procs.iter() {
+            symbols.push(json!({
+                "name": proc.item.getName().to_lowercase(),
+                "detail": Analysis::describe(&proc.item, false),
+                "kind": SYMBOL_KIND_FUNCTION,
+                "range": spanRange(&proc.span),
+                "selectionRange": spanRange(&proc.nameSpan),
+                "children": LanguageServer::procSymbols(&proc.children),
+            }));
+        }
+        return symbols;
+    }
+}
+
+//An LSP range
+fn range(startLine: usize, startCol: usize, endLine: usize, endCol: usize) -> Value {
+    return json!({
+        "start": { "line": startLine, "character": startCol },
+        "end": { "line": endLine, "character": endCol },
+    });
+}
+
+//The range of a span, the protocol counts lines from 0
+fn spanRange(span: &Span) -> Value {
+    return range(span.startLine.saturating_sub(1), span.startCol, span.endLine.saturating_sub(1), span.endCol);
+}
+
+//Reads one message, None at the end of the input
+pub fn readMessage<R: BufRead>(input: &mut R) -> Result<Option<Value>, String> {
+    //Reads the headers until the blank line
+    let mut contentLength: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        let read = input.read_line(&mut header).map_err(|err| format!("Error reading header: {}", err))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header == "" {
+            if contentLength.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(length) = header.strip_prefix("Content-Length:") {
+            contentLength = Some(length.trim().parse::<usize>().map_err(|err| format!("Bad Content-Length '{}': {}", length.trim(), err))?);
+        }
+    }
+
+    let mut body = vec![0u8; contentLength.unwrap_or(0)];
+    input.read_exact(&mut body).map_err(|err| format!("Error reading message body: {}", err))?;
+    let message: Value = serde_json::from_slice(&body).map_err(|err| format!("Bad JSON in message: {}", err))?;
+    return Ok(Some(message));
+}
+
+//Writes one message with its header
+pub fn writeMessage<W: Write>(output: &mut W, message: &Value) -> Result<(), String> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body).map_err(|err| format!("Error writing message: {}", err))?;
+    output.flush().map_err(|err| format!("Error writing message: {}", err))?;
+    return Ok(());
+}
+
+///////////////////////// /PROTOCOL SECTION /////////////////////////
@@ -2,4 +2,21 @@ pub mod lexer;
 pub mod parser;
 pub mod typechecker;
 pub mod reporting;
-pub mod compiler;
\ No newline at end of file
+pub mod builtins;
+#[cfg(feature = "llvm")]
+pub mod compiler;
+#[cfg(feature = "interp")]
+pub mod interpreter;
+#[cfg(feature = "llvm")]
+pub mod jit;
+pub mod linker;
+pub mod resolver;
+pub mod lsp;
+pub mod frontend;
+pub mod fuzzing;
+pub mod formatter;
+pub mod cst;
+pub mod emit;
+#[cfg(feature = "llvm")]
+pub mod optimizer;
+pub mod options;
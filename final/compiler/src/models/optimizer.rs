@@ -0,0 +1,256 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::options::OptLevel,
+    inkwell::{
+        attributes::{Attribute, AttributeLoc},
+        module::Module,
+        passes::{PassManager, PassManagerSubType},
+        values::FunctionValue,
+        OptimizationLevel,
+    },
+    std::time::{Duration, Instant},
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// OPTIMIZER SECTION /////////////////////////
+//This section runs the LLVM optimization pipeline over the module that the Compiler generates
+//Each pass is run on its own so that it can be listed with --print-passes and timed with --time-passes
+
+//OptLevel is parsed in options, the conversion to the LLVM level lives here so options doesn't need LLVM
+impl OptLevel {
+    //The optimization level given to the TargetMachine for instruction selection
+    pub fn toLlvm(&self) -> OptimizationLevel {
+        match self {
+            OptLevel::O0 => OptimizationLevel::None,
+            OptLevel::O1 => OptimizationLevel::Less,
+            OptLevel::O2 => OptimizationLevel::Default,
+            OptLevel::O3 => OptimizationLevel::Aggressive,
+            OptLevel::Os => OptimizationLevel::Default,
+        }
+    }
+}
+
+//The result of running one pass, used for the --time-passes report
+#[derive(Debug, Clone)]
+pub struct PassTiming {
+    pub name: String,       //The name of the pass
+    pub kind: String,       //"function" or "module"
+    pub time: Duration,     //How long the pass took over the whole module
+    pub changed: bool,      //If the pass changed anything
+}
+
+//The optimizer structure, holds the selected level and the reporting flags
+pub struct Optimizer {
+    pub level: OptLevel,
+    pub printPasses: bool,
+    pub timePasses: bool,
+}
+
+impl Optimizer {
+    //The constructor
+    pub fn new(level: OptLevel, printPasses: bool, timePasses: bool) -> Optimizer {
+        Optimizer {
+            level,
+            printPasses,
+            timePasses,
+        }
+    }
+
+    //The function passes for the selected level, these are run on every defined function
+    pub fn functionPasses(&self) -> Vec<&'static str> {
+        match self.level {
+            OptLevel::O0 => vec![],
+            OptLevel::O1 => vec!["mem2reg", "instcombine", "simplifycfg"],
+            OptLevel::O2 => vec!["mem2reg", "early-cse", "instcombine", "reassociate", "gvn", "simplifycfg"],
+            //-O3 also breaks up aggregates and threads jumps over known conditions
+            OptLevel::O3 => vec![
+                "mem2reg", "sroa", "early-cse", "jump-threading", "correlated-propagation",
+                "instcombine", "reassociate", "gvn", "instcombine", "simplifycfg",
+            ],
+            //-Os leaves out reassociate, it only reorders expressions to help the later passes and never makes code smaller
+            OptLevel::Os => vec!["mem2reg", "sroa", "early-cse", "instcombine", "gvn", "simplifycfg"],
+        }
+    }
+
+    //The module passes for the selected level, these run after the function passes
+    pub fn modulePasses(&self) -> Vec<&'static str> {
+        match self.level {
+            OptLevel::O0 => vec![],
+            OptLevel::O1 => vec!["always-inline", "globaldce"],
+            OptLevel::O2 => vec![
+                "inline", "sccp", "instcombine", "simplifycfg",
+                "loop-rotate", "licm", "indvars", "loop-deletion",
+                "gvn", "tailcallelim", "dse", "simplifycfg", "globaldce",
+            ],
+            OptLevel::O3 => vec![
+                "inline", "sccp", "instcombine", "simplifycfg",
+                "loop-rotate", "licm", "indvars", "loop-deletion",
+                //Unrolling goes after gvn, which forwards the loads of the loop variable so the trip count is known
+                "gvn", "loop-unroll", "tailcallelim", "dse", "adce", "instcombine", "simplifycfg", "globaldce",
+            ],
+            //No inlining or unrolling, and identical functions and constants are merged
+            OptLevel::Os => vec![
+                "always-inline", "sccp", "instcombine", "simplifycfg",
+                "licm", "loop-deletion", "tailcallelim", "dse",
+                "merge-functions", "constmerge", "simplifycfg", "globaldce",
+            ],
+        }
+    }
+
+    //Runs the full pipeline on the module, returns the timing of each pass
+    //Prints the pass list and the timing report if those flags were given
+    pub fn optimize(&self, module: &Module) -> Result<Vec<PassTiming>, String> {
+        let mut timings: Vec<PassTiming> = Vec::new();
+
+        if self.printPasses {
+            println!("Optimization pipeline for {}:", self.level);
+            println!("  Function passes: {}", self.functionPasses().join(", "));
+            println!("  Module passes: {}", self.modulePasses().join(", "));
+        }
+
+        //Gets the functions that have bodies, the builtins are only declarations and are skipped
+        let definedFuncs: Vec<FunctionValue> = module.get_functions()
+            .filter(|func| func.count_basic_blocks() > 0)
+            .collect();
+
+        //-Os marks every function optsize, so the passes and the instruction selection prefer smaller code
+        if self.level == OptLevel::Os {
+            Optimizer::markOptSize(module, &definedFuncs);
+        }
+
+        //Runs each function pass over every defined function
+        for passName in self.functionPasses() {
+            let fpm: PassManager<FunctionValue> = PassManager::create(module);
+            let addCheck = Optimizer::addPass(&fpm, passName);
+            if let Err(errMsg) = addCheck {
+                return Err(errMsg);
+            }
+
+            let start = Instant::now();
+            let mut changed = false;
+            fpm.initialize();
+            for func in definedFuncs.iter() {
+                if fpm.run_on(func) {
+                    changed = true;
+                }
+            }
+            fpm.finalize();
+
+            timings.push(PassTiming {
+                name: passName.to_string(),
+                kind: "function".to_string(),
+                time: start.elapsed(),
+                changed,
+            });
+        }
+
+        //Runs each module pass over the whole module
+        for passName in self.modulePasses() {
+            let mpm: PassManager<Module> = PassManager::create(());
+            let addCheck = Optimizer::addPass(&mpm, passName);
+            if let Err(errMsg) = addCheck {
+                return Err(errMsg);
+            }
+
+            let start = Instant::now();
+            let changed = mpm.run_on(module);
+
+            timings.push(PassTiming {
+                name: passName.to_string(),
+                kind: "module".to_string(),
+                time: start.elapsed(),
+                changed,
+            });
+        }
+
+        //Makes sure the optimized module is still valid
+        if let Err(errMsg) = module.verify() {
+            return Err(format!("Module failed verification after optimization: {}", errMsg.to_string()));
+        }
+
+        if self.timePasses {
+            Optimizer::printTimings(&timings);
+        }
+
+        return Ok(timings);
+    }
+
+    //Adds the optsize attribute to the functions
+    fn markOptSize(module: &Module, definedFuncs: &Vec<FunctionValue>) {
+        let context = module.get_context();
+        let optSize = context.create_enum_attribute(Attribute::get_named_enum_kind_id("optsize"), 0);
+        for func in definedFuncs.iter() {
+            func.add_attribute(AttributeLoc::Function, optSize);
+        }
+    }
+
+    //Adds a pass to a pass manager by its name
+    fn addPass<T: PassManagerSubType>(pm: &PassManager<T>, passName: &str) -> Result<(), String> {
+        match passName {
+            "mem2reg" => pm.add_promote_memory_to_register_pass(),
+            "early-cse" => pm.add_early_cse_pass(),
+            "instcombine" => pm.add_instruction_combining_pass(),
+            "reassociate" => pm.add_reassociate_pass(),
+            "gvn" => pm.add_gvn_pass(),
+            "simplifycfg" => pm.add_cfg_simplification_pass(),
+            "sccp" => pm.add_sccp_pass(),
+            "inline" => pm.add_function_inlining_pass(),
+            "always-inline" => pm.add_always_inliner_pass(),
+            "globaldce" => pm.add_global_dce_pass(),
+            "loop-rotate" => pm.add_loop_rotate_pass(),
+            "licm" => pm.add_licm_pass(),
+            "indvars" => pm.add_ind_var_simplify_pass(),
+            "loop-unroll" => pm.add_loop_unroll_pass(),
+            "loop-deletion" => pm.add_loop_deletion_pass(),
+            "tailcallelim" => pm.add_tail_call_elimination_pass(),
+            "dse" => pm.add_dead_store_elimination_pass(),
+            "adce" => pm.add_aggressive_dce_pass(),
+            "sroa" => pm.add_scalar_repl_aggregates_pass(),
+            "jump-threading" => pm.add_jump_threading_pass(),
+            "correlated-propagation" => pm.add_correlated_value_propagation_pass(),
+            "merge-functions" => pm.add_merge_functions_pass(),
+            "constmerge" => pm.add_constant_merge_pass(),
+            _ => {
+                return Err(format!("Unknown optimization pass '{}'", passName));
+            }
+        }
+        return Ok(());
+    }
+
+    //Prints the --time-passes report, slowest pass first
+    pub fn printTimings(timings: &Vec<PassTiming>) {
+        let mut sorted = timings.clone();
+        sorted.sort_by(|a, b| b.time.cmp(&a.time));
+
+        let total: Duration = timings.iter().map(|t| t.time).sum();
+
+        println!("===== Pass execution timing report =====");
+        println!("  Total: {:.3} ms", total.as_secs_f64() * 1000.0);
+        println!("  {:>10}  {:>6}  {:<9} {}", "Time (ms)", "%", "Kind", "Pass");
+        for timing in sorted {
+            let percent = if total.as_nanos() == 0 {
+                0.0
+            } else {
+                timing.time.as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            let changedStr = if timing.changed { "" } else { " (no change)" };
+            println!("  {:>10.3}  {:>5.1}%  {:<9} {}{}", timing.time.as_secs_f64() * 1000.0, percent, timing.kind, timing.name, changedStr);
+        }
+    }
+}
+
+///////////////////////// /OPTIMIZER SECTION /////////////////////////
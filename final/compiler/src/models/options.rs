@@ -0,0 +1,348 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::models::parser::NumberWidth,
+    std::fmt,
+    std::path::Path,
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// OPTIONS SECTION /////////////////////////
+//This section parses the command line arguments into the options that are used by main
+
+//The optimization levels that can be selected from the command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptLevel {
+    O0,     //No optimization
+    O1,     //Cleanup passes only (mem2reg, instcombine, simplifycfg)
+    O2,     //Adds GVN, inlining and the loop passes
+    O3,     //Adds loop unrolling, jump threading and aggressive dead code elimination
+    Os,     //Like O2 but skips the passes that grow code size and marks functions optsize
+}
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptLevel::O0 => write!(f, "-O0"),
+            OptLevel::O1 => write!(f, "-O1"),
+            OptLevel::O2 => write!(f, "-O2"),
+            OptLevel::O3 => write!(f, "-O3"),
+            OptLevel::Os => write!(f, "-Os"),
+        }
+    }
+}
+
+//The subcommands, what the compiler should do with the input file
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompilerCommand {
+    Build,      //Compiles and links an executable (the default)
+    Run,        //JIT compiles the program and runs it in-process (compiler run prog.src)
+    Fmt,        //Rewrites the file in the canonical layout (compiler fmt prog.src)
+}
+
+//The backends that can run the checked program
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    Llvm,       //Generates LLVM IR (the default), needs the llvm feature
+    Interp,     //Walks the AST with the interpreter, needs the interp feature
+}
+
+//The front end output that can be written instead of compiling (--emit)
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmitKind {
+    TokensJson, //The lexer's tokens as JSON (--emit=tokens-json)
+    AstJson,    //The parser's AST as JSON (--emit=ast-json)
+    AstDot,     //The parser's AST as a Graphviz graph (--emit=ast-dot)
+    CfgDot,     //The basic blocks of each generated LLVM function as a Graphviz graph (--emit=cfg-dot), needs the llvm feature
+}
+impl EmitKind {
+    //The extension of the file that is written when -o isn't given
+    pub fn extension(&self) -> &'static str {
+        match self {
+            EmitKind::TokensJson => "tokens.json",
+            EmitKind::AstJson => "ast.json",
+            EmitKind::AstDot => "ast.dot",
+            EmitKind::CfgDot => "cfg.dot",
+        }
+    }
+}
+
+//What getinteger, getfloat, getbool and getstring do with input they can't read (--bad-input)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BadInput {
+    Abort,      //A runtime error (the default)
+    Reprompt,   //Says what was wrong and reads the next line
+    Default,    //Returns 0, 0.0, false or an empty string
+}
+impl BadInput {
+    //The code funcLib's rt_setup takes for it
+    pub fn code(&self) -> i32 {
+        match self {
+            BadInput::Abort => funcLib::BAD_INPUT_ABORT,
+            BadInput::Reprompt => funcLib::BAD_INPUT_REPROMPT,
+            BadInput::Default => funcLib::BAD_INPUT_DEFAULT,
+        }
+    }
+}
+
+//The structure that holds all of the command line options
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilerOptions {
+    pub command: CompilerCommand, //What to do with the input file
+    pub inputPath: String,      //The .src file that is being compiled
+    pub optLevel: OptLevel,     //The optimization level (-O0, -O1, -O2, -O3, -Os)
+    pub printPasses: bool,      //Prints the optimization pipeline before running it (--print-passes)
+    pub timePasses: bool,       //Times each optimization pass and prints a report (--time-passes)
+    pub verbose: bool,          //Prints the progress of the front end and code generator to stderr (-v, --verbose)
+    pub outputPath: Option<String>, //The file given with -o, None when it wasn't given
+    pub linker: Option<String>, //The linker to use instead of the one that is discovered (--linker)
+    pub libDirs: Vec<String>,   //Extra library search directories (-L)
+    pub libs: Vec<String>,      //Extra libraries to link (-l)
+    pub moduleDirs: Vec<String>, //Extra directories searched for imported modules (-I)
+    pub backend: Backend,       //The backend that runs the program (--backend)
+    pub check: bool,            //Only checks that the file is formatted, doesn't write it (fmt --check)
+    pub emit: Option<EmitKind>, //Writes the tokens or the AST instead of compiling (--emit)
+    pub debugInfo: bool,        //Adds DWARF debug info to the generated code (-g)
+    pub numberWidth: NumberWidth, //The size of integer and float, 64 bit with --wide-numbers
+    pub checkedArith: bool,     //Integer overflow and division by zero end the program with an error (--checked-arith)
+    pub errorExitCode: i32,     //The exit code of a program that stops with a runtime error (--error-exit-code, default 1)
+    pub badInput: BadInput,     //What the get builtins do with bad input (--bad-input)
+}
+
+impl CompilerOptions {
+    //The default options, used before the arguments are parsed
+    pub fn new() -> CompilerOptions {
+        CompilerOptions {
+            command: CompilerCommand::Build,
+            inputPath: "".to_string(),
+            optLevel: OptLevel::O0,
+            printPasses: false,
+            timePasses: false,
+            verbose: false,
+            outputPath: None,
+            linker: None,
+            libDirs: Vec::new(),
+            libs: Vec::new(),
+            moduleDirs: Vec::new(),
+            backend: Backend::Llvm,
+            check: false,
+            emit: None,
+            debugInfo: false,
+            numberWidth: NumberWidth::Bits32,
+            checkedArith: false,
+            errorExitCode: 1,
+            badInput: BadInput::Abort,
+        }
+    }
+
+    //Parses the list of arguments (without the executable name) into the options structure
+    //Returns an error message if an argument is not recognized or the input file is missing
+    pub fn parseArgs(args: Vec<String>) -> Result<CompilerOptions, String> {
+        let mut options = CompilerOptions::new();
+        let mut args = args;
+
+        //The subcommand has to be the first argument if it is given
+        if args.len() > 0 {
+            match args[0].as_str() {
+                "run" => {
+                    options.command = CompilerCommand::Run;
+                    args.remove(0);
+                }
+                "build" => {
+                    options.command = CompilerCommand::Build;
+                    args.remove(0);
+                }
+                "fmt" => {
+                    options.command = CompilerCommand::Fmt;
+                    args.remove(0);
+                }
+                _ => {
+                    //No subcommand, defaults to build
+                }
+            }
+        }
+
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i].clone();
+            match arg.as_str() {
+                "-O0" => options.optLevel = OptLevel::O0,
+                "-O1" => options.optLevel = OptLevel::O1,
+                "-O2" => options.optLevel = OptLevel::O2,
+                "-O3" => options.optLevel = OptLevel::O3,
+                "-Os" => options.optLevel = OptLevel::Os,
+                "--print-passes" => options.printPasses = true,
+                "--time-passes" => options.timePasses = true,
+                "-v" | "--verbose" => options.verbose = true,
+                "--check" => options.check = true,
+                "-g" => options.debugInfo = true,
+                "--wide-numbers" => options.numberWidth = NumberWidth::Bits64,
+                "--checked-arith" => options.checkedArith = true,
+                //The options that take a value in the next argument
+                "-o" | "--linker" | "-L" | "-l" | "-I" | "--backend" | "--emit" | "--error-exit-code" | "--bad-input" => {
+                    if i + 1 >= args.len() {
+                        return Err(format!("Option '{}' requires a value\n{}", arg, CompilerOptions::usage()));
+                    }
+                    i += 1;
+                    let value = args[i].clone();
+                    options.setValue(&arg, value)?;
+                }
+                _ => {
+                    //The options that have their value attached (-Lpath, -lname, --linker=ld)
+                    if arg.starts_with("--linker=") {
+                        options.setValue("--linker", arg["--linker=".len()..].to_string())?;
+                    } else if arg.starts_with("--backend=") {
+                        options.setValue("--backend", arg["--backend=".len()..].to_string())?;
+                    } else if arg.starts_with("--emit=") {
+                        options.setValue("--emit", arg["--emit=".len()..].to_string())?;
+                    } else if arg.starts_with("--error-exit-code=") {
+                        options.setValue("--error-exit-code", arg["--error-exit-code=".len()..].to_string())?;
+                    } else if arg.starts_with("--bad-input=") {
+                        options.setValue("--bad-input", arg["--bad-input=".len()..].to_string())?;
+                    } else if arg.starts_with("-L") {
+                        options.setValue("-L", arg[2..].to_string())?;
+                    } else if arg.starts_with("-l") {
+                        options.setValue("-l", arg[2..].to_string())?;
+                    } else if arg.starts_with("-I") {
+                        options.setValue("-I", arg[2..].to_string())?;
+                    }
+                    //Anything else that starts with a - is an unknown flag
+                    else if arg.starts_with("-") {
+                        return Err(format!("Unknown option '{}'\n{}", arg, CompilerOptions::usage()));
+                    }
+                    //Otherwise it is the input file, only one is allowed
+                    else if options.inputPath != "" {
+                        return Err(format!("Only one input file can be given, found '{}' and '{}'", options.inputPath, arg));
+                    } else {
+                        options.inputPath = arg.clone();
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        if options.inputPath == "" {
+            return Err(format!("Please specify an input file\n{}", CompilerOptions::usage()));
+        }
+        //The control flow graph is read from the LLVM module, the interpreter never makes one
+        if options.backend == Backend::Interp && options.emit == Some(EmitKind::CfgDot) {
+            return Err(format!("--emit=cfg-dot shows the generated LLVM code and can't be used with --backend=interp\n{}", CompilerOptions::usage()));
+        }
+
+        return Ok(options);
+    }
+
+    //Sets an option that takes a value
+    //Returns an error message if the value is not allowed for the option
+    fn setValue(&mut self, option: &str, value: String) -> Result<(), String> {
+        match option {
+            "-o" => self.outputPath = Some(value),
+            "--linker" => self.linker = Some(value),
+            "-L" => self.libDirs.push(value),
+            "-l" => self.libs.push(value),
+            "-I" => self.moduleDirs.push(value),
+            "--backend" => match value.as_str() {
+                "llvm" => self.backend = Backend::Llvm,
+                "interp" => self.backend = Backend::Interp,
+                _ => {
+                    return Err(format!("Unknown backend '{}', expected llvm or interp\n{}", value, CompilerOptions::usage()));
+                }
+            },
+            "--emit" => match value.as_str() {
+                "tokens-json" => self.emit = Some(EmitKind::TokensJson),
+                "ast-json" => self.emit = Some(EmitKind::AstJson),
+                "ast-dot" => self.emit = Some(EmitKind::AstDot),
+                "cfg-dot" => self.emit = Some(EmitKind::CfgDot),
+                _ => {
+                    return Err(format!("Unknown emit kind '{}', expected tokens-json, ast-json, ast-dot or cfg-dot\n{}", value, CompilerOptions::usage()));
+                }
+            },
+            "--error-exit-code" => match value.parse::<i32>() {
+                Ok(code) if code > 0 && code < 256 => self.errorExitCode = code,
+                _ => {
+                    return Err(format!("The exit code '{}' isn't a number from 1 to 255\n{}", value, CompilerOptions::usage()));
+                }
+            },
+            "--bad-input" => match value.as_str() {
+                "abort" => self.badInput = BadInput::Abort,
+                "reprompt" => self.badInput = BadInput::Reprompt,
+                "default" => self.badInput = BadInput::Default,
+                _ => {
+                    return Err(format!("Unknown bad input handling '{}', expected abort, reprompt or default\n{}", value, CompilerOptions::usage()));
+                }
+            },
+            _ => {
+                //Only called with the options above
+            }
+        }
+        return Ok(());
+    }
+
+    //The executable that is produced, -o if it was given, otherwise a.out
+    pub fn executablePath(&self) -> String {
+        match &self.outputPath {
+            Some(path) => return path.clone(),
+            None => return "a.out".to_string(),
+        }
+    }
+
+    //The file that --emit writes to, -o if it was given (even -o a.out), otherwise the input file with the emit kind's extension
+    pub fn emitPath(&self, kind: &EmitKind) -> String {
+        if let Some(path) = &self.outputPath {
+            return path.clone();
+        }
+        let input = Path::new(&self.inputPath);
+        let stem = input.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or("out".to_string());
+        return input.with_file_name(format!("{}.{}", stem, kind.extension())).display().to_string();
+    }
+
+    //The usage message printed when the arguments are wrong
+    pub fn usage() -> String {
+        let mut usage = String::new();
+        usage.push_str("Usage: compiler [build|run|fmt] [options] <file.src>\n");
+        usage.push_str("Commands:\n");
+        usage.push_str("  build                     Compile and link an executable (default)\n");
+        usage.push_str("  run                       JIT compile the program and run it, exits with its status\n");
+        usage.push_str("  fmt                       Rewrite the file in the canonical layout, comments are kept\n");
+        usage.push_str("Options:\n");
+        usage.push_str("  -O0, -O1, -O2, -O3, -Os   Optimization level (default -O0)\n");
+        usage.push_str("  -g                        Add DWARF debug info so gdb and lldb can break on .src lines and print variables\n");
+        usage.push_str("  --wide-numbers            Make integer a 64 bit integer and float a double instead of 32 bit\n");
+        usage.push_str("  --checked-arith           Stop with the line number on integer overflow or division by zero instead of wrapping\n");
+        usage.push_str("  --error-exit-code <code>  Exit code of a program that stops with a runtime error, 1 to 255 (default 1)\n");
+        usage.push_str("  --bad-input <mode>        What getinteger, getfloat and getbool do with input that isn't a value:\n");
+        usage.push_str("                              abort      Stop with a runtime error (default)\n");
+        usage.push_str("                              reprompt   Say what was wrong and read the next line\n");
+        usage.push_str("                              default    Return 0, 0.0 or false\n");
+        usage.push_str("  --print-passes            Print the optimization passes that are run\n");
+        usage.push_str("  --time-passes             Print how long each optimization pass took\n");
+        usage.push_str("  -v, --verbose             Print the lexer, parser, checker and code generator progress and the LLVM IR to stderr\n");
+        usage.push_str("  -o <file>                 Name of the executable that is produced (default a.out)\n");
+        usage.push_str("  --linker <linker>         Linker to use instead of the discovered one (cc, clang, gcc or ld)\n");
+        usage.push_str("  -L <dir>                  Add a library search directory, also searched for libfuncLib.a\n");
+        usage.push_str("  -l <name>                 Link an extra library\n");
+        usage.push_str("  -I <dir>                  Add a directory searched for imported modules (<name>.src)\n");
+        usage.push_str("  --backend <llvm|interp>   Backend to use, interp runs the program with the AST interpreter (default llvm)\n");
+        usage.push_str("  --check                   With fmt, don't write the file, exit with 1 if it isn't formatted\n");
+        usage.push_str("  --emit <kind>             Write the compiler's output instead of compiling, kind is one of:\n");
+        usage.push_str("                              tokens-json, ast-json   The tokens or the AST as JSON\n");
+        usage.push_str("                              ast-dot                 The AST as a Graphviz graph\n");
+        usage.push_str("                              cfg-dot                 The basic blocks of each LLVM function as a Graphviz graph, not with --backend=interp\n");
+        usage.push_str("                            Written to -o, or next to the input as <file>.<kind> (e.g. prog.ast.dot)\n");
+        return usage;
+    }
+}
+
+///////////////////////// /OPTIONS SECTION /////////////////////////
@@ -1,12 +1,86 @@
 
 
 
+//package imports
+use {
+    serde::Serialize,
+    std::sync::atomic::{AtomicBool, Ordering},
+};
+
+//If the lexer, parser, checker and code generator print their progress, set by --verbose
+//It goes to stderr so the output of a program started with run is the only thing on stdout
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+pub fn setVerbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub fn isVerbose() -> bool {
+    return VERBOSE.load(Ordering::Relaxed);
+}
+
+//Prints a line of compiler progress to stderr, only with --verbose
+#[macro_export]
+macro_rules! verboseln {
+    ($($arg:tt)*) => {
+        if $crate::models::reporting::isVerbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+//Where something is in the source, from the start of its first token to the end of its last one
+//Lines are 1 based and columns are 0 based and counted in characters, like the tokens
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Span {
+    pub startLine: usize,
+    pub startCol: usize,
+    pub endLine: usize,
+    pub endCol: usize,
+}
+
+impl Span {
+    //A span inside one line
+    pub fn onLine(line: usize, startCol: usize, endCol: usize) -> Span {
+        return Span { startLine: line, startCol, endLine: line, endCol };
+    }
+
+    //The span from the start of one span to the end of another
+    pub fn to(&self, end: Span) -> Span {
+        return Span {
+            startLine: self.startLine,
+            startCol: self.startCol,
+            endLine: end.endLine,
+            endCol: end.endCol,
+        };
+    }
+
+    //If two spans cover the same text, == doesn't look at spans
+    pub fn samePlace(&self, other: &Span) -> bool {
+        return (self.startLine, self.startCol, self.endLine, self.endCol) == (other.startLine, other.startCol, other.endLine, other.endCol);
+    }
+
+    //If the position is inside the span
+    pub fn contains(&self, line: usize, col: usize) -> bool {
+        return (self.startLine, self.startCol) <= (line, col) && (line, col) <= (self.endLine, self.endCol);
+    }
+}
+
+//Where a node is written doesn't make it a different node, so ASTs that say the same thing are equal
+impl PartialEq for Span {
+    fn eq(&self, other: &Span) -> bool {
+        return true;
+    }
+}
+
 //Structure for reporting errors and warnings
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Reporting {
     pub status: bool,
     pub warnings: Vec<String>,
     pub errors: Vec<String>,
+    #[serde(skip)]
+    pub errorSpans: Vec<Option<Span>>,  //Where each error is, None for errors that aren't about one place in the source
 }
 
 impl Reporting {
@@ -15,14 +89,38 @@ impl Reporting {
             status: false,
             warnings: Vec::new(),
             errors: Vec::new(),
+            errorSpans: Vec::new(),
         }
     }
 
     pub fn reportError(&mut self, message: String) {
         self.errors.push(message.clone());
+        self.errorSpans.push(None);
+        self.status = true;
+    }
+
+    //Reports an error about a place in the source
+    pub fn reportErrorAt(&mut self, message: String, span: Span) {
+        self.errors.push(message.clone());
+        self.errorSpans.push(Some(span));
         self.status = true;
     }
 
+    //Adds the errors and warnings of another report, like a procedure's to the program's
+    pub fn merge(&mut self, other: &Reporting) {
+        for (index, message) in other.errors.iter().enumerate() {
+            self.errors.push(message.clone());
+            self.errorSpans.push(other.spanOf(index));
+        }
+        self.warnings.extend(other.warnings.iter().cloned());
+        self.status = self.status || other.status;
+    }
+
+    //The span of an error
+    pub fn spanOf(&self, index: usize) -> Option<Span> {
+        return self.errorSpans.get(index).cloned().flatten();
+    }
+
     pub fn reportWarning(&mut self, message: String) {
         self.warnings.push(message.clone());
     }
@@ -0,0 +1,375 @@
+///////////////////////// Setup /////////////////////////
+
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//package imports
+use {
+    crate::verboseln,
+    crate::models::{
+        lexer::Lexer,
+        parser::{NumberWidth, Parser, Stmt},
+        reporting::Reporting,
+        typechecker::{HashItem, SymbolTable, SyntaxChecker},
+    },
+    std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+///////////////////////// /Setup /////////////////////////
+
+
+
+///////////////////////// RESOLVER SECTION /////////////////////////
+//This section finds the modules a program imports, loads and parses them, and checks them in dependency order
+//A module is found as <name>.src in the directory of the file that imports it, then in the -I directories
+//Every top level procedure and global of a module is exported to the units that import it directly
+//Imports are not passed on, and a top level name can only be declared once in the whole program
+
+//One compilation unit, the program or one of the modules it imports
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,           //The name of the program or module
+    pub path: String,           //The .src file the unit was read from
+    pub ast: Stmt,              //The Program or Module statement
+    pub imports: Vec<String>,   //The names of the modules the unit imports, in order
+}
+
+impl Unit {
+    //Creates a unit and collects the imports from its header
+    pub fn new(path: String, ast: Stmt) -> Unit {
+        let mut name = String::new();
+        let mut imports: Vec<String> = Vec::new();
+        for stmt in unitHeader(&ast) {
+            if let Stmt::Import(moduleName, lineNum, ..) = stmt {
+                imports.push(moduleName.clone());
+            }
+        }
+        match &ast {
+            Stmt::Program(progName, header, body, lineNum, ..) => {
+                name = progName.clone();
+            }
+            Stmt::Module(moduleName, header, lineNum, ..) => {
+                name = moduleName.clone();
+            }
+            _ => {}
+        }
+        Unit {
+            name,
+            path,
+            ast,
+            imports,
+        }
+    }
+
+    //If the unit is a module (every unit but the program)
+    pub fn isModule(&self) -> bool {
+        match self.ast {
+            Stmt::Module(..) => {
+                return true;
+            }
+            _ => {
+                return false;
+            }
+        }
+    }
+
+    //The top level procedures, external procedures and globals, these are what a module exports
+    pub fn exports(&self) -> Vec<Stmt> {
+        let mut exports: Vec<Stmt> = Vec::new();
+        for stmt in unitHeader(&self.ast) {
+            match stmt {
+                Stmt::ProcDecl(..) | Stmt::ExternProcDecl(..) | Stmt::GlobVarDecl(..) => {
+                    exports.push(stmt.clone());
+                }
+                _ => {}
+            }
+        }
+        return exports;
+    }
+}
+
+//The master struct for the resolver
+pub struct Resolver {
+    pub units: Vec<Unit>,           //Every unit of the program, a module comes before the units that import it and the program is last
+    pub moduleDirs: Vec<String>,    //The extra directories modules are searched in (-I)
+    pub numberWidth: NumberWidth,   //The size of integer and float, the modules are parsed with it like the program
+    pub reports: Reporting,         //The reporting structure, import and type errors are reported here
+    stack: Vec<String>,             //The modules that are being loaded, used to find import cycles
+}
+
+impl Resolver {
+    //The constructor
+    pub fn new(moduleDirs: Vec<String>) -> Resolver {
+        Resolver {
+            units: Vec::new(),
+            moduleDirs,
+            numberWidth: NumberWidth::Bits32,
+            reports: Reporting::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    //Loads every module the program imports, directly or through another module
+    //Returns false if a module is missing, doesn't parse, is part of an import cycle or declares a name twice
+    pub fn resolve(&mut self, programPath: &str, programAst: Stmt) -> bool {
+        let program = Unit::new(programPath.to_string(), programAst);
+        let imports = program.imports.clone();
+        for moduleName in imports {
+            self.loadModule(&moduleName, programPath);
+        }
+        self.units.push(program);
+
+        if !self.reports.status {
+            self.checkNames();
+        }
+        return !self.reports.status;
+    }
+
+    //Loads one module and everything it imports, the module is added after its imports
+    fn loadModule(&mut self, moduleName: &String, importerPath: &str) {
+        if self.units.iter().any(|unit| &unit.name == moduleName) {
+            return;
+        }
+        if let Some(start) = self.stack.iter().position(|name| name == moduleName) {
+            let mut cycle: Vec<String> = self.stack[start..].to_vec();
+            cycle.push(moduleName.clone());
+            self.reports.reportError(format!("Import cycle: {}", cycle.join(" -> ")));
+            return;
+        }
+
+        let modulePath: PathBuf;
+        match self.findModule(moduleName, importerPath) {
+            Ok(path) => {
+                modulePath = path;
+            }
+            Err(errMsg) => {
+                self.reports.reportError(errMsg);
+                return;
+            }
+        }
+        let pathString = modulePath.display().to_string();
+
+        let moduleAst: Stmt;
+        match parseModule(&pathString, self.numberWidth) {
+            Ok(ast) => {
+                moduleAst = ast;
+            }
+            Err(errMsg) => {
+                self.reports.reportError(errMsg);
+                return;
+            }
+        }
+        let unit = Unit::new(pathString.clone(), moduleAst);
+        if !unit.isModule() {
+            self.reports.reportError(format!("Error: {} is imported but is not a module", pathString));
+            return;
+        }
+        if &unit.name != moduleName {
+            self.reports.reportError(format!("Error: {} declares module {} but is imported as {}", pathString, unit.name, moduleName));
+            return;
+        }
+
+        self.stack.push(moduleName.clone());
+        for importName in unit.imports.clone() {
+            self.loadModule(&importName, &pathString);
+        }
+        self.stack.pop();
+        self.units.push(unit);
+    }
+
+    //Finds <name>.src next to the importing file, then in the -I directories
+    fn findModule(&self, moduleName: &String, importerPath: &str) -> Result<PathBuf, String> {
+        let fileName = format!("{}.src", moduleName);
+        let mut searchDirs: Vec<PathBuf> = Vec::new();
+        let importerDir = Path::new(importerPath).parent().map(|dir| dir.to_path_buf()).unwrap_or(PathBuf::from("."));
+        searchDirs.push(importerDir);
+        for dir in &self.moduleDirs {
+            searchDirs.push(PathBuf::from(dir));
+        }
+
+        for dir in &searchDirs {
+            let candidate = dir.join(&fileName);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+        let searched: Vec<String> = searchDirs.iter().map(|dir| dir.join(&fileName).display().to_string()).collect();
+        return Err(format!("Error: module {} imported by {} was not found, looked for {}", moduleName, importerPath, searched.join(", ")));
+    }
+
+    //Every top level name can only be declared by one unit, otherwise the linked program would have it twice
+    fn checkNames(&mut self) {
+        let mut owners: HashMap<String, String> = HashMap::new();
+        let mut errors: Vec<String> = Vec::new();
+        for unit in &self.units {
+            for export in unit.exports() {
+                //External procedures name a C function, they can be declared by more than one unit
+                if let Stmt::ExternProcDecl(..) = export {
+                    continue;
+                }
+                let name = declName(&export);
+                match owners.get(&name) {
+                    Some(owner) => {
+                        errors.push(format!("Error on line {}: {} is declared in both {} and {}", export.lineNum(), name, owner, unit.name));
+                    }
+                    None => {
+                        owners.insert(name, unit.name.clone());
+                    }
+                }
+            }
+        }
+        for errMsg in errors {
+            self.reports.reportError(errMsg);
+        }
+    }
+
+    //Type checks every unit in order, each one sees the procedures and globals of the modules it imports
+    pub fn checkUnits(&mut self) -> bool {
+        let mut exportItems: HashMap<String, Vec<HashItem>> = HashMap::new();
+        for unit in self.units.clone() {
+            let mut unitTable = SymbolTable::new();
+            for importName in &unit.imports {
+                for item in exportItems.get(importName).cloned().unwrap_or(Vec::new()) {
+                    unitTable.insert(item);
+                }
+            }
+
+            //The checker's name is only used to allow recursive calls, so every unit is checked as Main
+            let mut unitChecker = SyntaxChecker::new(unit.ast.clone(), &mut unitTable, "Main".to_string());
+            let unitValid = unitChecker.checkProgram();
+            let unitReports = unitChecker.reports.clone();
+            self.reports.errors.extend(unitReports.errors);
+            self.reports.warnings.extend(unitReports.warnings);
+            if !unitValid {
+                self.reports.status = true;
+                verboseln!("Error in {}", unit.path);
+                return false;
+            }
+
+            if unit.isModule() {
+                let mut items: Vec<HashItem> = Vec::new();
+                for export in unit.exports() {
+                    if let Some(item) = unitTable.get(&declName(&export)) {
+                        items.push(item.clone());
+                    }
+                }
+                exportItems.insert(unit.name.clone(), items);
+            }
+        }
+        return true;
+    }
+
+    //The program, the last unit
+    pub fn program(&self) -> &Unit {
+        return &self.units[self.units.len() - 1];
+    }
+
+    //The imported modules in dependency order
+    pub fn modules(&self) -> &[Unit] {
+        return &self.units[..self.units.len() - 1];
+    }
+
+    //The declarations a unit imports, used to declare them in the unit's llvm module
+    pub fn importedDecls(&self, unit: &Unit) -> Vec<Stmt> {
+        let mut decls: Vec<Stmt> = Vec::new();
+        for importName in &unit.imports {
+            if let Some(module) = self.units.iter().find(|module| &module.name == importName) {
+                decls.extend(module.exports());
+            }
+        }
+        return decls;
+    }
+
+    //One program with the declarations of every module put before the program's own header, for the interpreter
+    pub fn mergeUnits(&self) -> Stmt {
+        let mut mergedHeader: Vec<Stmt> = Vec::new();
+        for module in self.modules() {
+            mergedHeader.extend(module.exports());
+        }
+        match self.program().ast.clone() {
+            Stmt::Program(name, header, body, lineNum, programSpan) => {
+                let mut headerLine = lineNum.clone();
+                let mut headerSpan = programSpan;
+                if let Stmt::Block(instrs, blockLine, blockSpan) = *header {
+                    mergedHeader.extend(instrs);
+                    headerLine = blockLine;
+                    headerSpan = blockSpan;
+                }
+                return Stmt::Program(name, Box::new(Stmt::Block(mergedHeader, headerLine, headerSpan)), body, lineNum, programSpan);
+            }
+            other => {
+                return other;
+            }
+        }
+    }
+}
+
+//Lexes and parses a module file
+fn parseModule(path: &str, numberWidth: NumberWidth) -> Result<Stmt, String> {
+    let source = fs::read_to_string(path).map_err(|err| format!("Error reading {}: {}", path, err))?;
+    let mut moduleLexer = Lexer::newFromString(path, source);
+    moduleLexer.scanThrough();
+    if moduleLexer.reports.status {
+        return Err(format!("Error in lexer for {}: {:?}", path, moduleLexer.reports.errors));
+    }
+    let mut moduleParser = Parser::new(&mut moduleLexer);
+    moduleParser.numberWidth = numberWidth;
+    match moduleParser.startParse() {
+        Ok((reporting, Some(stmt))) => {
+            return Ok(stmt);
+        }
+        Ok((reporting, None)) => {
+            return Err(format!("Error: {} has no module in it", path));
+        }
+        Err(reporting) => {
+            return Err(format!("Error parsing {}: {:?}", path, reporting.errors));
+        }
+    }
+}
+
+//The statements in the header of a program or module
+fn unitHeader(ast: &Stmt) -> Vec<Stmt> {
+    let header = match ast {
+        Stmt::Program(name, header, body, lineNum, ..) => header,
+        Stmt::Module(name, header, lineNum, ..) => header,
+        _ => {
+            return Vec::new();
+        }
+    };
+    match header.as_ref() {
+        Stmt::Block(instrs, lineNum, ..) => {
+            return instrs.clone();
+        }
+        _ => {
+            return Vec::new();
+        }
+    }
+}
+
+//The name a top level procedure or global declares
+fn declName(decl: &Stmt) -> String {
+    match decl {
+        Stmt::ProcDecl(retType, procName, params, header, body, lineNum, ..) => {
+            return procName.clone();
+        }
+        Stmt::ExternProcDecl(retType, procName, params, lineNum, ..) => {
+            return procName.clone();
+        }
+        Stmt::GlobVarDecl(varName, varType, lineNum, ..) => {
+            return varName.clone();
+        }
+        _ => {
+            return String::new();
+        }
+    }
+}
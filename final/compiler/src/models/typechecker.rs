@@ -29,6 +29,9 @@ use {
     }, unicode_segmentation::UnicodeSegmentation,
     utf8_chars::BufReadCharsExt,
     crate::tokenTypeEnum,
+    crate::verboseln,
+    crate::models::reporting::{Reporting, Span},
+    crate::models::builtins::BUILTINS,
     crate::models::lexer::{
         Lexer,
         Token,
@@ -58,6 +61,9 @@ pub struct SyntaxChecker<'a> {
     pub globalTable: &'a mut SymbolTable,   //The global table, passed through every scope
     pub name: String,                       //the name of the program (or procedure if in a nested scope)
     pub checked: bool,                      //Set to true when the checking has been finished (used by procedures when scope increases)
+    pub reports: Reporting,                 //The type errors found, kept for tools like the language server
+    pub curLine: String,                    //The line of the statement being checked, used for errors without a line
+    pub curSpan: Span,                      //The span of the statement or expression being checked, its errors point at it
 }
 //The methods within typeChecker
 impl<'a> SyntaxChecker<'a> {
@@ -71,8 +77,9 @@ impl<'a> SyntaxChecker<'a> {
             globalTable,
             name,
             checked: false,
-
-
+            reports: Reporting::new(),
+            curLine: "0".to_string(),
+            curSpan: Span::default(),
         }
     }
 
@@ -94,30 +101,44 @@ impl<'a> SyntaxChecker<'a> {
             globalTable: self.globalTable,
             name,
             checked: false,
+            reports: Reporting::new(),
+            curLine: "0".to_string(),
+            curSpan: Span::default(),
+        }
+    }
+
+    //Reports a type error, it is printed like the rest of the checker output and kept in the reports
+    //Errors that don't say their line get the line of the statement being checked, every error gets the span being checked
+    fn reportError(&mut self, message: String) {
+        verboseln!("{}", message);
+        if message.to_lowercase().contains("line") {
+            self.reports.reportErrorAt(message, self.curSpan);
+        } else {
+            self.reports.reportErrorAt(format!("Error on line {}: {}", self.curLine, message), self.curSpan);
         }
     }
 
     //The main outward facing checker, checks the two parts of the program
     pub fn checkProgram(&mut self) -> bool {
         match &self.ast.clone() {
-            Stmt::Program(name, header, body, lineNum) => {
+            Stmt::Program(name, header, body, lineNum, ..) => {
                 
                 //Parses and checks the header
                 let head = header.clone();
                 let mut progHeader = *head;
                 // Check if the variable is a Block and iterate through it
-                if let Stmt::Block(ref instrs, lineNum) = progHeader.clone() {
+                if let Stmt::Block(ref instrs, lineNum, ..) = progHeader.clone() {
                     for instr in instrs {
                         let good = self.checkStmt(instr.clone());
                         if (!good){
-                            println!("Error in header:");
+                            verboseln!("Error in header:");
                             instr.display(0);
                             return false;
                         } else {
                         }
                     }
                 } else {
-                    println!("Problem with AST: header must be a Block");
+                    self.reportError("Problem with AST: header must be a Block".to_string());
                 }
 
                 // println!("Finished checking header:");
@@ -126,18 +147,18 @@ impl<'a> SyntaxChecker<'a> {
                 let main = body.clone();
                 let mut progBody = *main;
                 // Check if the variable is a Block and iterate through it
-                if let Stmt::Block(ref instrs, lineNum) = progBody {
+                if let Stmt::Block(ref instrs, lineNum, ..) = progBody {
                     for instr in instrs {
                         let good = self.checkStmt(instr.clone());
                         if (!good){
-                            println!("Error in body:");
+                            verboseln!("Error in body:");
                             return false;
                         } else {
                             //continue
                         }
                     }
                 } else {
-                    println!("Problem with AST: header must be a Block");
+                    self.reportError("Problem with AST: header must be a Block".to_string());
                 }
 
 
@@ -146,8 +167,26 @@ impl<'a> SyntaxChecker<'a> {
                 self.checked = true;
                 return true
             }
+            //A module only has a header, its imports are put into the global table before it is checked
+            Stmt::Module(name, header, lineNum, ..) => {
+                if let Stmt::Block(ref instrs, lineNum, ..) = *header.clone() {
+                    for instr in instrs {
+                        let good = self.checkStmt(instr.clone());
+                        if (!good){
+                            verboseln!("Error in module {}:", name);
+                            instr.display(0);
+                            return false;
+                        }
+                    }
+                } else {
+                    self.reportError("Problem with AST: module header must be a Block".to_string());
+                    return false;
+                }
+                self.checked = true;
+                return true;
+            }
             _ => {
-                println!("TypeChecker must be passed a Program AST");
+                self.reportError("TypeChecker must be passed a Program or Module AST".to_string());
                 return false;
             }
         }
@@ -267,24 +306,24 @@ impl<'a> SyntaxChecker<'a> {
                 VarType::Bool => {
                     match new{
                         //Literals
-                        Expr::IntLiteral(val) => {
+                        Expr::IntLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::FloatLiteral(val) => {
+                        Expr::FloatLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::StringLiteral(val) => {
+                        Expr::StringLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::BoolLiteral(val) => {
+                        Expr::BoolLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::IntArrayLiteral(size, val) => {
+                        Expr::IntArrayLiteral(size, val, ..) => {
                             return false;
                         }
                     
                         //References
-                        Expr::VarRef(varName) => {
+                        Expr::VarRef(varName, ..) => {
                             let varTypeLocCheck = self.localTable.getType(&varName.clone());
                             match varTypeLocCheck{
                                 Some(varType) => {
@@ -292,14 +331,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -309,7 +348,7 @@ impl<'a> SyntaxChecker<'a> {
                             
                         }
                         //References
-                        Expr::ProcRef(varName, params) => {
+                        Expr::ProcRef(varName, params, ..) => {
                             
                             
                             
@@ -320,14 +359,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -336,18 +375,18 @@ impl<'a> SyntaxChecker<'a> {
                             
                             
                         }
-                        Expr::ArrayRef(name, index) => {
+                        Expr::ArrayRef(name, index, ..) => {
                             return true;
                         }
                         
                         //Operations
-                        Expr::ArthOp(op1, op, op2) => {
+                        Expr::ArthOp(op1, op, op2, ..) => {
                             return true;
                         }
-                        Expr::LogOp(op1, op, op2) => {
+                        Expr::LogOp(op1, op, op2, ..) => {
                             return true;
                         }
-                        Expr::RelOp(op1, op, op2) => {
+                        Expr::RelOp(op1, op, op2, ..) => {
                             return true;
                         }
 
@@ -356,29 +395,29 @@ impl<'a> SyntaxChecker<'a> {
                 VarType::Float => {
                     match new{
                         //Literals
-                        Expr::IntLiteral(val) => {
+                        Expr::IntLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::FloatLiteral(val) => {
+                        Expr::FloatLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::StringLiteral(val) => {
-                            println!("STRINGLITERAL {}", val.clone());
+                        Expr::StringLiteral(val, ..) => {
+                            verboseln!("STRINGLITERAL {}", val.clone());
                             if val == "floatval"{
                                 return true;
                             } else{
                                 return false;
                             }
                         }
-                        Expr::BoolLiteral(val) => {
+                        Expr::BoolLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::IntArrayLiteral(size, val) => {
+                        Expr::IntArrayLiteral(size, val, ..) => {
                             return false;
                         }
                     
                         //References
-                        Expr::VarRef(varName) => {
+                        Expr::VarRef(varName, ..) => {
                             let varTypeLocCheck = self.localTable.getType(&varName.clone());
                             match varTypeLocCheck{
                                 Some(varType) => {
@@ -386,14 +425,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -403,7 +442,7 @@ impl<'a> SyntaxChecker<'a> {
                             
                         }
                         //References
-                        Expr::ProcRef(varName, params) => {
+                        Expr::ProcRef(varName, params, ..) => {
                             
                             
                             
@@ -414,14 +453,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -430,18 +469,18 @@ impl<'a> SyntaxChecker<'a> {
                             
                             
                         }
-                        Expr::ArrayRef(name, index) => {
+                        Expr::ArrayRef(name, index, ..) => {
                             return true;
                         }
                         
                         //Operations
-                        Expr::ArthOp(op1, op, op2) => {
+                        Expr::ArthOp(op1, op, op2, ..) => {
                             return true;
                         }
-                        Expr::LogOp(op1, op, op2) => {
+                        Expr::LogOp(op1, op, op2, ..) => {
                             return false;
                         }
-                        Expr::RelOp(op1, op, op2) => {
+                        Expr::RelOp(op1, op, op2, ..) => {
                             return false;
                         }
 
@@ -450,24 +489,24 @@ impl<'a> SyntaxChecker<'a> {
                 VarType::Int => {
                     match new{
                         //Literals
-                        Expr::IntLiteral(val) => {
+                        Expr::IntLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::FloatLiteral(val) => {
+                        Expr::FloatLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::StringLiteral(val) => {
+                        Expr::StringLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::BoolLiteral(val) => {
+                        Expr::BoolLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::IntArrayLiteral(size, val) => {
+                        Expr::IntArrayLiteral(size, val, ..) => {
                             return false;
                         }
                     
                         //References
-                        Expr::VarRef(varName) => {
+                        Expr::VarRef(varName, ..) => {
                             let varTypeLocCheck = self.localTable.getType(&varName.clone());
                             match varTypeLocCheck{
                                 Some(varType) => {
@@ -482,7 +521,7 @@ impl<'a> SyntaxChecker<'a> {
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -492,7 +531,7 @@ impl<'a> SyntaxChecker<'a> {
                             
                         }
                         //References
-                        Expr::ProcRef(varName, params) => {
+                        Expr::ProcRef(varName, params, ..) => {
                             
                             
                             
@@ -503,14 +542,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -519,18 +558,18 @@ impl<'a> SyntaxChecker<'a> {
                             
                             
                         }
-                        Expr::ArrayRef(name, index) => {
+                        Expr::ArrayRef(name, index, ..) => {
                             return true;
                         }
                         
                         //Operations
-                        Expr::ArthOp(op1, op, op2) => {
+                        Expr::ArthOp(op1, op, op2, ..) => {
                             return true;
                         }
-                        Expr::LogOp(op1, op, op2) => {
+                        Expr::LogOp(op1, op, op2, ..) => {
                             return true;
                         }
-                        Expr::RelOp(op1, op, op2) => {
+                        Expr::RelOp(op1, op, op2, ..) => {
                             return true;
                         }
 
@@ -539,19 +578,19 @@ impl<'a> SyntaxChecker<'a> {
                 VarType::IntArray(targetSizee) => {
                     match new{
                         //Literals
-                        Expr::IntLiteral(val) => {
+                        Expr::IntLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::FloatLiteral(val) => {
+                        Expr::FloatLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::StringLiteral(val) => {
+                        Expr::StringLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::BoolLiteral(val) => {
+                        Expr::BoolLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::IntArrayLiteral(size, val) => {
+                        Expr::IntArrayLiteral(size, val, ..) => {
                             if (targetSizee == size){
                                 return true;
                             } else {
@@ -560,7 +599,7 @@ impl<'a> SyntaxChecker<'a> {
                         }
                     
                         //References
-                        Expr::VarRef(varName) => {
+                        Expr::VarRef(varName, ..) => {
                             let varTypeLocCheck = self.localTable.getType(&varName.clone());
                             match varTypeLocCheck{
                                 Some(varType) => {
@@ -568,14 +607,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -585,7 +624,7 @@ impl<'a> SyntaxChecker<'a> {
                             
                         }
                         //References
-                        Expr::ProcRef(varName, params) => {
+                        Expr::ProcRef(varName, params, ..) => {
                             
                             
                             
@@ -596,14 +635,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -612,18 +651,18 @@ impl<'a> SyntaxChecker<'a> {
                             
                             
                         }
-                        Expr::ArrayRef(name, index) => {
+                        Expr::ArrayRef(name, index, ..) => {
                             return false;
                         }
                         
                         //Operations
-                        Expr::ArthOp(op1, op, op2) => {
+                        Expr::ArthOp(op1, op, op2, ..) => {
                             return false;
                         }
-                        Expr::LogOp(op1, op, op2) => {
+                        Expr::LogOp(op1, op, op2, ..) => {
                             return false;
                         }
-                        Expr::RelOp(op1, op, op2) => {
+                        Expr::RelOp(op1, op, op2, ..) => {
                             return false;
                         }
 
@@ -632,24 +671,24 @@ impl<'a> SyntaxChecker<'a> {
                 VarType::Str => {
                     match new{
                         //Literals
-                        Expr::IntLiteral(val) => {
+                        Expr::IntLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::FloatLiteral(val) => {
+                        Expr::FloatLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::StringLiteral(val) => {
+                        Expr::StringLiteral(val, ..) => {
                             return true;
                         }
-                        Expr::BoolLiteral(val) => {
+                        Expr::BoolLiteral(val, ..) => {
                             return false;
                         }
-                        Expr::IntArrayLiteral(size, val) => {
+                        Expr::IntArrayLiteral(size, val, ..) => {
                             return false;
                         }
                     
                         //References
-                        Expr::VarRef(varName) => {
+                        Expr::VarRef(varName, ..) => {
                             let varTypeLocCheck = self.localTable.getType(&varName.clone());
                             match varTypeLocCheck{
                                 Some(varType) => {
@@ -657,14 +696,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -674,7 +713,7 @@ impl<'a> SyntaxChecker<'a> {
                             
                         }
                         //References
-                        Expr::ProcRef(varName, params) => {
+                        Expr::ProcRef(varName, params, ..) => {
                             
                             
                             
@@ -685,14 +724,14 @@ impl<'a> SyntaxChecker<'a> {
                                     return compat;
                                 }
                                 None => {
-                                    let varGlobTypeCheck = self.localTable.getType(&varName.clone());
+                                    let varGlobTypeCheck = self.globalTable.getType(&varName.clone());
                                     match varGlobTypeCheck{
                                         Some(varType) => {
                                             let compat = self.checkTypeCompatability(target.clone(), varType.clone());
                                             return compat;
                                         }
                                         None => {
-                                            println!("Variable {} not defined", varName.clone());
+                                            self.reportError(format!("Variable {} not defined", varName.clone()));
                                             return false;
                                         }
                                     }
@@ -701,18 +740,18 @@ impl<'a> SyntaxChecker<'a> {
                             
                             
                         }
-                        Expr::ArrayRef(name, index) => {
+                        Expr::ArrayRef(name, index, ..) => {
                             return false;
                         }
                         
                         //Operations
-                        Expr::ArthOp(op1, op, op2) => {
+                        Expr::ArthOp(op1, op, op2, ..) => {
                             return false;
                         }
-                        Expr::LogOp(op1, op, op2) => {
+                        Expr::LogOp(op1, op, op2, ..) => {
                             return false;
                         }
-                        Expr::RelOp(op1, op, op2) => {
+                        Expr::RelOp(op1, op, op2, ..) => {
                             return false;
                         }
 
@@ -752,33 +791,43 @@ impl<'a> SyntaxChecker<'a> {
         }
     }
 
-    pub fn checkExpr(&mut self, mut checkExpr: Expr) -> bool{
+    //Checks an expression, the errors found in it point at it
+    pub fn checkExpr(&mut self, checkExpr: Expr) -> bool {
+        let outerSpan = self.curSpan;
+        self.curSpan = checkExpr.span();
+        let good = self.checkExprParts(checkExpr);
+        self.curSpan = outerSpan;
+        return good;
+    }
+
+    //Checks the kind of expression it is
+    fn checkExprParts(&mut self, mut checkExpr: Expr) -> bool{
         match checkExpr.clone(){
             //Literals
-            Expr::IntLiteral(val) => {
+            Expr::IntLiteral(val, ..) => {
                 return true;
             }
-            Expr::FloatLiteral(val) => {
+            Expr::FloatLiteral(val, ..) => {
                 return true;
             }
-            Expr::StringLiteral(val) => {
+            Expr::StringLiteral(val, ..) => {
                 return true;
             }
-            Expr::BoolLiteral(val) => {
+            Expr::BoolLiteral(val, ..) => {
                 return true;
             }
-            Expr::IntArrayLiteral(size, array) => {
+            Expr::IntArrayLiteral(size, array, ..) => {
                 return true;
             }
             
             //References
-            Expr::VarRef(varName) => {
+            Expr::VarRef(varName, ..) => {
                 //Gets the type if defined in local scope
                 let checkLocVar = self.localTable.get(&varName.clone());
                 match checkLocVar{
                     Some(var) => {
                         if var.hashType != HashItemType::Variable {
-                            println!("{} is not a variable", varName.clone());
+                            self.reportError(format!("{} is not a variable", varName.clone()));
                             return false;
                         } else {
                             return true;
@@ -789,14 +838,14 @@ impl<'a> SyntaxChecker<'a> {
                             match checkGlobVar{
                                 Some(var) => {
                                     if var.hashType != HashItemType::Variable {
-                                        println!("{} is not a variable", varName.clone());
+                                        self.reportError(format!("{} is not a variable", varName.clone()));
                                         return false;
                                     } else {
                                         return true;
                                     }
                                 }
                                 None => {
-                                    println!("Variable {} is not defined", varName.clone());
+                                    self.reportError(format!("Variable {} is not defined", varName.clone()));
                                     return false;
                                 }
                             }
@@ -804,7 +853,7 @@ impl<'a> SyntaxChecker<'a> {
                 }
                 
             }
-            Expr::ProcRef(procName, params) => {
+            Expr::ProcRef(procName, params, ..) => {
                 if (self.checked.clone() == false) & (self.name.clone() == procName.clone()){
                     return true;
                 } else {
@@ -828,12 +877,12 @@ impl<'a> SyntaxChecker<'a> {
                                                         if compatable {
                                                             //Continue to checking next param
                                                         } else {
-                                                            println!("Error with call to procedure {}: param {} is type {}, which is incompatible with given type {}", procName.clone(), procParamList[i].clone(), targetType.clone(), param.clone());
+                                                            self.reportError(format!("Error with call to procedure {}: param {} is type {}, which is incompatible with given type {}", procName.clone(), procParamList[i].clone(), targetType.clone(), param.clone()));
                                                             return false;
                                                         }
                                                     }
                                                     None => {
-                                                        println!("Some sort of error with the procedure symbol table. Could not located defined parameter in table");
+                                                        self.reportError("Some sort of error with the procedure symbol table. Could not located defined parameter in table".to_string());
                                                         return false;
                                                     }
                                                 }
@@ -842,21 +891,21 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
 
                                         } else {
-                                            println!("Error with call to procedure {}: {} params required, {} provided", procName.clone(), paramsVec.len().to_string(), procParamList.len().clone().to_string())
+                                            self.reportError(format!("Error with call to procedure {}: {} params required, {} provided", procName.clone(), paramsVec.len().to_string(), procParamList.len().clone().to_string()))
                                         }
                                     }
                                     None => {
                                         if (procParamList.len() == 0){
                                             return true;
                                         } else {
-                                            println!("Procedure call to {} missing parameters", procName.clone());
+                                            self.reportError(format!("Procedure call to {} missing parameters", procName.clone()));
                                             return false;
                                         }
                                     }
                                 }
                                 return true;
                             } else {
-                                println!("{} is not defined as a procedure", procName.clone());
+                                self.reportError(format!("{} is not defined as a procedure", procName.clone()));
                                 return false;
                             }
                         }
@@ -882,12 +931,12 @@ impl<'a> SyntaxChecker<'a> {
                                                                 if compatable {
                                                                     //Continue to checking next param
                                                                 } else {
-                                                                    println!("Error with call to procedure {}: param {} is type {}, which is incompatible with given type {}", procName.clone(), procParamList[i].clone(), targetType.clone(), param.clone());
+                                                                    self.reportError(format!("Error with call to procedure {}: param {} is type {}, which is incompatible with given type {}", procName.clone(), procParamList[i].clone(), targetType.clone(), param.clone()));
                                                                     return false;
                                                                 }
                                                             }
                                                             None => {
-                                                                println!("Some sort of error with the procedure symbol table. Could not located defined parameter in table");
+                                                                self.reportError("Some sort of error with the procedure symbol table. Could not located defined parameter in table".to_string());
                                                                 return false;
                                                             }
                                                         }
@@ -896,7 +945,7 @@ impl<'a> SyntaxChecker<'a> {
                                                     return true;
 
                                                 } else {
-                                                    println!("Error with call to procedure {}: {} params required, {} provided", procName.clone(), paramsVec.len().to_string(), procParamList.len().clone().to_string());
+                                                    self.reportError(format!("Error with call to procedure {}: {} params required, {} provided", procName.clone(), paramsVec.len().to_string(), procParamList.len().clone().to_string()));
                                                     return false;
                                                 }
                                             }
@@ -904,18 +953,18 @@ impl<'a> SyntaxChecker<'a> {
                                                 if (procParamList.len() == 0){
                                                     return true;
                                                 } else {
-                                                    println!("Procedure call to {} missing parameters", procName.clone());
+                                                    self.reportError(format!("Procedure call to {} missing parameters", procName.clone()));
                                                     return false;
                                                 }
                                             }
                                         }
                                     } else {
-                                        println!("{} is not defined as a procedure", procName.clone());
+                                        self.reportError(format!("{} is not defined as a procedure", procName.clone()));
                                         return false;
                                     }
                                 }
                                 None => {
-                                    println!("Procedure {} is not defined", procName.clone());
+                                    self.reportError(format!("Procedure {} is not defined", procName.clone()));
                                     return false;
                                 }
                             }
@@ -924,13 +973,13 @@ impl<'a> SyntaxChecker<'a> {
                 }
                 
             }
-            Expr::ArrayRef(varName, indexExpr) => {
+            Expr::ArrayRef(varName, indexExpr, ..) => {
                 let existVar: VarType;
                 let checkLocVar = self.localTable.get(&varName.clone());
                 match checkLocVar{
                     Some(var) => {
                         if var.hashType != HashItemType::Variable {
-                            println!("{} is not a variable", varName.clone());
+                            self.reportError(format!("{} is not a variable", varName.clone()));
                             return false;
                         } else {
                             existVar = var.clone().getType().clone();
@@ -941,14 +990,14 @@ impl<'a> SyntaxChecker<'a> {
                             match checkGlobVar{
                                 Some(var) => {
                                     if var.hashType != HashItemType::Variable {
-                                        println!("{} is not a variable", varName.clone());
+                                        self.reportError(format!("{} is not a variable", varName.clone()));
                                         return false;
                                     } else {
                                         existVar = var.clone().getType().clone();
                                     }
                                 }
                                 None => {
-                                    println!("Variable {} is not defined", varName.clone());
+                                    self.reportError(format!("Variable {} is not defined", varName.clone()));
                                     return false;
                                 }
                             }
@@ -962,53 +1011,53 @@ impl<'a> SyntaxChecker<'a> {
                             return true;
                         }
                         else {
-                            println!("Error with array index");
+                            self.reportError("Error with array index".to_string());
                             return false;
                         }
                     }
                     _ => {
-                        println!("Variable {} is not an array", varName.clone());
+                        self.reportError(format!("Variable {} is not an array", varName.clone()));
                         return false;
                     }
                 }                
             }
             
             //Operations
-            Expr::ArthOp(op1, op, op2) => {
+            Expr::ArthOp(op1, op, op2, ..) => {
                 //First checks operand 1 to ensure it is valid
                 let checkedOp1 = self.checkExpr(*op1.clone());
                 if !checkedOp1 {
-                    println!("Error in operand one of arithmetic operation");
+                    self.reportError("Error in operand one of arithmetic operation".to_string());
                     return false;
                 }
                 //Checks operand 2
                 let checkedOp2 = self.checkExpr(*op2.clone());
                 if !checkedOp2{
-                    println!("Error in operand two of arithmetic operation");
+                    self.reportError("Error in operand two of arithmetic operation".to_string());
                     return false;
                 }
 
                 //Since both are good, need to ensure both are compatabile with ArthOps
                 match *op1 {
-                    Expr::IntLiteral(val) => {
+                    Expr::IntLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::FloatLiteral(val) => {
+                    Expr::FloatLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Cannot use string in arithmetic operation");
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError("Cannot use string in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::BoolLiteral(val) => {
-                        println!("Cannot use boolean as operand in arithmetic operation");
+                    Expr::BoolLiteral(val, ..) => {
+                        self.reportError("Cannot use boolean as operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::IntArrayLiteral(size, val) => {
-                        println!("Cannot use entire array in arithmetic operation");
+                    Expr::IntArrayLiteral(size, val, ..) => {
+                        self.reportError("Cannot use entire array in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::VarRef(varName) => {
+                    Expr::VarRef(varName, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(varName.clone());
                         match op1TypeCheck{
@@ -1016,7 +1065,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", varName.clone());
+                                self.reportError(format!("Referenced to undefined {}", varName.clone()));
                                 return false;
                             }
                         }
@@ -1033,13 +1082,13 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use variable {} of type {} in arithmetic operation", varName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use variable {} of type {} in arithmetic operation", varName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     
                     }
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         if (self.checked.clone() == false) & (self.name.clone() == procName.clone()){
                             return true;
                         } else {    let mut op1Type: VarType;
@@ -1049,7 +1098,7 @@ impl<'a> SyntaxChecker<'a> {
                                     op1Type = foundType;
                                 }
                                 None => {
-                                    println!("Referenced to undefined {}", procName.clone());
+                                    self.reportError(format!("Referenced to undefined {}", procName.clone()));
                                     return false;
                                 }
                             }
@@ -1063,49 +1112,49 @@ impl<'a> SyntaxChecker<'a> {
                                     //continue
                                 }
                                 _ => {
-                                    println!("Cannot use procedure {} of type {} in arithmetic operation", procName.clone(), op1Type.clone());
+                                    self.reportError(format!("Cannot use procedure {} of type {} in arithmetic operation", procName.clone(), op1Type.clone()));
                                     return false;
                                 }
                             }
                         }
                     }
-                    Expr::ArrayRef(varName, indexExpr) => {
+                    Expr::ArrayRef(varName, indexExpr, ..) => {
                         //continue
                     }
-                    Expr::ArthOp(operand1, op, operand2) => {
+                    Expr::ArthOp(operand1, op, operand2, ..) => {
                         //continue
                     }
-                    Expr::LogOp(operand1, oeprator, operand2) => {
-                        println!("Cannot use a logical operation as an operand in arithmetic operation");
+                    Expr::LogOp(operand1, oeprator, operand2, ..) => {
+                        self.reportError("Cannot use a logical operation as an operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::RelOp(operand1, operator, operand2) => {
-                        println!("Cannot use a relational operation as an operand in arithmetic operation");
+                    Expr::RelOp(operand1, operator, operand2, ..) => {
+                        self.reportError("Cannot use a relational operation as an operand in arithmetic operation".to_string());
                         return false;
                     }
                 }
 
                 //Checks the compatability of operand 2
                 match *op2 {
-                    Expr::IntLiteral(val) => {
+                    Expr::IntLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::FloatLiteral(val) => {
+                    Expr::FloatLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Cannot use string in arithmetic operation");
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError("Cannot use string in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::BoolLiteral(val) => {
-                        println!("Cannot use boolean as operand in arithmetic operation");
+                    Expr::BoolLiteral(val, ..) => {
+                        self.reportError("Cannot use boolean as operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::IntArrayLiteral(size, val) => {
-                        println!("Cannot use entire array in arithmetic operation");
+                    Expr::IntArrayLiteral(size, val, ..) => {
+                        self.reportError("Cannot use entire array in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::VarRef(varName) => {
+                    Expr::VarRef(varName, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(varName.clone());
                         match op1TypeCheck{
@@ -1113,7 +1162,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", varName.clone());
+                                self.reportError(format!("Referenced to undefined {}", varName.clone()));
                                 return false;
                             }
                         }
@@ -1130,13 +1179,13 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use variable {} of type {} in arithmetic operation", varName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use variable {} of type {} in arithmetic operation", varName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     
                     }
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         if (self.checked.clone() == false) & (self.name.clone() == procName.clone()){
                             return true;
                         } else {
@@ -1147,7 +1196,7 @@ impl<'a> SyntaxChecker<'a> {
                                     op1Type = foundType;
                                 }
                                 None => {
-                                    println!("Referenced to undefined {}", procName.clone());
+                                    self.reportError(format!("Referenced to undefined {}", procName.clone()));
                                     return false;
                                 }
                             }
@@ -1161,24 +1210,24 @@ impl<'a> SyntaxChecker<'a> {
                                     //continue
                                 }
                                 _ => {
-                                    println!("Cannot use procedure {} of type {} in arithmetic operation", procName.clone(), op1Type.clone());
+                                    self.reportError(format!("Cannot use procedure {} of type {} in arithmetic operation", procName.clone(), op1Type.clone()));
                                     return false;
                                 }
                             }
                         }
                     }
-                    Expr::ArrayRef(varName, indexExpr) => {
+                    Expr::ArrayRef(varName, indexExpr, ..) => {
                         //continue
                     }
-                    Expr::ArthOp(operand1, op, operand2) => {
+                    Expr::ArthOp(operand1, op, operand2, ..) => {
                         //continue
                     }
-                    Expr::LogOp(operand1, oeprator, operand2) => {
-                        println!("Cannot use a logical operation as an operand in arithmetic operation");
+                    Expr::LogOp(operand1, oeprator, operand2, ..) => {
+                        self.reportError("Cannot use a logical operation as an operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::RelOp(operand1, operator, operand2) => {
-                        println!("Cannot use a relational operation as an operand in arithmetic operation");
+                    Expr::RelOp(operand1, operator, operand2, ..) => {
+                        self.reportError("Cannot use a relational operation as an operand in arithmetic operation".to_string());
                         return false;
                     }
                 }
@@ -1188,42 +1237,42 @@ impl<'a> SyntaxChecker<'a> {
                 return true;
             }
             
-            Expr::LogOp(op1, op, op2) => {
+            Expr::LogOp(op1, op, op2, ..) => {
                 //First checks operand 1 to ensure it is valid
                 let checkedOp1 = self.checkExpr(*op1.clone());
                 if !checkedOp1 {
-                    println!("Error in operand one of arithmetic operation");
+                    self.reportError("Error in operand one of arithmetic operation".to_string());
                     return false;
                 }
                 //Checks operand 2
                 let checkedOp2 = self.checkExpr(*op2.clone());
                 if !checkedOp2{
-                    println!("Error in operand two of arithmetic operation");
+                    self.reportError("Error in operand two of arithmetic operation".to_string());
                     return false;
                 }
 
                 //Since both are good, need to ensure both are compatabile with ArthOps
                 match *op1 {
-                    Expr::IntLiteral(val) => {
+                    Expr::IntLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::FloatLiteral(val) => {
-                        println!("Cannot use float as operand in logical operation");
+                    Expr::FloatLiteral(val, ..) => {
+                        self.reportError("Cannot use float as operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Cannot use string as operand in arithmetic operation");
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError("Cannot use string as operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::BoolLiteral(val) => {
-                        println!("Cannot use string as operand in arithmetic operation");
+                    Expr::BoolLiteral(val, ..) => {
+                        self.reportError("Cannot use string as operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::IntArrayLiteral(size, val) => {
-                        println!("Cannot use entire array as operand in logical operation");
+                    Expr::IntArrayLiteral(size, val, ..) => {
+                        self.reportError("Cannot use entire array as operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::VarRef(varName) => {
+                    Expr::VarRef(varName, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(varName.clone());
                         match op1TypeCheck{
@@ -1231,7 +1280,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", varName.clone());
+                                self.reportError(format!("Referenced to undefined {}", varName.clone()));
                                 return false;
                             }
                         }
@@ -1245,13 +1294,13 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use variable {} of type {} in logical operation", varName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use variable {} of type {} in logical operation", varName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     
                     }
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(procName.clone());
                         match op1TypeCheck{
@@ -1259,7 +1308,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", procName.clone());
+                                self.reportError(format!("Referenced to undefined {}", procName.clone()));
                                 return false;
                             }
                         }
@@ -1270,23 +1319,23 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use procedure {} of type {} in logical operation", procName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use procedure {} of type {} in logical operation", procName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     }
-                    Expr::ArrayRef(varName, indexExpr) => {
+                    Expr::ArrayRef(varName, indexExpr, ..) => {
                         //continue
                     }
-                    Expr::ArthOp(operand1, op, operand2) => {
+                    Expr::ArthOp(operand1, op, operand2, ..) => {
                         //continue
                     }
-                    Expr::LogOp(operand1, oeprator, operand2) => {
-                        println!("Cannot use a logical operation as an operand in logical operation");
+                    Expr::LogOp(operand1, oeprator, operand2, ..) => {
+                        self.reportError("Cannot use a logical operation as an operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::RelOp(operand1, operator, operand2) => {
-                        println!("Cannot use a relational operation as an operand in logical operation");
+                    Expr::RelOp(operand1, operator, operand2, ..) => {
+                        self.reportError("Cannot use a relational operation as an operand in logical operation".to_string());
                         return false;
                     }
                }
@@ -1294,26 +1343,26 @@ impl<'a> SyntaxChecker<'a> {
 
                 //Checks the compatability of operand 2
                 match *op2 {
-                    Expr::IntLiteral(val) => {
+                    Expr::IntLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::FloatLiteral(val) => {
-                        println!("Cannot use float as operand in logical operation");
+                    Expr::FloatLiteral(val, ..) => {
+                        self.reportError("Cannot use float as operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Cannot use string as operand in arithmetic operation");
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError("Cannot use string as operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::BoolLiteral(val) => {
-                        println!("Cannot use string as operand in arithmetic operation");
+                    Expr::BoolLiteral(val, ..) => {
+                        self.reportError("Cannot use string as operand in arithmetic operation".to_string());
                         return false;
                     }
-                    Expr::IntArrayLiteral(size, val) => {
-                        println!("Cannot use entire array as operand in logical operation");
+                    Expr::IntArrayLiteral(size, val, ..) => {
+                        self.reportError("Cannot use entire array as operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::VarRef(varName) => {
+                    Expr::VarRef(varName, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(varName.clone());
                         match op1TypeCheck{
@@ -1321,7 +1370,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", varName.clone());
+                                self.reportError(format!("Referenced to undefined {}", varName.clone()));
                                 return false;
                             }
                         }
@@ -1335,13 +1384,13 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use variable {} of type {} in logical operation", varName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use variable {} of type {} in logical operation", varName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     
                     }
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(procName.clone());
                         match op1TypeCheck{
@@ -1349,7 +1398,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", procName.clone());
+                                self.reportError(format!("Referenced to undefined {}", procName.clone()));
                                 return false;
                             }
                         }
@@ -1360,23 +1409,23 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use procedure {} of type {} in logical operation", procName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use procedure {} of type {} in logical operation", procName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     }
-                    Expr::ArrayRef(varName, indexExpr) => {
+                    Expr::ArrayRef(varName, indexExpr, ..) => {
                         //continue
                     }
-                    Expr::ArthOp(operand1, op, operand2) => {
+                    Expr::ArthOp(operand1, op, operand2, ..) => {
                         //continue
                     }
-                    Expr::LogOp(operand1, oeprator, operand2) => {
-                        println!("Cannot use a logical operation as an operand in logical operation");
+                    Expr::LogOp(operand1, oeprator, operand2, ..) => {
+                        self.reportError("Cannot use a logical operation as an operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::RelOp(operand1, operator, operand2) => {
-                        println!("Cannot use a relational operation as an operand in logical operation");
+                    Expr::RelOp(operand1, operator, operand2, ..) => {
+                        self.reportError("Cannot use a relational operation as an operand in logical operation".to_string());
                         return false;
                     }
                 }
@@ -1384,40 +1433,40 @@ impl<'a> SyntaxChecker<'a> {
                 //Now that we are here and everything has been checked, we are good
                 return true;
             }
-            Expr::RelOp(op1, op, op2) => {
+            Expr::RelOp(op1, op, op2, ..) => {
                 //First checks operand 1 to ensure it is valid
                 let checkedOp1 = self.checkExpr(*op1.clone());
                 if !checkedOp1 {
-                    println!("Error in operand one of relational operation");
+                    self.reportError("Error in operand one of relational operation".to_string());
                     return false;
                 }
                 //Checks operand 2
                 let checkedOp2 = self.checkExpr(*op2.clone());
                 if !checkedOp2{
-                    println!("Error in operand two of relational operation");
+                    self.reportError("Error in operand two of relational operation".to_string());
                     return false;
                 }
 
                 //Since both are good, need to ensure both are compatabile with ArthOps
                 match *op1 {
-                    Expr::IntLiteral(val) => {
+                    Expr::IntLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::FloatLiteral(val) => {
+                    Expr::FloatLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Cannot use string as operand in relational operation");
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError("Cannot use string as operand in relational operation".to_string());
                         return false;
                     }
-                    Expr::BoolLiteral(val) => {
+                    Expr::BoolLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::IntArrayLiteral(size, val) => {
-                        println!("Cannot use entire array as operand in logical operation");
+                    Expr::IntArrayLiteral(size, val, ..) => {
+                        self.reportError("Cannot use entire array as operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::VarRef(varName) => {
+                    Expr::VarRef(varName, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(varName.clone());
                         match op1TypeCheck{
@@ -1425,7 +1474,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", varName.clone());
+                                self.reportError(format!("Referenced to undefined {}", varName.clone()));
                                 return false;
                             }
                         }
@@ -1442,13 +1491,13 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use variable {} of type {} in relational operation", varName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use variable {} of type {} in relational operation", varName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     
                     }
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(procName.clone());
                         match op1TypeCheck{
@@ -1456,7 +1505,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", procName.clone());
+                                self.reportError(format!("Referenced to undefined {}", procName.clone()));
                                 return false;
                             }
                         }
@@ -1473,21 +1522,21 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use procedure {} of type {} in relational operation", procName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use procedure {} of type {} in relational operation", procName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     }
-                    Expr::ArrayRef(varName, indexExpr) => {
+                    Expr::ArrayRef(varName, indexExpr, ..) => {
                         //continue
                     }
-                    Expr::ArthOp(operand1, op, operand2) => {
+                    Expr::ArthOp(operand1, op, operand2, ..) => {
                         //continue
                     }
-                    Expr::LogOp(operand1, oeprator, operand2) => {
+                    Expr::LogOp(operand1, oeprator, operand2, ..) => {
                         //continue
                     }
-                    Expr::RelOp(operand1, operator, operand2) => {
+                    Expr::RelOp(operand1, operator, operand2, ..) => {
                         //continue
                     }
                }
@@ -1495,24 +1544,24 @@ impl<'a> SyntaxChecker<'a> {
 
                //Checks the compatability of operand 2
                 match *op2 {
-                    Expr::IntLiteral(val) => {
+                    Expr::IntLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::FloatLiteral(val) => {
+                    Expr::FloatLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Cannot use string as operand in relational operation");
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError("Cannot use string as operand in relational operation".to_string());
                         return false;
                     }
-                    Expr::BoolLiteral(val) => {
+                    Expr::BoolLiteral(val, ..) => {
                         //continue
                     }
-                    Expr::IntArrayLiteral(size, val) => {
-                        println!("Cannot use entire array as operand in logical operation");
+                    Expr::IntArrayLiteral(size, val, ..) => {
+                        self.reportError("Cannot use entire array as operand in logical operation".to_string());
                         return false;
                     }
-                    Expr::VarRef(varName) => {
+                    Expr::VarRef(varName, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(varName.clone());
                         match op1TypeCheck{
@@ -1520,7 +1569,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", varName.clone());
+                                self.reportError(format!("Referenced to undefined {}", varName.clone()));
                                 return false;
                             }
                         }
@@ -1537,13 +1586,13 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use variable {} of type {} in relational operation", varName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use variable {} of type {} in relational operation", varName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     
                     }
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         let mut op1Type: VarType;
                         let op1TypeCheck = self.checkVar(procName.clone());
                         match op1TypeCheck{
@@ -1551,7 +1600,7 @@ impl<'a> SyntaxChecker<'a> {
                                 op1Type = foundType;
                             }
                             None => {
-                                println!("Referenced to undefined {}", procName.clone());
+                                self.reportError(format!("Referenced to undefined {}", procName.clone()));
                                 return false;
                             }
                         }
@@ -1568,21 +1617,21 @@ impl<'a> SyntaxChecker<'a> {
                                 //continue
                             }
                             _ => {
-                                println!("Cannot use procedure {} of type {} in relational operation", procName.clone(), op1Type.clone());
+                                self.reportError(format!("Cannot use procedure {} of type {} in relational operation", procName.clone(), op1Type.clone()));
                                 return false;
                             }
                         }
                     }
-                    Expr::ArrayRef(varName, indexExpr) => {
+                    Expr::ArrayRef(varName, indexExpr, ..) => {
                         //continue
                     }
-                    Expr::ArthOp(operand1, op, operand2) => {
+                    Expr::ArthOp(operand1, op, operand2, ..) => {
                         //continue
                     }
-                    Expr::LogOp(operand1, oeprator, operand2) => {
+                    Expr::LogOp(operand1, oeprator, operand2, ..) => {
                         //continue
                     }
-                    Expr::RelOp(operand1, operator, operand2) => {
+                    Expr::RelOp(operand1, operator, operand2, ..) => {
                         //continue
                     }
                 }
@@ -1594,27 +1643,43 @@ impl<'a> SyntaxChecker<'a> {
     }
     
     //Checks each statement one at a time, returns a bool if there's an error
-    pub fn checkStmt(&mut self, mut checkStmt: Stmt) -> bool{
+    //The errors found in it have its line and point at it, the statement around it is current again after
+    pub fn checkStmt(&mut self, checkStmt: Stmt) -> bool {
+        let (outerLine, outerSpan) = (self.curLine.clone(), self.curSpan);
+        //Blocks don't have a real line, the statements in them do
+        if let Stmt::Block(_, _, ..) = checkStmt {
+        } else {
+            self.curLine = checkStmt.lineNum();
+            self.curSpan = checkStmt.span();
+        }
+        let good = self.checkStmtParts(checkStmt);
+        self.curLine = outerLine;
+        self.curSpan = outerSpan;
+        return good;
+    }
+
+    //Checks the kind of statement it is
+    fn checkStmtParts(&mut self, mut checkStmt: Stmt) -> bool{
         match (checkStmt){
             //For checking and declaring local variables
-            Stmt::VarDecl(varName, varType, lineNum) => {
+            Stmt::VarDecl(varName, varType, lineNum, _, nameSpan) => {
                 if self.scope != 0 {
                     let defined = self.localTable.checkItem(&varName.clone());
                     if(defined){
-                        println!("Error: variable: {} defined twice", varName.clone());
+                        self.reportError(format!("Error: variable: {} defined twice", varName.clone()));
                         return false;
                     } else {
-                        let item = HashItem::newVar(varName.clone(), varType.clone());
+                        let item = HashItem::newVar(varName.clone(), varType.clone()).declaredAt(nameSpan);
                         self.localTable.symTab.insert(varName.clone(), item.clone());
                         return true;
                     }
                 } else {
                     let defined = self.globalTable.checkItem(&varName.clone());
                     if(defined){
-                        println!("Error: variable: {} defined twice", varName.clone());
+                        self.reportError(format!("Error: variable: {} defined twice", varName.clone()));
                         return false;
                     } else {
-                        let item = HashItem::newVar(varName.clone(), varType.clone());
+                        let item = HashItem::newVar(varName.clone(), varType.clone()).declaredAt(nameSpan);
                         self.globalTable.symTab.insert(varName.clone(), item.clone());
                         return true;
                     }
@@ -1623,23 +1688,23 @@ impl<'a> SyntaxChecker<'a> {
                 
             }
             //For checking and declaring global variables
-            Stmt::GlobVarDecl(varName, varType, lineNum) => {
+            Stmt::GlobVarDecl(varName, varType, lineNum, _, nameSpan) => {
 
                 let defined = self.globalTable.checkItem(&varName.clone());
                 if(defined){
-                    println!("Error: variable: {} defined twice", varName.clone());
+                    self.reportError(format!("Error: variable: {} defined twice", varName.clone()));
                     return false;
                 } else {
-                    let item = HashItem::newVar(varName.clone(), varType.clone());
+                    let item = HashItem::newVar(varName.clone(), varType.clone()).declaredAt(nameSpan);
                     self.globalTable.symTab.insert(varName.clone(), item.clone());
                     return true;
                 }
                     
             }
             //For checking a procedure
-            Stmt::ProcDecl(retType, procName, params, header, body, lineNum) => {
+            Stmt::ProcDecl(retType, procName, params, header, body, lineNum, procSpan, nameSpan) => {
                 // println!("procedure declaration");
-                let procAst = Stmt::Program(procName.clone(), header.clone(), body.clone(), lineNum.clone());
+                let procAst = Stmt::Program(procName.clone(), header.clone(), body.clone(), lineNum.clone(), procSpan.clone());
                 
                 let mut paramStrings: Vec<String> = Vec::new();
 
@@ -1647,27 +1712,29 @@ impl<'a> SyntaxChecker<'a> {
 
                 let mut procChecker: SyntaxChecker = self.newScope(procAst, curScope, procName.clone());
                 //Iterates through the parameters, registering them in the Symboltable and copying the names to the list of params
-                if let Stmt::Block(ref instrs, lineNum) = *params.clone() {
+                if let Stmt::Block(ref instrs, lineNum, ..) = *params.clone() {
                     for instr in instrs {
                         let good = procChecker.checkStmt(instr.clone());
                         if (!good){
-                            println!("Error in Procedure parameter definition on line {}:", lineNum.clone());
+                            let procReports = procChecker.reports.clone();
+                            self.reports.merge(&procReports);
+                            verboseln!("Error in Procedure parameter definition on line {}:", lineNum.clone());
                             // instr.display(0);
                             return false;
                         } else {
                             match instr.clone(){
-                                Stmt::VarDecl(varName, VarType, lineNum) => {
+                                Stmt::VarDecl(varName, VarType, lineNum, ..) => {
                                     paramStrings.push(varName.clone());
                                 }
                                 _ => {
-                                    println!("Error with procedure {} declaration on line {}:\n Procedure parameters must be variable declarations in the following format:\n    variable <identifier> : <type_mark>", procName.clone(), lineNum.clone());
+                                    self.reportError(format!("Error with procedure {} declaration on line {}:\n Procedure parameters must be variable declarations in the following format:\n    variable <identifier> : <type_mark>", procName.clone(), lineNum.clone()));
                                     return false;
                                 }
                             }
                         }
                     }
                 } else {
-                    println!("Error in Procedure parameter definition on line {}:", lineNum.clone());
+                    self.reportError(format!("Error in Procedure parameter definition on line {}:", lineNum.clone()));
                     // instr.display(0);
                     return false;
                 }
@@ -1679,35 +1746,38 @@ impl<'a> SyntaxChecker<'a> {
 
                 //If the procedure is good, appends to the symboltable and moved on
                 if(!procGood){
-                    println!("Error in procedure {} defined on line {}", procName.clone(), lineNum.clone());
+                    //The errors are reported by the procedure's checker, they are moved up to this one
+                    let procReports = procChecker.reports.clone();
+                    self.reports.merge(&procReports);
+                    verboseln!("Error in procedure {} defined on line {}", procName.clone(), lineNum.clone());
                     return false;
                 } else {
                     if curScope != 0 {
                         //Sets up the things and inserts the procedure into the symboltable
                         let mut procItemType = HashItemType::newProcItem(body.clone(), paramStrings.clone(), procChecker.localTable.clone());
-                        let mut procItem: HashItem = HashItem::newProc(procName.clone(), retType.clone(), procItemType);
+                        let mut procItem: HashItem = HashItem::newProc(procName.clone(), retType.clone(), procItemType).declaredAt(nameSpan);
                         self.localTable.symTab.insert(procName.clone(), procItem.clone());
                         
                         return true;
                     } else {
                         //Sets up the things and inserts the procedure into the symboltable
                         let mut procItemType = HashItemType::newProcItem(body.clone(), paramStrings.clone(), procChecker.localTable.clone());
-                        let mut procItem: HashItem = HashItem::newProc(procName.clone(), retType.clone(), procItemType);
+                        let mut procItem: HashItem = HashItem::newProc(procName.clone(), retType.clone(), procItemType).declaredAt(nameSpan);
                         self.globalTable.symTab.insert(procName.clone(), procItem.clone());
                         return true;
                     }
                 }
             }
             //For checking a variable assignment
-            Stmt::Assign(valueToAssign, newValue, lineNum) => {
+            Stmt::Assign(valueToAssign, newValue, lineNum, ..) => {
                 // Check if assigning to variable or not
-                if let Expr::VarRef(ref targName) = valueToAssign {
+                if let Expr::VarRef(ref targName, ..) = valueToAssign {
                     //Check if variable assignment is in the local table
                     let mut targValue: HashItem; 
                     //Looks for the value in the local then global table, retrieves it if so
                     if !(self.localTable.checkItem(targName)){
                         if !(self.globalTable.checkItem(targName)){
-                            println!("Attempting to assign value to undeclared variable: {} on line: {}", targName.clone(), lineNum.clone());
+                            self.reportError(format!("Attempting to assign value to undeclared variable: {} on line: {}", targName.clone(), lineNum.clone()));
                             return false;
                         } else {
                             let gotValue = self.globalTable.get(targName);
@@ -1716,7 +1786,7 @@ impl<'a> SyntaxChecker<'a> {
                                     targValue = val.clone();
                                 }
                                 None => {
-                                    println!("Error with value {} on line: {}", targName.clone(), lineNum.clone());
+                                    self.reportError(format!("Error with value {} on line: {}", targName.clone(), lineNum.clone()));
                                     return false;
                                 }
                             }
@@ -1728,7 +1798,7 @@ impl<'a> SyntaxChecker<'a> {
                                 targValue = val.clone();
                             }
                             None => {
-                                println!("Error with value {} on line: {}", targName.clone(), lineNum.clone());
+                                self.reportError(format!("Error with value {} on line: {}", targName.clone(), lineNum.clone()));
                                 return false;
                             }
                         }
@@ -1736,7 +1806,7 @@ impl<'a> SyntaxChecker<'a> {
                     
                     //Checks if value being assigned to is a variable
                     if targValue.hashType != HashItemType::Variable {
-                        println!("On line: {}, cannot assign value to procedure", lineNum.clone());
+                        self.reportError(format!("On line: {}, cannot assign value to procedure", lineNum.clone()));
                         return false;
                     }
                     
@@ -1746,64 +1816,64 @@ impl<'a> SyntaxChecker<'a> {
                         VarType::Int => {
                             match newValue.clone(){
                                 //Literals
-                                Expr::IntLiteral(val) => {
+                                Expr::IntLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::FloatLiteral(val) => {
+                                Expr::FloatLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::ArrayRef(name, index) => {
+                                Expr::ArrayRef(name, index, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if checked {
                                         return true;
                                     } else {
-                                        println!("Error with array reference on line {}", lineNum.clone());
+                                        self.reportError(format!("Error with array reference on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::BoolLiteral(val) => {
+                                Expr::BoolLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::StringLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign string to variable of type int", lineNum.clone());
+                                Expr::StringLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign string to variable of type int", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::IntArrayLiteral(size, array) => {
-                                    println!("Cannot assign array to variable of type {}", targType.clone());
+                                Expr::IntArrayLiteral(size, array, ..) => {
+                                    self.reportError(format!("Cannot assign array to variable of type {}", targType.clone()));
                                     return false;
                                 }
 
                                 //Operations
-                                Expr::ArthOp(op1, op, op2) => {
+                                Expr::ArthOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in arithmetic operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in arithmetic operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::LogOp(op1, op, op2) => {
+                                Expr::LogOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in logical operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in logical operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::RelOp(op1, op, op2) => {
+                                Expr::RelOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in relational operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in relational operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
                                 
                                 //Calls/references
-                                Expr::ProcRef(procName, params) => {
+                                Expr::ProcRef(procName, params, ..) => {
                                     if (self.checked.clone() == false) & (self.name.clone() == procName.clone()){
                                         return true;
                                     } else {    
@@ -1821,7 +1891,7 @@ impl<'a> SyntaxChecker<'a> {
                                                         procType = proc
                                                     }
                                                     None => {
-                                                        println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                                        self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                                         return false;
                                                         
                                                     }
@@ -1841,12 +1911,12 @@ impl<'a> SyntaxChecker<'a> {
                                                 return true;
                                             }
                                             _ => {
-                                                println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone());
+                                                self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone()));
                                                 return false;
                                             }
                                         }}
                                 }   
-                                Expr::VarRef(assignName) => {
+                                Expr::VarRef(assignName, ..) => {
                                     let mut assignType: VarType;
                                     //Checks if variable is defined
                                     let checkLocVar = self.localTable.getType(&assignName.clone());
@@ -1861,7 +1931,7 @@ impl<'a> SyntaxChecker<'a> {
                                                     assignType = var
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -1884,7 +1954,7 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
@@ -1894,65 +1964,65 @@ impl<'a> SyntaxChecker<'a> {
                         VarType::Bool => {
                             match newValue.clone(){
                                 //Literals
-                                Expr::IntLiteral(val) => {
+                                Expr::IntLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::FloatLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign float to variable of type bool", lineNum.clone());
+                                Expr::FloatLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign float to variable of type bool", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::ArrayRef(name, index) => {
-                                    println!("Assinging: Int Array ref");
+                                Expr::ArrayRef(name, index, ..) => {
+                                    self.reportError("Assinging: Int Array ref".to_string());
                                     let checked = self.checkExpr(newValue.clone());
                                     if checked {
                                         return true;
                                     } else {
-                                        println!("Error with array reference on line {}", lineNum.clone());
+                                        self.reportError(format!("Error with array reference on line {}", lineNum.clone()));
                                         return false;
                                     }
-                                }Expr::BoolLiteral(val) => {
+                                }Expr::BoolLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::StringLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign string to variable of type bool", lineNum.clone());
+                                Expr::StringLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign string to variable of type bool", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::IntArrayLiteral(size, array) => {
-                                    println!("Cannot assign array to variable of type {}", targType.clone());
+                                Expr::IntArrayLiteral(size, array, ..) => {
+                                    self.reportError(format!("Cannot assign array to variable of type {}", targType.clone()));
                                     return false;
                                 }
                                 
                                 //Operations
-                                Expr::ArthOp(op1, op, op2) => {
+                                Expr::ArthOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in arithmetic operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in arithmetic operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::LogOp(op1, op, op2) => {
+                                Expr::LogOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in logical operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in logical operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::RelOp(op1, op, op2) => {
+                                Expr::RelOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in relational operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in relational operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
                                 
                                 //Calls/references
-                                Expr::ProcRef(procName, params) => {
+                                Expr::ProcRef(procName, params, ..) => {
                                     let mut procType: VarType;
                                     //Checks if procedure is defined
                                     let checkLocProc = self.localTable.getType(&procName.clone());
@@ -1961,14 +2031,14 @@ impl<'a> SyntaxChecker<'a> {
                                             procType = proc;
                                         }
                                         None => {
-                                            println!("Procedure does not exist locally, checking global");
-                                            let checkGlobProc = self.localTable.getType(&procName.clone());
+                                            verboseln!("Procedure does not exist locally, checking global");
+                                            let checkGlobProc = self.globalTable.getType(&procName.clone());
                                             match checkGlobProc{
                                                 Some(proc) => {
                                                     procType = proc
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -1992,7 +2062,7 @@ impl<'a> SyntaxChecker<'a> {
                                         }
                                     }
                                 }   
-                                Expr::VarRef(assignName) => {
+                                Expr::VarRef(assignName, ..) => {
                                     let mut assignType: VarType;
                                     //Checks if variable is defined
                                     let checkLocVar = self.localTable.getType(&assignName.clone());
@@ -2001,13 +2071,13 @@ impl<'a> SyntaxChecker<'a> {
                                             assignType = var;
                                         }
                                         None => {
-                                            let checkGlobVar = self.localTable.getType(&assignName.clone());
+                                            let checkGlobVar = self.globalTable.getType(&assignName.clone());
                                             match checkGlobVar{
                                                 Some(var) => {
                                                     assignType = var
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2027,7 +2097,7 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
@@ -2037,56 +2107,56 @@ impl<'a> SyntaxChecker<'a> {
                         VarType::Float => {
                             match newValue.clone(){
                                 //Literals
-                                Expr::IntLiteral(val) => {
+                                Expr::IntLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::FloatLiteral(val) => {
+                                Expr::FloatLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::ArrayRef(name, index) => {
+                                Expr::ArrayRef(name, index, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if checked {
                                         return true;
                                     } else {
-                                        println!("Error with array reference on line {}", lineNum.clone());
+                                        self.reportError(format!("Error with array reference on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::BoolLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign bool to variable of type float", lineNum.clone());
+                                Expr::BoolLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign bool to variable of type float", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::StringLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign string to variable of type float", lineNum.clone());
+                                Expr::StringLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign string to variable of type float", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::IntArrayLiteral(size, array) => {
-                                    println!("Cannot assign array to variable of type {}", targType.clone());
+                                Expr::IntArrayLiteral(size, array, ..) => {
+                                    self.reportError(format!("Cannot assign array to variable of type {}", targType.clone()));
                                     return false;
                                 }
 
                                 //Operations
-                                Expr::ArthOp(op1, op, op2) => {
+                                Expr::ArthOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in arithmetic operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in arithmetic operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }          
-                                Expr::LogOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot assign output of logical operation to variable of type float", lineNum.clone());
+                                Expr::LogOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign output of logical operation to variable of type float", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::RelOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot assign output of relational operation to variable of type float", lineNum.clone());
+                                Expr::RelOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign output of relational operation to variable of type float", lineNum.clone()));
                                     return false;
                                 }
                                 
                                 //Calls/references
-                                Expr::ProcRef(procName, params) => {
-                                    println!("Assigning: procedure {}", procName.clone());
+                                Expr::ProcRef(procName, params, ..) => {
+                                    verboseln!("Assigning: procedure {}", procName.clone());
                                     let mut procType: VarType;
                                     //Checks if procedure is defined
                                     let checkLocProc = self.localTable.getType(&procName.clone());
@@ -2095,15 +2165,15 @@ impl<'a> SyntaxChecker<'a> {
                                             procType = proc;
                                         }
                                         None => {
-                                            println!("Procedure does not exist locally, checking global");
-                                            let checkGlobProc = self.localTable.getType(&procName.clone());
+                                            verboseln!("Procedure does not exist locally, checking global");
+                                            let checkGlobProc = self.globalTable.getType(&procName.clone());
                                             match checkGlobProc{
                                                 Some(proc) => {
-                                                    println!("procedure exists globally");
+                                                    verboseln!("procedure exists globally");
                                                     procType = proc
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2114,7 +2184,7 @@ impl<'a> SyntaxChecker<'a> {
                                     //Checks procedure type compatability with int
                                     match procType{
                                         VarType::Bool =>{
-                                            println!("Error on line {}:\n Cannot assign output of procedure of type bool to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign output of procedure of type bool to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Int =>{
@@ -2124,31 +2194,31 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
                                 }   
-                                Expr::VarRef(assignName) => {
-                                    println!("Assigning: variable {}", assignName.clone());
+                                Expr::VarRef(assignName, ..) => {
+                                    verboseln!("Assigning: variable {}", assignName.clone());
                                     let mut assignType: VarType;
                                     //Checks if variable is defined
                                     let checkLocVar = self.localTable.getType(&assignName.clone());
                                     match checkLocVar{
                                         Some(var) => {
-                                            println!("variable exists locally");
+                                            verboseln!("variable exists locally");
                                             assignType = var;
                                         }
                                         None => {
-                                            println!("Variable does not exist locally, checking global");
-                                            let checkGlobVar = self.localTable.getType(&assignName.clone());
+                                            verboseln!("Variable does not exist locally, checking global");
+                                            let checkGlobVar = self.globalTable.getType(&assignName.clone());
                                             match checkGlobVar{
                                                 Some(var) => {
-                                                    println!("Variable exists globally");
+                                                    verboseln!("Variable exists globally");
                                                     assignType = var
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2159,7 +2229,7 @@ impl<'a> SyntaxChecker<'a> {
                                     //Checks variable type compatability with int
                                     match assignType{
                                         VarType::Bool =>{
-                                            println!("Error on line {}:\n Cannot assign value of variable of type bool to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign value of variable of type bool to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Int =>{
@@ -2169,7 +2239,7 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
@@ -2179,47 +2249,47 @@ impl<'a> SyntaxChecker<'a> {
                         VarType::Str => {
                             match newValue.clone(){
                                 //Literals
-                                Expr::IntLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign int to variable of type string", lineNum.clone());
+                                Expr::IntLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign int to variable of type string", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::FloatLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign float to variable of type string", lineNum.clone());
+                                Expr::FloatLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign float to variable of type string", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::ArrayRef(name, index) => {
-                                    println!("Error on line {}:\n Cannot assign int array value to variable of type string", lineNum.clone());
+                                Expr::ArrayRef(name, index, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign int array value to variable of type string", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::BoolLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign bool to variable of type string", lineNum.clone());
+                                Expr::BoolLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign bool to variable of type string", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::StringLiteral(val) => {
+                                Expr::StringLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::IntArrayLiteral(size, array) => {
-                                    println!("Cannot assign array to variable of type {}", targType.clone());
+                                Expr::IntArrayLiteral(size, array, ..) => {
+                                    self.reportError(format!("Cannot assign array to variable of type {}", targType.clone()));
                                     return false;
                                 }
 
                                 //Operations
-                                Expr::ArthOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot assign output of arithmetic operation to variable of type string", lineNum.clone());
+                                Expr::ArthOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign output of arithmetic operation to variable of type string", lineNum.clone()));
                                     return false;
                                 }          
-                                Expr::LogOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot assign output of logical operation to variable of type string", lineNum.clone());
+                                Expr::LogOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign output of logical operation to variable of type string", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::RelOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot assign output of relational operation to variable of type string", lineNum.clone());
+                                Expr::RelOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign output of relational operation to variable of type string", lineNum.clone()));
                                     return false;
                                 }
                                 
                                 //Calls/references
-                                Expr::ProcRef(procName, params) => {
-                                    println!("Assigning: procedure {}", procName.clone());
+                                Expr::ProcRef(procName, params, ..) => {
+                                    verboseln!("Assigning: procedure {}", procName.clone());
                                     let mut procType: VarType;
                                     //Checks if procedure is defined
                                     let checkLocProc = self.localTable.getType(&procName.clone());
@@ -2228,15 +2298,15 @@ impl<'a> SyntaxChecker<'a> {
                                             procType = proc;
                                         }
                                         None => {
-                                            println!("Procedure does not exist locally, checking global");
-                                            let checkGlobProc = self.localTable.getType(&procName.clone());
+                                            verboseln!("Procedure does not exist locally, checking global");
+                                            let checkGlobProc = self.globalTable.getType(&procName.clone());
                                             match checkGlobProc{
                                                 Some(proc) => {
-                                                    println!("procedure exists globally");
+                                                    verboseln!("procedure exists globally");
                                                     procType = proc
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2247,46 +2317,46 @@ impl<'a> SyntaxChecker<'a> {
                                     //Checks procedure type compatability with int
                                     match procType{
                                         VarType::Bool =>{
-                                            println!("Error on line {}:\n Cannot assign output of procedure of type bool to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign output of procedure of type bool to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Int =>{
-                                            println!("Error on line {}:\n Cannot assign output of procedure of type integer to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign output of procedure of type integer to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Float =>{
-                                            println!("Error on line {}:\n Cannot assign output of procedure of float bool to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign output of procedure of float bool to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Str => {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
                                 }   
-                                Expr::VarRef(assignName) => {
-                                    println!("Assigning: variable {}", assignName.clone());
+                                Expr::VarRef(assignName, ..) => {
+                                    verboseln!("Assigning: variable {}", assignName.clone());
                                     let mut assignType: VarType;
                                     //Checks if variable is defined
                                     let checkLocVar = self.localTable.getType(&assignName.clone());
                                     match checkLocVar{
                                         Some(var) => {
-                                            println!("variable exists locally");
+                                            verboseln!("variable exists locally");
                                             assignType = var;
                                         }
                                         None => {
-                                            println!("Variable does not exist locally, checking global");
-                                            let checkGlobVar = self.localTable.getType(&assignName.clone());
+                                            verboseln!("Variable does not exist locally, checking global");
+                                            let checkGlobVar = self.globalTable.getType(&assignName.clone());
                                             match checkGlobVar{
                                                 Some(var) => {
-                                                    println!("Variable exists globally");
+                                                    verboseln!("Variable exists globally");
                                                     assignType = var
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2297,22 +2367,22 @@ impl<'a> SyntaxChecker<'a> {
                                     //Checks variable type compatability with int
                                     match assignType{
                                         VarType::Bool =>{
-                                            println!("Error on line {}:\n Cannot assign value of variable of type bool to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign value of variable of type bool to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Int =>{
-                                            println!("Error on line {}:\n Cannot assign value of variable of type integer to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign value of variable of type integer to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Float =>{
-                                            println!("Error on line {}:\n Cannot assign value of variable of type float to variable of type float", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign value of variable of type float to variable of type float", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Str => {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
@@ -2321,16 +2391,16 @@ impl<'a> SyntaxChecker<'a> {
                         }
                         VarType::IntArray(targSize) => {
                             match newValue.clone(){
-                                Expr::IntArrayLiteral(newSize, array) => {
+                                Expr::IntArrayLiteral(newSize, array, ..) => {
                                     if(targSize == newSize) {
                                         return true;
                                     } else {
-                                        println!("Error on line {}:\n When copying integers, sizes must be equivalent", lineNum.clone());
+                                        self.reportError(format!("Error on line {}:\n When copying integers, sizes must be equivalent", lineNum.clone()));
                                         return false;
                                     }
                                 }
                                 _ => {
-                                    println!("Error on line {}:\n Cannot assign {} to integer array", lineNum.clone(), targType.clone());
+                                    self.reportError(format!("Error on line {}:\n Cannot assign {} to integer array", lineNum.clone(), targType.clone()));
                                     return true;
                                 }
                             }
@@ -2340,13 +2410,13 @@ impl<'a> SyntaxChecker<'a> {
                 } 
                 
                 //For index value references
-                else if let Expr::ArrayRef(ref targName, targIndexExpr) = valueToAssign {
+                else if let Expr::ArrayRef(ref targName, targIndexExpr, ..) = valueToAssign {
                     //Check if variable assignment is in the local table
                     let mut targValue: HashItem; 
                     //Looks for the value in the local then global table, retrieves it if so
                     if !(self.localTable.checkItem(targName)){
                         if !(self.globalTable.checkItem(targName)){
-                            println!("Attempting to assign value to undeclared variable: {} on line: {}", targName.clone(), lineNum.clone());
+                            self.reportError(format!("Attempting to assign value to undeclared variable: {} on line: {}", targName.clone(), lineNum.clone()));
                             return false;
                         } else {
                             let gotValue = self.globalTable.get(targName);
@@ -2355,7 +2425,7 @@ impl<'a> SyntaxChecker<'a> {
                                     targValue = val.clone();
                                 }
                                 None => {
-                                    println!("Error with value {} on line: {}", targName.clone(), lineNum.clone());
+                                    self.reportError(format!("Error with value {} on line: {}", targName.clone(), lineNum.clone()));
                                     return false;
                                 }
                             }
@@ -2367,7 +2437,7 @@ impl<'a> SyntaxChecker<'a> {
                                 targValue = val.clone();
                             }
                             None => {
-                                println!("Error with value {} on line: {}", targName.clone(), lineNum.clone());
+                                self.reportError(format!("Error with value {} on line: {}", targName.clone(), lineNum.clone()));
                                 return false;
                             }
                         }
@@ -2375,7 +2445,7 @@ impl<'a> SyntaxChecker<'a> {
                     
                     //Checks if value being assigned to is a variable
                     if targValue.hashType != HashItemType::Variable {
-                        println!("On line: {}, cannot assign value to procedure", lineNum.clone());
+                        self.reportError(format!("On line: {}, cannot assign value to procedure", lineNum.clone()));
                         return false;
                     }
                     
@@ -2389,68 +2459,68 @@ impl<'a> SyntaxChecker<'a> {
                             let checked = self.checkExpr(*targIndexExpr.clone());
                             if (checked){
                             } else {
-                                println!("Error with index expression on line {}", lineNum.clone());
+                                self.reportError(format!("Error with index expression on line {}", lineNum.clone()));
                                 return false;
                             }
                             
                             //Reacts based on the type of expression the index expression is
                             match *targIndexExpr{
                                 //Literals
-                                Expr::IntLiteral(val) => {
+                                Expr::IntLiteral(val, ..) => {
                                     if (val > targSize.into()){
-                                        println!("Error on line {}:\n Index {} is out of bounds", lineNum.clone(), val.clone())
+                                        self.reportError(format!("Error on line {}:\n Index {} is out of bounds", lineNum.clone(), val.clone()))
                                     } else {
                                     }
                                     
                                 }
-                                Expr::FloatLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot use float as index value", lineNum.clone());
+                                Expr::FloatLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot use float as index value", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::ArrayRef(name, index) => {
+                                Expr::ArrayRef(name, index, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if checked {
                                         return true;
                                     } else {
-                                        println!("Error with array reference on line {}", lineNum.clone());
+                                        self.reportError(format!("Error with array reference on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::BoolLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot use bool as index value", lineNum.clone());
+                                Expr::BoolLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot use bool as index value", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::StringLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot use string as index value", lineNum.clone());
+                                Expr::StringLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot use string as index value", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::IntArrayLiteral(size, array) => {
-                                    println!("Error on line {}:\n Cannot use array as index value", lineNum.clone());
+                                Expr::IntArrayLiteral(size, array, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot use array as index value", lineNum.clone()));
                                     return false;
                                 }
 
                                 //Operations
-                                Expr::ArthOp(op1, op, op2) => {
+                                Expr::ArthOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true
                                     } else {
-                                        println!("Error in arithmetic operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in arithmetic operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::LogOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot use logical operation as index value", lineNum.clone());
+                                Expr::LogOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot use logical operation as index value", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::RelOp(op1, op, op2) => {
-                                    println!("Error on line {}:\n Cannot use relational operation as index value", lineNum.clone());
+                                Expr::RelOp(op1, op, op2, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot use relational operation as index value", lineNum.clone()));
                                     return false;
                                 }
                                 
                                 //Calls/references
-                                Expr::ProcRef(procName, params) => {
-                                    println!("Indexing with procedure {}", procName.clone());
+                                Expr::ProcRef(procName, params, ..) => {
+                                    verboseln!("Indexing with procedure {}", procName.clone());
                                     let mut procType: VarType;
                                     //Checks if procedure is defined
                                     let checkLocProc = self.localTable.getType(&procName.clone());
@@ -2459,13 +2529,13 @@ impl<'a> SyntaxChecker<'a> {
                                             procType = proc;
                                         }
                                         None => {
-                                            let checkGlobProc = self.localTable.getType(&procName.clone());
+                                            let checkGlobProc = self.globalTable.getType(&procName.clone());
                                             match checkGlobProc{
                                                 Some(proc) => {
                                                     procType = proc
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2476,43 +2546,43 @@ impl<'a> SyntaxChecker<'a> {
                                     //Checks procedure type compatability with int
                                     match procType{
                                         VarType::Bool =>{
-                                            println!("Error on line {}:\n Cannot use procedure of type bool as index value", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot use procedure of type bool as index value", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Int =>{
-                                            println!("Procedure type int");
+                                            verboseln!("Procedure type int");
                                             
                                         }
                                         VarType::Float =>{
-                                            println!("Error on line {}:\n Cannot use procedure of type float as index value", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot use procedure of type float as index value", lineNum.clone()));
                                             return false;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot use procedure {} to index integer array", lineNum.clone(), procName.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot use procedure {} to index integer array", lineNum.clone(), procName.clone()));
                                             return false;
                                         }
                                     }
                                 }   
-                                Expr::VarRef(indexVarName) => {
-                                    println!("indexing with variable {}", indexVarName.clone());
+                                Expr::VarRef(indexVarName, ..) => {
+                                    verboseln!("indexing with variable {}", indexVarName.clone());
                                     let mut assignType: VarType;
                                     //Checks if variable is defined
                                     let checkLocVar = self.localTable.getType(&indexVarName.clone());
                                     match checkLocVar{
                                         Some(var) => {
-                                            println!("variable exists locally");
+                                            verboseln!("variable exists locally");
                                             assignType = var;
                                         }
                                         None => {
-                                            println!("Variable does not exist locally, checking global");
-                                            let checkGlobVar = self.localTable.getType(&indexVarName.clone());
+                                            verboseln!("Variable does not exist locally, checking global");
+                                            let checkGlobVar = self.globalTable.getType(&indexVarName.clone());
                                             match checkGlobVar{
                                                 Some(var) => {
-                                                    println!("Variable exists globally");
+                                                    verboseln!("Variable exists globally");
                                                     assignType = var
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), indexVarName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), indexVarName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2523,19 +2593,19 @@ impl<'a> SyntaxChecker<'a> {
                                     //Checks variable type compatability with int
                                     match assignType{
                                         VarType::Bool =>{
-                                            println!("Error on line {}:\n Cannot use variable of type bool as index value", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot use variable of type bool as index value", lineNum.clone()));
                                             return false;
                                         }
                                         VarType::Int =>{
-                                            println!("variable type int");
+                                            verboseln!("variable type int");
                                             
                                         }
                                         VarType::Float =>{
-                                            println!("Error on line {}:\n Cannot use variable of type float as index value", lineNum.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot use variable of type float as index value", lineNum.clone()));
                                             return false;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot use variable {} to index integer array", lineNum.clone(), indexVarName.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot use variable {} to index integer array", lineNum.clone(), indexVarName.clone()));
                                             return false;
                                         }
                                     }
@@ -2546,69 +2616,69 @@ impl<'a> SyntaxChecker<'a> {
                             //Now that we know the index is good, need to check the target assignment
                             match newValue.clone(){
                                 //Literals
-                                Expr::IntLiteral(val) => {
+                                Expr::IntLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::FloatLiteral(val) => {
+                                Expr::FloatLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::ArrayRef(name, index) => {
+                                Expr::ArrayRef(name, index, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if checked {
                                         return true;
                                     } else {
-                                        println!("Error with array reference on line {}", lineNum.clone());
+                                        self.reportError(format!("Error with array reference on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::BoolLiteral(val) => {
+                                Expr::BoolLiteral(val, ..) => {
                                     return true;
                                 }
-                                Expr::StringLiteral(val) => {
-                                    println!("Error on line {}:\n Cannot assign string to variable of type int", lineNum.clone());
+                                Expr::StringLiteral(val, ..) => {
+                                    self.reportError(format!("Error on line {}:\n Cannot assign string to variable of type int", lineNum.clone()));
                                     return false;
                                 }
-                                Expr::IntArrayLiteral(size, array) => {
-                                    println!("Cannot assign array to variable of type {}", targType.clone());
+                                Expr::IntArrayLiteral(size, array, ..) => {
+                                    self.reportError(format!("Cannot assign array to variable of type {}", targType.clone()));
                                     return false;
                                 }
 
                                 //Operations
-                                Expr::ArthOp(op1, op, op2) => {
+                                Expr::ArthOp(op1, op, op2, ..) => {
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in arithmetic operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in arithmetic operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::LogOp(op1, op, op2) => {
-                                    println!("Assigning LogOp");
-                                    println!("Checking expression");
+                                Expr::LogOp(op1, op, op2, ..) => {
+                                    self.reportError("Assigning LogOp".to_string());
+                                    verboseln!("Checking expression");
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in logical operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in logical operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
-                                Expr::RelOp(op1, op, op2) => {
-                                    println!("Assigning RelOp");
-                                    println!("Checking expression");
+                                Expr::RelOp(op1, op, op2, ..) => {
+                                    self.reportError("Assigning RelOp".to_string());
+                                    verboseln!("Checking expression");
                                     let checked = self.checkExpr(newValue.clone());
                                     if(checked){
                                         return true;
                                     } else {
-                                        println!("Error in relational operation on line {}", lineNum.clone());
+                                        self.reportError(format!("Error in relational operation on line {}", lineNum.clone()));
                                         return false;
                                     }
                                 }
                                 
                                 //Calls/references
-                                Expr::ProcRef(procName, params) => {
-                                    println!("Assigning: procedure {}", procName.clone());
+                                Expr::ProcRef(procName, params, ..) => {
+                                    verboseln!("Assigning: procedure {}", procName.clone());
                                     let mut procType: VarType;
                                     //Checks if procedure is defined
                                     let checkLocProc = self.localTable.getType(&procName.clone());
@@ -2617,13 +2687,13 @@ impl<'a> SyntaxChecker<'a> {
                                             procType = proc;
                                         }
                                         None => {
-                                            let checkGlobProc = self.localTable.getType(&procName.clone());
+                                            let checkGlobProc = self.globalTable.getType(&procName.clone());
                                             match checkGlobProc{
                                                 Some(proc) => {
                                                     procType = proc
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2643,31 +2713,31 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), procType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
                                 }   
-                                Expr::VarRef(assignName) => {
-                                    println!("Assigning: variable {}", assignName.clone());
+                                Expr::VarRef(assignName, ..) => {
+                                    verboseln!("Assigning: variable {}", assignName.clone());
                                     let mut assignType: VarType;
                                     //Checks if variable is defined
                                     let checkLocVar = self.localTable.getType(&assignName.clone());
                                     match checkLocVar{
                                         Some(var) => {
-                                            println!("variable exists locally");
+                                            verboseln!("variable exists locally");
                                             assignType = var;
                                         }
                                         None => {
-                                            println!("Variable does not exist locally, checking global");
-                                            let checkGlobVar = self.localTable.getType(&assignName.clone());
+                                            verboseln!("Variable does not exist locally, checking global");
+                                            let checkGlobVar = self.globalTable.getType(&assignName.clone());
                                             match checkGlobVar{
                                                 Some(var) => {
-                                                    println!("Variable exists globally");
+                                                    verboseln!("Variable exists globally");
                                                     assignType = var
                                                 }
                                                 None => {
-                                                    println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone());
+                                                    self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), assignName.clone()));
                                                     return false;
                                                     
                                                 }
@@ -2687,7 +2757,7 @@ impl<'a> SyntaxChecker<'a> {
                                             return true;
                                         }
                                         _ => {
-                                            println!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone());
+                                            self.reportError(format!("Error on line {}:\n Cannot assign {} to variable {} of type {}", lineNum.clone(), assignType.clone(), targName.clone(), targType.clone()));
                                             return false;
                                         }
                                     }
@@ -2695,51 +2765,51 @@ impl<'a> SyntaxChecker<'a> {
                             }                        
                         }
                         _ => {
-                            println!("Error on line {}:\n Variable {} is not an array", lineNum.clone(), targName.clone());
+                            self.reportError(format!("Error on line {}:\n Variable {} is not an array", lineNum.clone(), targName.clone()));
                             return false;
                         }
                     }
 
                 } else {
-                    println!("On line {}: cannot assign to non-variable", lineNum.clone());
+                    self.reportError(format!("On line {}: cannot assign to non-variable", lineNum.clone()));
                     return false;
                 }
 
 
             }
             //For Stmts that are just Exprs
-            Stmt::Expr(expr, lineNum) => {
+            Stmt::Expr(expr, lineNum, ..) => {
                 match (expr){
                     _ => {
                         let checked = self.checkExpr(expr.clone());
                         if checked {
                             return true;
                         } else {
-                            println!("Error with expression statement on line {}", lineNum.clone());
+                            self.reportError(format!("Error with expression statement on line {}", lineNum.clone()));
                             return false;
                         }
                     }
                 }
             }
             //For checking if statements
-            Stmt::If(condition, body, elseBody, lineNum) => {
+            Stmt::If(condition, body, elseBody, lineNum, ..) => {
                 //Checks the condition
                 match condition.clone() {
-                    Expr::IntArrayLiteral(size, array) => {
-                        println!("Error with if condition on line {}:\n Cannot use array as condition", lineNum.clone());
+                    Expr::IntArrayLiteral(size, array, ..) => {
+                        self.reportError(format!("Error with if condition on line {}:\n Cannot use array as condition", lineNum.clone()));
                         return false;
                     }
-                    Expr::FloatLiteral(val) => {
-                        println!("Error with if condition on line {}:\n Cannot use float as condition", lineNum.clone());
+                    Expr::FloatLiteral(val, ..) => {
+                        self.reportError(format!("Error with if condition on line {}:\n Cannot use float as condition", lineNum.clone()));
                         return false;
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Error with if condition on line {}:\n Cannot use string as condition", lineNum.clone());
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError(format!("Error with if condition on line {}:\n Cannot use string as condition", lineNum.clone()));
                         return false;
                     }
                     
                     
-                    Expr::ProcRef(procName, params) => {
+                    Expr::ProcRef(procName, params, ..) => {
                         let mut procType: VarType;
                         //Checks if procedure is defined
                         let checkLocProc = self.localTable.getType(&procName.clone());
@@ -2748,13 +2818,13 @@ impl<'a> SyntaxChecker<'a> {
                                 procType = proc;
                             }
                             None => {
-                                let checkGlobProc = self.localTable.getType(&procName.clone());
+                                let checkGlobProc = self.globalTable.getType(&procName.clone());
                                 match checkGlobProc{
                                     Some(proc) => {
                                         procType = proc
                                     }
                                     None => {
-                                        println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                        self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                         return false;
                                         
                                     }
@@ -2765,17 +2835,17 @@ impl<'a> SyntaxChecker<'a> {
                         //Checks procedure type compatability with int
                         match procType{
                             VarType::Bool =>{
-                                println!("Procedure type bool");
+                                verboseln!("Procedure type bool");
                             }
                             VarType::Int =>{
-                                println!("Procedure type int");
+                                verboseln!("Procedure type int");
                             }
                             VarType::Float =>{
-                                println!("Error with if condition on line {}:\n Cannot use float procedure as condition", lineNum.clone());
+                                self.reportError(format!("Error with if condition on line {}:\n Cannot use float procedure as condition", lineNum.clone()));
                         return false;
                             }
                             _ => {
-                                println!("Error on line {}:\n Cannot use procedure of type {} as if condition", lineNum.clone(), procType.clone());
+                                self.reportError(format!("Error on line {}:\n Cannot use procedure of type {} as if condition", lineNum.clone(), procType.clone()));
                                 return false;
                             }
                         }
@@ -2784,7 +2854,7 @@ impl<'a> SyntaxChecker<'a> {
                         let goodCond = self.checkExpr(condition.clone());
                         //If the condition is bad, fails here
                         if (!goodCond){
-                            println!("Error in if condition on line {}", lineNum.clone());
+                            self.reportError(format!("Error in if condition on line {}", lineNum.clone()));
                             return false;
                         //If the condition is good, checks the rest of the if statement
                         } else {
@@ -2798,7 +2868,7 @@ impl<'a> SyntaxChecker<'a> {
                                     Some(elseStmt) => {
                                         let goodElse = self.checkStmt(*elseStmt.clone());
                                         if(!goodElse){
-                                            println!("Error with else in if statement on line {}", lineNum.clone());
+                                            self.reportError(format!("Error with else in if statement on line {}", lineNum.clone()));
                                             return false;
                                         } else {
                                             return true;
@@ -2811,7 +2881,7 @@ impl<'a> SyntaxChecker<'a> {
 
                                 }
                             } else {
-                                println!("Error with body of if statement on line: {}", lineNum.clone());
+                                self.reportError(format!("Error with body of if statement on line: {}", lineNum.clone()));
                                 return false;
                             }
                         }
@@ -2819,26 +2889,26 @@ impl<'a> SyntaxChecker<'a> {
 
                     }   
                     
-                    Expr::VarRef(varCondName) => {
-                        println!("Assigning: variable {}", varCondName.clone());
+                    Expr::VarRef(varCondName, ..) => {
+                        verboseln!("Assigning: variable {}", varCondName.clone());
                         let mut ifCondType: VarType;
                         //Checks if variable is defined
                         let checkLocVar = self.localTable.getType(&varCondName.clone());
                         match checkLocVar{
                             Some(var) => {
-                                println!("variable exists locally");
+                                verboseln!("variable exists locally");
                                 ifCondType = var;
                             }
                             None => {
-                                println!("Variable does not exist locally, checking global");
-                                let checkGlobVar = self.localTable.getType(&varCondName.clone());
+                                verboseln!("Variable does not exist locally, checking global");
+                                let checkGlobVar = self.globalTable.getType(&varCondName.clone());
                                 match checkGlobVar{
                                     Some(var) => {
-                                        println!("Variable exists globally");
+                                        verboseln!("Variable exists globally");
                                         ifCondType = var
                                     }
                                     None => {
-                                        println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), varCondName.clone());
+                                        self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), varCondName.clone()));
                                         return false;
                                         
                                     }
@@ -2849,17 +2919,17 @@ impl<'a> SyntaxChecker<'a> {
                         //Checks variable type compatability with int
                         match ifCondType{
                             VarType::Bool =>{
-                                println!("Variable type bool");
+                                verboseln!("Variable type bool");
                             }
                             VarType::Int =>{
-                                println!("Variable type int");
+                                verboseln!("Variable type int");
                             }
                             VarType::Float =>{
-                                println!("Error on line {}:\n Cannot use variable of type float as if condition", lineNum.clone());
+                                self.reportError(format!("Error on line {}:\n Cannot use variable of type float as if condition", lineNum.clone()));
                                 return false;
                             }
                             _ => {
-                                println!("Error on line {}:\n Cannot use variable of type {} for if condition", lineNum.clone(), ifCondType.clone());
+                                self.reportError(format!("Error on line {}:\n Cannot use variable of type {} for if condition", lineNum.clone(), ifCondType.clone()));
                                 return false;
                             }
                         }
@@ -2868,7 +2938,7 @@ impl<'a> SyntaxChecker<'a> {
                         let goodCond = self.checkExpr(condition.clone());
                         //If the condition is bad, fails here
                         if (!goodCond){
-                            println!("Error in if condition on line {}", lineNum.clone());
+                            self.reportError(format!("Error in if condition on line {}", lineNum.clone()));
                             return false;
                         //If the condition is good, checks the rest of the if statement
                         } else {
@@ -2882,7 +2952,7 @@ impl<'a> SyntaxChecker<'a> {
                                     Some(elseStmt) => {
                                         let goodElse = self.checkStmt(*elseStmt.clone());
                                         if(!goodElse){
-                                            println!("Error with else in if statement on line {}", lineNum.clone());
+                                            self.reportError(format!("Error with else in if statement on line {}", lineNum.clone()));
                                             return false;
                                         } else {
                                             return true;
@@ -2895,7 +2965,7 @@ impl<'a> SyntaxChecker<'a> {
 
                                 }
                             } else {
-                                println!("Error with body of if statement on line: {}", lineNum.clone());
+                                self.reportError(format!("Error with body of if statement on line: {}", lineNum.clone()));
                                 return false;
                             }
                         }
@@ -2909,7 +2979,7 @@ impl<'a> SyntaxChecker<'a> {
                         let goodCond = self.checkExpr(condition.clone());
                         //If the condition is bad, fails here
                         if (!goodCond){
-                            println!("Error in if condition on line {}", lineNum.clone());
+                            self.reportError(format!("Error in if condition on line {}", lineNum.clone()));
                             return false;
                         //If the condition is good, checks the rest of the if statement
                         } else {
@@ -2923,7 +2993,7 @@ impl<'a> SyntaxChecker<'a> {
                                     Some(elseStmt) => {
                                         let goodElse = self.checkStmt(*elseStmt.clone());
                                         if(!goodElse){
-                                            println!("Error with else in if statement on line {}", lineNum.clone());
+                                            self.reportError(format!("Error with else in if statement on line {}", lineNum.clone()));
                                             return false;
                                         } else {
                                             return true;
@@ -2936,42 +3006,42 @@ impl<'a> SyntaxChecker<'a> {
 
                                 }
                             } else {
-                                println!("Error with body of if statement on line: {}", lineNum.clone());
+                                self.reportError(format!("Error with body of if statement on line: {}", lineNum.clone()));
                                 return false;
                             }
                         }
                     }
                 }
             }    
-            Stmt::For(assignment, condition, body, lineNum) => {
+            Stmt::For(assignment, condition, body, lineNum, ..) => {
 
                 //Checks if the condition is valid
                 let checked = self.checkExpr(condition.clone());
                 if checked {
                     //Continue
                 } else {
-                    println!("Error with for condition on line {}", lineNum.clone());
+                    self.reportError(format!("Error with for condition on line {}", lineNum.clone()));
                     return false;
                 }
 
                 //Ensures for condition is the correct type
                 match condition.clone() {
-                    Expr::IntArrayLiteral(size, array) => {
-                        println!("Error with if condition on line {}:\n Cannot use array as condition", lineNum.clone());
+                    Expr::IntArrayLiteral(size, array, ..) => {
+                        self.reportError(format!("Error with if condition on line {}:\n Cannot use array as condition", lineNum.clone()));
                         return false;
                     }
-                    Expr::FloatLiteral(val) => {
-                        println!("Error with if condition on line {}:\n Cannot use float as condition", lineNum.clone());
+                    Expr::FloatLiteral(val, ..) => {
+                        self.reportError(format!("Error with if condition on line {}:\n Cannot use float as condition", lineNum.clone()));
                         return false;
                     }
-                    Expr::StringLiteral(val) => {
-                        println!("Error with if condition on line {}:\n Cannot use string as condition", lineNum.clone());
+                    Expr::StringLiteral(val, ..) => {
+                        self.reportError(format!("Error with if condition on line {}:\n Cannot use string as condition", lineNum.clone()));
                         return false;
                     }
                     
                     
-                    Expr::ProcRef(procName, params) => {
-                        println!("If condition procedure {}", procName.clone());
+                    Expr::ProcRef(procName, params, ..) => {
+                        verboseln!("If condition procedure {}", procName.clone());
                         let mut procType: VarType;
                         //Checks if procedure is defined
                         let checkLocProc = self.localTable.getType(&procName.clone());
@@ -2980,13 +3050,13 @@ impl<'a> SyntaxChecker<'a> {
                                 procType = proc;
                             }
                             None => {
-                                let checkGlobProc = self.localTable.getType(&procName.clone());
+                                let checkGlobProc = self.globalTable.getType(&procName.clone());
                                 match checkGlobProc{
                                     Some(proc) => {
                                         procType = proc
                                     }
                                     None => {
-                                        println!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone());
+                                        self.reportError(format!("Error on line {}:\n Procedure {} is not defined", lineNum.clone(), procName.clone()));
                                         return false;
                                         
                                     }
@@ -2997,42 +3067,42 @@ impl<'a> SyntaxChecker<'a> {
                         //Checks procedure type compatability with int
                         match procType{
                             VarType::Bool =>{
-                                println!("Procedure type bool");
+                                verboseln!("Procedure type bool");
                             }
                             VarType::Int =>{
-                                println!("Procedure type int");
+                                verboseln!("Procedure type int");
                             }
                             VarType::Float =>{
-                                println!("Error with for condition on line {}:\n Cannot use float procedure as condition", lineNum.clone());
+                                self.reportError(format!("Error with for condition on line {}:\n Cannot use float procedure as condition", lineNum.clone()));
                         return false;
                             }
                             _ => {
-                                println!("Error on line {}:\n Cannot use procedure of type {} as for condition", lineNum.clone(), procType.clone());
+                                self.reportError(format!("Error on line {}:\n Cannot use procedure of type {} as for condition", lineNum.clone(), procType.clone()));
                                 return false;
                             }
                         }
                     }   
                     
-                    Expr::VarRef(varCondName) => {
-                        println!("Assigning: variable {}", varCondName.clone());
+                    Expr::VarRef(varCondName, ..) => {
+                        verboseln!("Assigning: variable {}", varCondName.clone());
                         let mut forCondType: VarType;
                         //Checks if variable is defined
                         let checkLocVar = self.localTable.getType(&varCondName.clone());
                         match checkLocVar{
                             Some(var) => {
-                                println!("variable exists locally");
+                                verboseln!("variable exists locally");
                                 forCondType = var;
                             }
                             None => {
-                                println!("Variable does not exist locally, checking global");
-                                let checkGlobVar = self.localTable.getType(&varCondName.clone());
+                                verboseln!("Variable does not exist locally, checking global");
+                                let checkGlobVar = self.globalTable.getType(&varCondName.clone());
                                 match checkGlobVar{
                                     Some(var) => {
-                                        println!("Variable exists globally");
+                                        verboseln!("Variable exists globally");
                                         forCondType = var
                                     }
                                     None => {
-                                        println!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), varCondName.clone());
+                                        self.reportError(format!("Error on line {}:\n Variable {} is not defined", lineNum.clone(), varCondName.clone()));
                                         return false;
                                         
                                     }
@@ -3043,17 +3113,17 @@ impl<'a> SyntaxChecker<'a> {
                         //Checks variable type compatability with int
                         match forCondType{
                             VarType::Bool =>{
-                                println!("Variable type bool");
+                                verboseln!("Variable type bool");
                             }
                             VarType::Int =>{
-                                println!("Variable type int");
+                                verboseln!("Variable type int");
                             }
                             VarType::Float =>{
-                                println!("Error on line {}:\n Cannot use variable of type float as for condition", lineNum.clone());
+                                self.reportError(format!("Error on line {}:\n Cannot use variable of type float as for condition", lineNum.clone()));
                                 return false;
                             }
                             _ => {
-                                println!("Error on line {}:\n Cannot use variable of type {} as for condition", lineNum.clone(), forCondType.clone());
+                                self.reportError(format!("Error on line {}:\n Cannot use variable of type {} as for condition", lineNum.clone(), forCondType.clone()));
                                 return false;
                             }
                         }
@@ -3067,7 +3137,7 @@ impl<'a> SyntaxChecker<'a> {
                         let goodCond = self.checkExpr(condition.clone());
                         //If the condition is bad, fails here
                         if (!goodCond){
-                            println!("Error in if condition on line {}", lineNum.clone());
+                            self.reportError(format!("Error in if condition on line {}", lineNum.clone()));
                             return false;
                         //If the condition is good, checks the rest of the if statement
                         } else {
@@ -3083,15 +3153,15 @@ impl<'a> SyntaxChecker<'a> {
                     //Checks for there is an else
                     return true;
                 } else {
-                    println!("Error with body of for statement on line: {}", lineNum.clone());
+                    self.reportError(format!("Error with body of for statement on line: {}", lineNum.clone()));
                     return false;
                 }
             }  
-            Stmt::Block(stmts, lineNum) => {
+            Stmt::Block(stmts, lineNum, ..) => {
                 for instr in stmts {
                     let good = self.checkStmt(instr.clone());
                     if (!good){
-                        println!("Error in header:");
+                        verboseln!("Error in header:");
                         instr.display(0);
                         return false;
                     } else {
@@ -3100,23 +3170,75 @@ impl<'a> SyntaxChecker<'a> {
                 }
                 return true;
             }
-            Stmt::Error(report, errMsg) => {
-                println!("Error found in AST: {}", errMsg);
+            Stmt::Error(report, errMsg, ..) => {
+                self.reportError(format!("Error found in AST: {}", errMsg));
                 return false;
             }
-            Stmt::Program(name, header, body, lineNum) => {
+            Stmt::Program(name, header, body, lineNum, ..) => {
+                return true;
+            }
+            Stmt::Module(name, header, lineNum, ..) => {
+                return true;
+            }
+            //The imported symbols are added by the resolver before checking (resolver::checkUnits)
+            Stmt::Import(name, lineNum, ..) => {
+                return true;
+            }
+            //An external procedure is declared like a procedure without a body, only types C can take are allowed
+            Stmt::ExternProcDecl(retType, procName, params, lineNum, _, nameSpan) => {
+                if self.globalTable.checkItem(&procName.clone()) {
+                    self.reportError(format!("Error on line {}: procedure {} defined twice", lineNum.clone(), procName.clone()));
+                    return false;
+                }
+                match retType {
+                    VarType::Int | VarType::Float | VarType::Bool => {}
+                    _ => {
+                        self.reportError(format!("Error on line {}: external procedure {} can only return an integer, float or bool, not {}", lineNum.clone(), procName.clone(), retType.clone()));
+                        return false;
+                    }
+                }
+
+                //The parameters go into the procedure's table like the ones of a normal procedure
+                let mut paramStrings: Vec<String> = Vec::new();
+                let mut paramTable = SymbolTable::newEmpty();
+                if let Stmt::Block(ref instrs, _, ..) = *params {
+                    for instr in instrs {
+                        match instr {
+                            Stmt::VarDecl(varName, VarType::IntArray(_), _, ..) => {
+                                self.reportError(format!("Error on line {}: parameter {} of external procedure {} can't be an array", lineNum.clone(), varName.clone(), procName.clone()));
+                                return false;
+                            }
+                            Stmt::VarDecl(varName, varType, _, ..) => {
+                                if paramTable.checkItem(&varName.clone()) {
+                                    self.reportError(format!("Error on line {}: parameter {} defined twice", lineNum.clone(), varName.clone()));
+                                    return false;
+                                }
+                                paramTable.insert(HashItem::newVar(varName.clone(), varType.clone()).declaredAt(instr.nameSpan().unwrap_or_default()));
+                                paramStrings.push(varName.clone());
+                            }
+                            _ => {
+                                self.reportError(format!("Error with external procedure {} declaration on line {}:\n Procedure parameters must be variable declarations", procName.clone(), lineNum.clone()));
+                                return false;
+                            }
+                        }
+                    }
+                }
+
+                let procItemType = HashItemType::newProcItem(params.clone(), paramStrings, paramTable);
+                let procItem = HashItem::newProc(procName.clone(), retType.clone(), procItemType).declaredAt(nameSpan);
+                self.globalTable.symTab.insert(procName.clone(), procItem);
                 return true;
             }
-            Stmt::Return(retVal, lineNum) => {
+            Stmt::Return(retVal, lineNum, ..) => {
                 let checked = self.checkExpr(retVal.clone());
                 if checked {
                     return true;
                 } else {
-                    println!("Error with return statement on line {}", lineNum.clone());
+                    self.reportError(format!("Error with return statement on line {}", lineNum.clone()));
                     return false;
                 }
             }
-            Stmt::StringLiteral(val, lineNum) => {
+            Stmt::StringLiteral(val, lineNum, ..) => {
                 return true;
             }
         }
@@ -3141,6 +3263,7 @@ pub struct HashItem {
     itemType: VarType,      //The type of the variable/the return type of the proc
     name: String,           //The name of the item
     hashType: HashItemType, //Which type of hash item it is (Procedure or variable)
+    span: Option<Span>,     //Where the name is declared, None for the builtins which aren't in the source
 }
 //Assistive functions for hashItem
 impl HashItem {
@@ -3151,6 +3274,7 @@ impl HashItem {
             itemType: variableType,
             name: varName,
             hashType: HashItemType::Variable,
+            span: None,
         }
     }
 
@@ -3160,14 +3284,36 @@ impl HashItem {
             itemType: procType,
             name: procName,
             hashType: procItem,
+            span: None,
         }
     }
 
+    //Sets where the item is declared
+    pub fn declaredAt(mut self, span: Span) -> HashItem {
+        self.span = Some(span);
+        return self;
+    }
+
+    //The getter for where the item is declared
+    pub fn getSpan(&self) -> Option<Span> {
+        return self.span;
+    }
+
     //The getter for type
     pub fn getType(&mut self) -> VarType {
         return self.itemType.clone();
     }
 
+    //The getter for the name
+    pub fn getName(&self) -> String {
+        return self.name.clone();
+    }
+
+    //The getter for the kind of item (Procedure or Variable)
+    pub fn getHashType(&self) -> HashItemType {
+        return self.hashType.clone();
+    }
+
     //Checking an items type against another
     pub fn checkType(&mut self, typeCheck: VarType) -> bool {
         if(typeCheck == self.itemType){
@@ -3195,82 +3341,16 @@ impl SymbolTable{
         //Creates the empty hash map
         let mut symHash: HashMap<String, HashItem> = HashMap::new();
 
-        //Seeding the symbol table with the built in functions
-        let builtIns = vec![
-            ("getbool", HashItem::newProc("getbool".to_string(), VarType::Bool, HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), ("0".to_string()))), Vec::new(), SymbolTable::newBuiltIn()))),
-            ("getinteger", HashItem::newProc("getinteger".to_string(), VarType::Int, HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), ("0".to_string()))), Vec::new(), SymbolTable::newBuiltIn()))),
-            ("getfloat", HashItem::newProc("getfloat".to_string(), VarType::Float, HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), ("0".to_string()))), Vec::new(), SymbolTable::newBuiltIn()))),
-            ("getstring", HashItem::newProc("getstring".to_string(), VarType::Str, HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), ("0".to_string()))), Vec::new(), SymbolTable::newBuiltIn()))),
-            ("getbool", HashItem::newProc("getBool".to_string(), VarType::Bool, HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), ("0".to_string()))), Vec::new(), SymbolTable::newBuiltIn()))),
-            
-            (
-                "putbool",
-                HashItem::newProc(
-                    "putbool".to_string(),
-                    VarType::Bool,
-                    HashItemType::Procedure(
-                        Box::new(Stmt::StringLiteral("NONE".to_string(), "0".to_string())),
-                        vec!["boolparam".to_string()], 
-                        SymbolTable::newBuiltIn(),
-                    ),
-                ),
-            ),
-
-            (
-                "putinteger",
-                HashItem::newProc(
-                    "putinteger".to_string(),
-                    VarType::Bool,
-                    HashItemType::Procedure(
-                        Box::new(Stmt::StringLiteral("NONE".to_string(), "0".to_string())),
-                        vec!["intparam".to_string()], 
-                        SymbolTable::newBuiltIn(),
-                    ),
-                ),
-            ),
-
-            (
-                "putfloat",
-                HashItem::newProc(
-                    "putfloat".to_string(),
-                    VarType::Bool,
-                    HashItemType::Procedure(
-                        Box::new(Stmt::StringLiteral("NONE".to_string(), "0".to_string())),
-                        vec!["floatparam".to_string()],  
-                        SymbolTable::newBuiltIn(),
-                    ),
-                ),
-            ),
-
-            (
-                "putstring",
-                HashItem::newProc(
-                    "putstring".to_string(),
-                    VarType::Bool,
-                    HashItemType::Procedure(
-                        Box::new(Stmt::StringLiteral("NONE".to_string(), "0".to_string())),
-                        vec!["stringparam".to_string()],  // Replace this with your desired string
-                        SymbolTable::newBuiltIn(),
-                    ),
-                ),
-            ),
-
-            (
-                "sqrt",
-                HashItem::newProc(
-                    "sqrt".to_string(),
-                    VarType::Float,
-                    HashItemType::Procedure(
-                        Box::new(Stmt::StringLiteral("NONE".to_string(), "0".to_string())),
-                        vec!["intparam".to_string()],  // Replace this with your desired string
-                        SymbolTable::newBuiltIn(),
-                    ),
-                ),
-            ),
-        ];
-        //Inserted seed values into hash table
-        for (key, value) in builtIns {
-            symHash.insert(key.to_string(), value);
+        //Seeding the symbol table with the built in functions, each one gets a table with its parameters
+        for builtin in BUILTINS {
+            let mut paramTable = SymbolTable::newEmpty();
+            let mut paramNames: Vec<String> = Vec::new();
+            for (paramName, paramType) in builtin.params {
+                paramTable.insert(HashItem::newVar(paramName.to_string(), paramType.clone()));
+                paramNames.push(paramName.to_string());
+            }
+            let procItem = HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), "0".to_string(), Span::default())), paramNames, paramTable);
+            symHash.insert(builtin.name.to_string(), HashItem::newProc(builtin.name.to_string(), builtin.retType.clone(), procItem));
         }
 
         // println!("symbol table created");
@@ -3284,37 +3364,6 @@ impl SymbolTable{
         }
     }
     
-    pub fn newBuiltIn() -> SymbolTable {
-        //Creates the empty hash map
-        let mut symHash: HashMap<String, HashItem> = HashMap::new();
-
-        //THESE NEED TO BE WRITTEN TO INCLUDE ACTUAL STUFF
-        let mut builtInHash: HashMap<String, HashItem> = HashMap::new();
-        let builtInStmt = Stmt::StringLiteral(("NULL".to_string()), "0".to_string());
-        // let builtInParams = Vec::new();
-        //Seeding the symbol table with the built in functions
-        let builtIns = vec![
-            // ("getbool", HashItem::newProc("getbool".to_string(), VarType::Bool, HashItemType::Procedure(Box::new(Stmt::StringLiteral("NONE".to_string(), ("0".to_string()))), Vec::new(), SymbolTable::newEmpty()))),
-            ("boolparam", HashItem::newVar("boolparam".to_string(), VarType::Bool)),
-            ("intparam", HashItem::newVar("intparam".to_string(), VarType::Int)),
-            ("floatparam", HashItem::newVar("floatparam".to_string(), VarType::Float)),
-            ("stringparam", HashItem::newVar("stringparam".to_string(), VarType::Str)),
-
-
-        ];
-        //Inserted seed values into hash table
-        for (key, value) in builtIns {
-            symHash.insert(key.to_string(), value);
-        }
-
-        
-
-
-        SymbolTable{
-            symTab: symHash,
-        }
-    }
-
     pub fn newEmpty() -> SymbolTable {
         //Creates the empty hash map
         let mut symHash: HashMap<String, HashItem> = HashMap::new();
@@ -3329,7 +3378,7 @@ impl SymbolTable{
 
 
     //Returns an option of if the item exists or not
-    pub fn get(&mut self, itemName: &String) -> Option<&HashItem> {
+    pub fn get(&self, itemName: &String) -> Option<&HashItem> {
         if self.symTab.contains_key(itemName) {
             return(self.symTab.get(itemName));
             // println!("Key '{}' exists in the map.", key);
@@ -3354,6 +3403,18 @@ impl SymbolTable{
         }
     }
 
+    //Returns every item in the table sorted by name, used by tools that list the symbols (completion in the language server)
+    pub fn getItems(&self) -> Vec<HashItem> {
+        let mut items: Vec<HashItem> = self.symTab.values().cloned().collect();
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        return items;
+    }
+
+    //Adds an item that was declared somewhere else, used for the symbols imported from a module
+    pub fn insert(&mut self, item: HashItem) {
+        self.symTab.insert(item.getName(), item);
+    }
+
     //Checks if a variable/procedure is in the table, returns a bool
     pub fn checkItem(&mut self, itemName: &String) -> bool {
         let value = self.symTab.get(itemName);
@@ -3371,7 +3432,7 @@ impl SymbolTable{
 }
 
 fn combine_blocks(block1: Box<Stmt>, block2: Box<Stmt>) -> Option<Vec<Stmt>> {
-    if let (Stmt::Block(mut stmts1, _), Stmt::Block(stmts2, _)) = (*block1, *block2) {
+    if let (Stmt::Block(mut stmts1, _, ..), Stmt::Block(stmts2, _, ..)) = (*block1, *block2) {
         stmts1.extend(stmts2);
         Some(stmts1)
     } else {
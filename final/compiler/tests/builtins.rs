@@ -0,0 +1,343 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Checks the builtin table against the functions the runtime library exports
+
+//package imports
+use {
+    compiler::models::{builtins::{runtimeAddresses, runtimeType, BUILTINS, C_EXPORTS, RUNTIME_HOOKS}, parser::NumberWidth, typechecker::{HashItemType, SymbolTable}},
+    std::{collections::HashMap, env, fs, io::Write, path::Path, process::{Command, Stdio}},
+};
+
+//The #[no_mangle] functions of funcLib, name -> (parameter types, return type)
+fn runtimeExports() -> HashMap<String, (Vec<String>, String)> {
+    let source = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("funcLib/src/lib.rs")).unwrap();
+    let mut exports = HashMap::new();
+    let mut exported = false;
+    for line in source.lines() {
+        let line = line.trim();
+        if line == "#[no_mangle]" {
+            exported = true;
+            continue;
+        }
+        if !exported || line.starts_with("//") {
+            continue;
+        }
+        exported = false;
+        let signature = line.strip_prefix("pub extern \"C\" fn ").unwrap_or_else(|| panic!("Exported function isn't extern \"C\": {}", line));
+        let (name, rest) = signature.split_once('(').unwrap();
+        let (params, rest) = rest.split_once(')').unwrap();
+        let paramTypes: Vec<String> = params.split(',').filter(|param| param.trim() != "").map(|param| param.split_once(':').unwrap().1.trim().to_string()).collect();
+        let retType = rest.split("->").nth(1).map(|ret| ret.trim_end_matches('{').trim().to_string()).unwrap_or("()".to_string());
+        exports.insert(name.to_string(), (paramTypes, retType));
+    }
+    return exports;
+}
+
+#[test]
+fn everyBuiltinMatchesTheRuntime() {
+    let exports = runtimeExports();
+    for width in [NumberWidth::Bits32, NumberWidth::Bits64] {
+        for builtin in BUILTINS {
+            let symbol = builtin.symbolFor(width);
+            let (paramTypes, retType) = exports.get(&symbol).unwrap_or_else(|| panic!("funcLib doesn't export {}", symbol));
+            let expected: Vec<String> = builtin.params.iter().map(|(_, paramType)| runtimeType(paramType, width).to_string()).collect();
+            assert_eq!(paramTypes, &expected, "Parameters of {}", symbol);
+            assert_eq!(retType, runtimeType(&builtin.retType, width), "Return type of {}", symbol);
+        }
+    }
+    for name in exports.keys() {
+        let isBuiltin = BUILTINS.iter().any(|builtin| &builtin.symbolFor(NumberWidth::Bits32) == name || &builtin.symbolFor(NumberWidth::Bits64) == name);
+        let isHook = RUNTIME_HOOKS.iter().any(|(hook, _)| hook == name) || C_EXPORTS.contains(&name.as_str());
+        assert!(isBuiltin || isHook, "funcLib exports {} but it isn't a builtin", name);
+    }
+}
+
+//The JIT maps the generated code's calls with these addresses, so every symbol needs its own function
+#[test]
+fn everyRuntimeSymbolHasAnAddress() {
+    let addresses = runtimeAddresses();
+    let mut symbols: Vec<String> = Vec::new();
+    for builtin in BUILTINS {
+        symbols.push(builtin.symbolFor(NumberWidth::Bits32));
+        if builtin.hasNumbers() {
+            symbols.push(builtin.symbolFor(NumberWidth::Bits64));
+        }
+    }
+    symbols.extend(RUNTIME_HOOKS.iter().map(|(hook, _)| hook.to_string()));
+    let mapped: Vec<String> = addresses.iter().map(|(symbol, _)| symbol.clone()).collect();
+    assert_eq!(mapped, symbols);
+    for (index, (symbol, address)) in addresses.iter().enumerate() {
+        assert_ne!(*address, 0, "{} has no address", symbol);
+        //A row that gives the 32 bit function for its 64 symbol would call it with the wrong types
+        let other = addresses[index + 1..].iter().find(|(_, otherAddress)| otherAddress == address);
+        assert!(other.is_none(), "{} and {} have the same address", symbol, other.unwrap().0);
+    }
+}
+
+#[test]
+fn symbolTableIsSeededFromTheTable() {
+    let mut table = SymbolTable::new();
+    for builtin in BUILTINS {
+        let item = table.get(&builtin.name.to_string()).unwrap().clone();
+        assert_eq!(item.getName(), builtin.name);
+        assert_eq!(item.clone().getType(), builtin.retType);
+        let HashItemType::Procedure(_, params, mut paramTable) = item.getHashType() else { panic!("{} is not a procedure", builtin.name) };
+        assert_eq!(params.len(), builtin.params.len(), "Parameters of {}", builtin.name);
+        for (paramName, paramType) in builtin.params {
+            assert_eq!(paramTable.getType(&paramName.to_string()), Some(paramType.clone()));
+        }
+    }
+}
+
+//Runs a program with the backend, returns its exit code and what it printed after the compiler's own output
+fn runProgram(name: &str, source: &str, backend: &str) -> (i32, Vec<String>) {
+    return runProgramWith(name, source, backend, &[]);
+}
+
+fn runProgramWith(name: &str, source: &str, backend: &str, flags: &[&str]) -> (i32, Vec<String>) {
+    let (status, printed, _) = runProgramFull(name, source, backend, flags, "");
+    return (status, printed);
+}
+
+//How each backend is asked for, the interpreter or the run command that JIT compiles the program
+const INTERP: &str = "--backend=interp";
+const RUN: &str = "run";
+
+//The backends a program is run on to check they give the same results, run needs the LLVM backend
+fn backends() -> Vec<&'static str> {
+    let mut backends = vec![INTERP];
+    if cfg!(feature = "llvm") {
+        backends.push(RUN);
+    }
+    return backends;
+}
+
+//Runs a program with the backend and the input on stdin, returns its exit code, what it printed and what it wrote to stderr
+fn runProgramFull(name: &str, source: &str, backend: &str, flags: &[&str], input: &str) -> (i32, Vec<String>, String) {
+    let dir = env::temp_dir().join(format!("builtins-test-{}-{}-{}", name, backend.trim_start_matches("--backend="), std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let srcPath = dir.join(format!("{}.src", name));
+    fs::write(&srcPath, source).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_compiler")).arg(backend).args(flags).arg(&srcPath)
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    //Only the program writes to stdout, the compiler's progress is on stderr with --verbose
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let printed: Vec<String> = stdout.lines().map(|line| line.to_string()).collect();
+    if !stdout.is_empty() {
+        assert!(stdout.ends_with('\n'), "{} didn't end its output with a newline: {:?}", name, stdout);
+    }
+    return (output.status.code().unwrap(), printed, String::from_utf8_lossy(&output.stderr).to_string());
+}
+
+#[test]
+fn runtimeFunctionsRun() {
+    let source = "program Rt is\nvariable s : string;\nvariable b : bool;\nbegin\n\
+        s := concat(\"hello\", inttostring(42));\nputstring(s);\nputinteger(stringlength(s));\nputstring(substring(s, 1, 3));\n\
+        putinteger(stringcompare(\"abc\", \"abd\"));\nputinteger(stringtoint(\"122\") + 1);\nputfloat(stringtofloat(\"1.5\"));\n\
+        putfloat(pow(2, 10));\nputfloat(floor(2.7));\nputfloat(abs(0.0 - 3.5));\nputfloat(cos(0.0));\nputfloat(log(1.0));\n\
+        putbool(time() > 1000000000);\nb := exit(3);\nputstring(\"not reached\");\nend program.\n";
+    for backend in backends() {
+        let (status, printed) = runProgram("rt", source, backend);
+        assert_eq!(status, 3, "{}", backend);
+        assert_eq!(printed, vec!["hello42", "7", "ell", "-1", "123", "1.5", "1024", "2", "3.5", "1", "0", "true"], "{}", backend);
+    }
+}
+
+#[test]
+fn seededRandomNumbersRepeat() {
+    let source = "program Rand is\nvariable b : bool;\nbegin\nb := seedrandom(7);\nputinteger(random(1000));\nputinteger(random(1000));\nputinteger(random(0));\nend program.\n";
+    let (_, first) = runProgram("rand", source, INTERP);
+    let (_, second) = runProgram("rand2", source, INTERP);
+    assert_eq!(first, second);
+    assert_eq!(first[2], "0");
+    for value in &first[..2] {
+        let value: i32 = value.parse().unwrap();
+        assert!((0..1000).contains(&value));
+    }
+    //Both backends use the runtime's generator, so they give the same numbers
+    for backend in backends() {
+        assert_eq!(runProgram("rand", source, backend).1, first, "{}", backend);
+    }
+}
+
+#[test]
+fn stringsKeepEscapesAndTheirRealLength() {
+    let source = "program Esc is\nvariable s : string;\nbegin\nputstring(\"line\\n\\ttab \\\"quoted\\\" \\\\ \\u{e9}\");\n\
+        s := concat(\"hello \", \"world\");\nputstring(s);\nputinteger(stringlength(\"h\\u{e9}llo \"));\nend program.\n";
+    for backend in backends() {
+        let (_, printed) = runProgram("esc", source, backend);
+        assert_eq!(printed, vec!["line", "\ttab \"quoted\" \\ \u{e9}", "hello world", "6"], "{}", backend);
+    }
+}
+
+#[test]
+fn stringsHaveNoLengthLimit() {
+    let long = "x".repeat(100);
+    let source = format!("program Long is\nvariable s : string;\nbegin\ns := \"{}\";\ns := concat(s, s);\nputinteger(stringlength(s));\nputstring(substring(s, 195, 10));\nend program.\n", long);
+    for backend in backends() {
+        let (_, printed) = runProgram("long", &source, backend);
+        assert_eq!(printed, vec!["200", "xxxxx"], "{}", backend);
+    }
+}
+
+#[test]
+fn stringsCanHoldNuls() {
+    //A string keeps its length, so a NUL in it is a character like any other
+    let source = "program Nul is\nvariable s : string;\nbegin\ns := \"a\\u{0}b\";\nputinteger(stringlength(s));\nputstring(s);\n\
+        putinteger(stringcompare(s, \"a\"));\ns := concat(s, \"\\u{0}\");\nputinteger(stringlength(s));\nputstring(substring(s, 2, 2));\nend program.\n";
+    for backend in backends() {
+        let (_, printed) = runProgram("nul", source, backend);
+        assert_eq!(printed, vec!["3", "a\0b", "1", "4", "b\0"], "{}", backend);
+    }
+}
+
+#[test]
+fn wideNumbersHold64BitsAndDoubles() {
+    //The 64 versions of the builtins are called, and integers and floats are converted at the wide types
+    let source = "program Wide is\nvariable i : integer;\nvariable f : float;\nvariable s : string;\nbegin\n\
+        i := 2147483647 + 1;\nputinteger(i);\ni := 9223372036854775807;\nputinteger(i + 1);\n\
+        f := 0.1;\nputfloat(f);\nputbool((1.0e300 * 10.0) > 1.0e300);\nputinteger(stringtoint(\"5000000000\"));\nputfloat(sqrt(10000000000));\n\
+        putfloat(pow(2, 40));\ni := 5000000000.0;\nputinteger(i);\nf := i;\nputfloat(f / 3.0);\n\
+        s := inttostring(i * 2);\nputstring(substring(s, 0, 4));\nputinteger(stringlength(s));\nputstring(floattostring(stringtofloat(\"1e300\")));\nend program.\n";
+    for backend in backends() {
+        let (_, printed) = runProgramWith("wide", source, backend, &["--wide-numbers"]);
+        assert_eq!(printed, vec!["2147483648", "-9223372036854775808", "0.1", "true", "5000000000", "100000",
+            "1099511627776", "5000000000", "1666666666.6666667", "1000", "11", "1e300"], "{}", backend);
+    }
+
+    //Without the flag the same arithmetic wraps at 32 bits and floats are f32
+    let source = "program Narrow is\nvariable i : integer;\nvariable f : float;\nbegin\n\
+        i := 2147483647 + 1;\nputinteger(i);\nf := 16777217.0;\nputfloat(f);\nputinteger(stringtoint(\"5000000000\"));\n\
+        i := 0.0 - 7.9;\nputinteger(i);\nf := i;\nputfloat(f / 2.0);\nend program.\n";
+    for backend in backends() {
+        let (_, printed) = runProgram("narrow", source, backend);
+        assert_eq!(printed, vec!["-2147483648", "16777216", "0", "-7", "-3.5"], "{}", backend);
+    }
+}
+
+#[test]
+fn checkedArithmeticStopsOnOverflowAndDivisionByZero() {
+    let overflow = "program Over is\nvariable i : integer;\nbegin\ni := 2147483647;\nputinteger(i);\ni := i + 1;\nputinteger(i);\nend program.\n";
+    //Compiled, the checks call the llvm.*.with.overflow intrinsics and rt_arithpanic
+    for backend in backends() {
+        let (status, printed) = runProgram("wraps", overflow, backend);
+        assert_eq!((status, printed), (0, vec!["2147483647".to_string(), "-2147483648".to_string()]), "{}", backend);
+        let (status, printed, stderr) = runProgramFull("overflow", overflow, backend, &["--checked-arith"], "");
+        assert_eq!((status, printed), (1, vec!["2147483647".to_string()]), "{}", backend);
+        assert!(stderr.contains("Runtime error on line 6: Integer overflow in 2147483647 + 1"), "{}: {}", backend, stderr);
+
+        //The check follows the number width
+        let (status, printed) = runProgramWith("wideover", overflow, backend, &["--checked-arith", "--wide-numbers"]);
+        assert_eq!((status, printed), (0, vec!["2147483647".to_string(), "2147483648".to_string()]), "{}", backend);
+
+        for (expr, message) in [
+            ("i := 0 - 2147483647;\ni := i - 2;", "Integer overflow in -2147483647 - 2"),
+            ("i := 65536;\ni := i * 65536;", "Integer overflow in 65536 * 65536"),
+            ("i := -2147483648;\ni := i / -1;", "Integer overflow in -2147483648 / -1"),
+            ("i := 0;\ni := 5 / i;", "Integer division by zero in 5 / 0"),
+        ] {
+            let source = format!("program Ops is\nvariable i : integer;\nbegin\n{}\nend program.\n", expr);
+            let (status, _, stderr) = runProgramFull("ops", &source, backend, &["--checked-arith"], "");
+            assert_eq!(status, 1, "{} {}", backend, expr);
+            assert!(stderr.contains(&format!("Runtime error on line 5: {}", message)), "{} {}: {}", backend, expr, stderr);
+        }
+    }
+
+    //The optimizer keeps the checks
+    if cfg!(feature = "llvm") {
+        let (status, _, stderr) = runProgramFull("overflowO2", overflow, RUN, &["--checked-arith", "-O2"], "");
+        assert_eq!(status, 1);
+        assert!(stderr.contains("Runtime error on line 6: Integer overflow in 2147483647 + 1"), "{}", stderr);
+    }
+}
+
+#[test]
+fn badInputNamesTheBuiltin() {
+    let source = "program In is\nvariable i : integer;\nvariable f : float;\nvariable b : bool;\nbegin\n\
+        i := getinteger();\nputinteger(i);\nf := getfloat();\nputfloat(f);\nb := getbool();\nputbool(b);\nend program.\n";
+    //The compiled program tells the runtime the line of each builtin call, so both give the same message
+    for backend in backends() {
+        for (input, message) in [
+            ("x\n", "Runtime error on line 6: getinteger: 'x' is not an integer"),
+            ("4\n1.5.2\n", "Runtime error on line 8: getfloat: '1.5.2' is not a float"),
+            ("4\n1.5\nyes\n", "Runtime error on line 10: getbool: 'yes' is not a bool"),
+            ("4\n", "Runtime error on line 8: getfloat: there is no more input"),
+        ] {
+            let (status, _, stderr) = runProgramFull("badin", source, backend, &[], input);
+            assert_eq!(status, 1, "{} {:?}", backend, input);
+            assert!(stderr.contains(message), "{} {:?}: {}", backend, input, stderr);
+        }
+
+        //The exit code of a runtime error can be picked
+        let (status, _, _) = runProgramFull("badcode", source, backend, &["--error-exit-code=42"], "x\n");
+        assert_eq!(status, 42);
+
+        //Reprompting says what was wrong and reads the next line, default gives the zero value
+        let (status, printed, stderr) = runProgramFull("reprompt", source, backend, &["--bad-input", "reprompt"], "x\n 7 \nnope\n2.5\ntrue\n");
+        assert_eq!((status, printed), (0, vec!["7".to_string(), "2.5".to_string(), "true".to_string()]));
+        assert!(stderr.contains("getinteger: 'x' is not an integer, enter it again"), "{}: {}", backend, stderr);
+        assert!(stderr.contains("getfloat: 'nope' is not a float, enter it again"), "{}", stderr);
+        let (status, printed, _) = runProgramFull("default", source, backend, &["--bad-input=default"], "x\n");
+        assert_eq!((status, printed), (0, vec!["0".to_string(), "0".to_string(), "false".to_string()]));
+    }
+}
+
+#[test]
+fn theMainProgramReturnsItsStatus() {
+    let source = "program Status is\nvariable i : integer;\nbegin\ni := 7;\nputinteger(i);\nreturn i * 3;\nend program.\n";
+    let boolSource = "program Status is\nbegin\nreturn true;\nend program.\n";
+    //A wide status is cut to the 32 bits of an exit code, 4294967298 is 2
+    let wideSource = "program Status is\nvariable i : integer;\nbegin\ni := 4294967298;\nreturn i;\nend program.\n";
+    for backend in backends() {
+        let (status, printed) = runProgram("status", source, backend);
+        assert_eq!((status, printed), (21, vec!["7".to_string()]), "{}", backend);
+        assert_eq!(runProgram("boolstatus", boolSource, backend).0, 1, "{}", backend);
+        assert_eq!(runProgramWith("widestatus", wideSource, backend, &["--wide-numbers"]).0, 2, "{}", backend);
+    }
+}
+
+#[test]
+fn floatsPrintTheSameWayEverywhere() {
+    let source = "program Floats is\nvariable f : float;\nbegin\n\
+        putfloat(1.0e20);\nputfloat(0.00001);\nputfloat(0.000001);\nputfloat(0.1);\nputfloat(0.0 - 2.5);\nputfloat(16777216.0);\n\
+        putstring(floattostring(1.5e-7));\nf := log(0.0);\nputfloat(f);\nend program.\n";
+    let expected = ["1e20", "0.00001", "1e-6", "0.1", "-2.5", "16777216", "1.5e-7", "-inf"];
+    let wideSource = "program Floats is\nbegin\nputfloat(1.0e300);\nputfloat(0.1 + 0.2);\nend program.\n";
+    for backend in backends() {
+        let (status, printed) = runProgram("floats", source, backend);
+        assert_eq!((status, printed), (0, expected.iter().map(|line| line.to_string()).collect::<Vec<String>>()), "{}", backend);
+        let (status, printed) = runProgramWith("widefloats", wideSource, backend, &["--wide-numbers"]);
+        assert_eq!((status, printed), (0, vec!["1e300".to_string(), "0.30000000000000004".to_string()]), "{}", backend);
+    }
+}
+
+#[test]
+fn bufferedOutputIsWrittenBeforeItEnds() {
+    //What was printed comes out before a read, an exit and a runtime error
+    let source = "program Flush is\nvariable i : integer;\nbegin\nputstring(\"first\");\ni := getinteger();\n\
+        putinteger(i);\nif (i > 5) then\nexit(3);\nend if;\ni := 1 / 0;\nend program.\n";
+    //More than the 64 KiB buffer, then main returns and the generated code calls rt_flush
+    let manyLines = "program Many is\nvariable i : integer;\nbegin\nfor (i := 0; i < 20000)\nputinteger(i);\ni := i + 1;\nend for;\nputstring(\"last\");\nend program.\n";
+    for backend in backends() {
+        let (status, printed, _) = runProgramFull("flushexit", source, backend, &[], "9\n");
+        assert_eq!((status, printed), (3, vec!["first".to_string(), "9".to_string()]), "{}", backend);
+        let (status, printed, stderr) = runProgramFull("flusherror", source, backend, &["--checked-arith"], "2\n");
+        assert_eq!((status, printed), (1, vec!["first".to_string(), "2".to_string()]), "{}", backend);
+        assert!(stderr.contains("Integer division by zero in 1 / 0"), "{}: {}", backend, stderr);
+
+        let (status, printed) = runProgram("flushend", manyLines, backend);
+        assert_eq!(status, 0, "{}", backend);
+        assert_eq!(printed.len(), 20001, "{}", backend);
+        assert_eq!((printed[19999].as_str(), printed[20000].as_str()), ("19999", "last"), "{}", backend);
+    }
+}
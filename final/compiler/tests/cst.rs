@@ -0,0 +1,70 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for the lossless concrete syntax tree
+
+//package imports
+use {
+    compiler::models::cst::{Cst, NodeKind},
+    std::{fs, path::{Path, PathBuf}},
+};
+
+//Every file under a directory
+fn filesUnder(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(filesUnder(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    return files;
+}
+
+#[test]
+fn roundTripsEveryTestProgram() {
+    let files = filesUnder(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/testPgms")));
+    assert!(files.len() > 0);
+    for path in files {
+        let source = fs::read_to_string(&path).unwrap();
+        let cst = Cst::parse(&path.display().to_string(), &source);
+        assert!(cst.text() == source, "The tree of {} doesn't give back the file", path.display());
+    }
+}
+
+#[test]
+fn keepsTriviaInTheTree() {
+    let source = "/* header */\nPROGRAM Test IS\r\n\tvariable X : integer; // trailing\nbegin\n  X := 1;\nEND PROGRAM.\n";
+    let cst = Cst::parse("test.src", source);
+    assert_eq!(cst.text(), source);
+
+    let comments: Vec<String> = cst.comments().iter().map(|comment| comment.text.clone()).collect();
+    assert_eq!(comments, vec!["/* header */".to_string(), "// trailing".to_string()]);
+
+    //The original case is kept in the tree even though the lexer lowercases the tokens
+    let header = cst.root.find(&NodeKind::ProgramHeader)[0];
+    assert_eq!(header.text().trim(), "PROGRAM Test IS");
+    assert_eq!(cst.root.find(&NodeKind::Declaration).len(), 1);
+    assert_eq!(cst.root.find(&NodeKind::Assignment)[0].text().trim(), "X := 1;");
+}
+
+#[test]
+fn buildsProceduresAndBlocks() {
+    let source = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/testPgms/correct/test1.src")).unwrap();
+    let cst = Cst::parse("test1.src", &source);
+    let procedures = cst.root.find(&NodeKind::Procedure);
+    assert_eq!(procedures.len(), 2);
+    assert!(procedures[0].find(&NodeKind::ProcedureHeader)[0].text().contains("if_proc : integer()"));
+    assert_eq!(cst.root.find(&NodeKind::If).len(), 1);
+    assert_eq!(cst.root.find(&NodeKind::For).len(), 1);
+    assert_eq!(cst.root.find(&NodeKind::Error).len(), 0);
+}
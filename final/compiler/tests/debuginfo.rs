@@ -0,0 +1,69 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+//The debug info is part of the LLVM module
+#![cfg(feature = "llvm")]
+
+//Tests for the DWARF debug info written with -g
+
+//package imports
+use {
+    compiler::models::{compiler::Compiler, lexer::Lexer, parser::{Parser, Stmt}, typechecker::{SymbolTable, SyntaxChecker}},
+    inkwell::{context::Context, values::PointerValue},
+    std::{collections::HashMap, env, fs, process::Command},
+};
+
+const PROGRAM: &str = "program Dbg is\nvariable total : integer;\n\
+    procedure twice : integer(variable x : integer)\nvariable y : integer;\nbegin\ny := x * 2;\nreturn y;\nend procedure;\n\
+    begin\ntotal := twice(21);\nputinteger(total);\nend program.\n";
+
+fn parse(source: &str) -> Stmt {
+    let mut myLexer = Lexer::newFromString("dbg.src", source.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    let programAst = myParser.startParse().unwrap().1.unwrap();
+    let mut globalTable = SymbolTable::new();
+    let mut myChecker = SyntaxChecker::new(programAst.clone(), &mut globalTable, "Main".to_string());
+    assert!(myChecker.checkProgram());
+    return programAst;
+}
+
+#[test]
+fn describesTheProgramProceduresAndLocals() {
+    let mut globalTable: HashMap<String, PointerValue> = HashMap::new();
+    let context = Context::create();
+    let mut myGen = Compiler::new(parse(PROGRAM), &context, &mut globalTable, "test".to_string(), "Program".to_string());
+    myGen.enableDebugInfo("dbg.src");
+    let module = myGen.compileProgram().unwrap().clone();
+    assert!(module.verify().is_ok());
+    let ir = module.print_to_string().to_string();
+
+    assert!(ir.contains("distinct !DICompileUnit("), "{}", ir);
+    assert!(ir.contains("!DIFile(filename: \"dbg.src\""), "{}", ir);
+    assert!(ir.contains("!DISubprogram(name: \"main\""), "{}", ir);
+    assert!(ir.contains("!DISubprogram(name: \"twice\""), "{}", ir);
+    //total is a program variable, so it is a global and not a local
+    for local in ["x", "y"] {
+        assert!(ir.contains(&format!("!DILocalVariable(name: \"{}\"", local)), "No debug info for {}:\n{}", local, ir);
+    }
+    //The statements point at their .src lines
+    assert!(ir.contains("!DILocation(line: 6,"), "{}", ir);
+    assert!(ir.contains("\"Debug Info Version\""), "{}", ir);
+}
+
+#[test]
+fn runWorksWithDebugInfo() {
+    let dir = env::temp_dir().join(format!("debuginfo-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("dbg.src");
+    fs::write(&input, PROGRAM).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).args(["run", "-g"]).arg(&input).output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n");
+}
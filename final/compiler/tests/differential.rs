@@ -0,0 +1,55 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+//The programs are compared against the run command, it only exists in the LLVM backend
+#![cfg(feature = "llvm")]
+
+//Runs the test programs on the interpreter and on compiled code and checks that they behave the same
+
+//package imports
+use {
+    std::{env, fs, io::Write, path::{Path, PathBuf}, process::{Command, Stdio}, thread},
+};
+
+//The input given to the programs that read from stdin, the others get nothing
+const INPUTS: [(&str, &str); 2] = [
+    ("recursiveFib.src", "5\n"),
+    ("test_heap.src", "5\nhello\n"),
+];
+
+//Runs a source file with the backend and the input on stdin, returns its exit code and everything it printed
+fn runWith(backend: &str, srcPath: &Path, input: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_compiler")).arg(backend).arg(srcPath)
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    return (output.status.code().unwrap_or(-1), String::from_utf8_lossy(&output.stdout).to_string());
+}
+
+#[test]
+fn backendsAgreeOnTheCorrectTestPrograms() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testPgms").join("correct");
+    let mut programs: Vec<PathBuf> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "src")).collect();
+    programs.sort();
+    assert!(programs.len() >= 10, "Only found {} programs in {}", programs.len(), dir.display());
+
+    //Each program runs on its own thread, the interpreter takes a while on the recursive ones
+    let handles: Vec<_> = programs.into_iter().map(|srcPath| thread::spawn(move || {
+        let fileName = srcPath.file_name().unwrap().to_string_lossy().to_string();
+        let input = INPUTS.iter().find(|(name, _)| *name == fileName).map_or("", |(_, input)| *input);
+        let compiled = runWith("run", &srcPath, input);
+        let interpreted = runWith("--backend=interp", &srcPath, input);
+        return (fileName, compiled, interpreted);
+    })).collect();
+    for handle in handles {
+        let (fileName, compiled, interpreted) = handle.join().unwrap();
+        assert_eq!(compiled.0, 0, "{} failed when compiled", fileName);
+        assert_eq!(interpreted, compiled, "{} behaves differently on the interpreter", fileName);
+    }
+}
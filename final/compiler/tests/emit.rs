@@ -0,0 +1,172 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for the JSON written by --emit=tokens-json and --emit=ast-json
+
+//package imports
+use {
+    compiler::models::{emit::{astDot, astJson, tokensJson, JSON_SCHEMA_VERSION}, lexer::Lexer, options::{CompilerOptions, EmitKind}, parser::Parser},
+    serde_json::Value,
+    std::{env, fs, process::Command},
+};
+
+const SOURCE: &str = "program Emit is\nvariable x : integer;\nbegin\n  x := x + 12;\nend program.\n";
+
+#[test]
+fn tokenSpansPointIntoTheSource() {
+    let mut myLexer = Lexer::newFromString("emit.src", SOURCE.to_string());
+    myLexer.scanThrough();
+    let json: Value = serde_json::from_str(&tokensJson("emit.src", &myLexer.tokenList).unwrap()).unwrap();
+    assert_eq!(json["schemaVersion"], JSON_SCHEMA_VERSION);
+    assert_eq!(json["kind"], "tokens");
+
+    let chars: Vec<char> = SOURCE.chars().collect();
+    let tokens = json["tokens"].as_array().unwrap();
+    let number = tokens.iter().find(|token| token["type"] == "INT" && token["group"] == "CONSTANT").unwrap();
+    assert_eq!(number["text"], "12");
+    assert_eq!(number["line"], 4);
+    assert_eq!(number["col"], 11);
+    let (start, end) = (number["start"].as_u64().unwrap() as usize, number["end"].as_u64().unwrap() as usize);
+    assert_eq!(chars[start..end].iter().collect::<String>(), "12");
+    assert_eq!(tokens.last().unwrap()["type"], "EOF");
+}
+
+#[test]
+fn astUsesKindAndData() {
+    let mut myLexer = Lexer::newFromString("emit.src", SOURCE.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    let programAst = myParser.startParse().unwrap().1.unwrap();
+    let json: Value = serde_json::from_str(&astJson("emit.src", &programAst).unwrap()).unwrap();
+    assert_eq!(json["kind"], "ast");
+
+    let program = &json["ast"];
+    assert_eq!(program["kind"], "Program");
+    assert_eq!(program["data"][0], "emit");
+    let declaration = &program["data"][1]["data"][0][0];
+    assert_eq!(declaration["kind"], "GlobVarDecl");
+    assert_eq!(declaration["data"][1]["kind"], "Int");
+    let assignment = &program["data"][2]["data"][0][0];
+    assert_eq!(assignment["kind"], "Assign");
+    assert_eq!(assignment["data"][1]["kind"], "ArthOp");
+    assert_eq!(assignment["data"][1]["data"][1], "Add");
+    assert_eq!(assignment["data"][2], 4);
+}
+
+//The spans in the data of a node
+fn spansOf(node: &Value) -> Vec<&Value> {
+    return node["data"].as_array().map(|data| data.iter().filter(|value| value.get("startLine").is_some()).collect()).unwrap_or_default();
+}
+
+//Checks that every statement and expression under a node has a span, the types are the only other nodes with a kind
+fn assertEverySpan(node: &Value) {
+    match node {
+        Value::Object(fields) => {
+            let isType = ["Int", "Float", "Bool", "Str", "IntArray"].iter().any(|kind| node["kind"] == *kind);
+            if fields.contains_key("kind") && !isType {
+                assert!(spansOf(node).len() > 0, "No span in {}", node);
+            }
+            fields.values().for_each(assertEverySpan);
+        }
+        Value::Array(values) => values.iter().for_each(assertEverySpan),
+        _ => {}
+    }
+}
+
+#[test]
+fn everyNodeHasASpan() {
+    let source = "program Spans is\nvariable a : integer[3];\nprocedure f : integer(variable n : integer)\nbegin\nreturn n;\nend procedure;\n\
+        begin\n  a[1] := (f(2) + 1) * 3;\nif (a[1] > 2) then a[0] := 1; else return; end if;\nfor (a[2] := 0; a[2] < 3) a[2] := a[2] + 1; end for;\nend program.\n";
+    let mut myLexer = Lexer::newFromString("spans.src", source.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    let programAst = myParser.startParse().unwrap().1.unwrap();
+    let json: Value = serde_json::from_str(&astJson("spans.src", &programAst).unwrap()).unwrap();
+    assertEverySpan(&json["ast"]);
+
+    //The assignment goes from its name to its ';', the parenthesized sum includes its parentheses
+    let assignment = &json["ast"]["data"][2]["data"][0][0];
+    assert_eq!(*spansOf(assignment)[0], serde_json::json!({ "startLine": 8, "startCol": 2, "endLine": 8, "endCol": 25 }));
+    let sum = &assignment["data"][1]["data"][0];
+    assert_eq!(*spansOf(sum)[0], serde_json::json!({ "startLine": 8, "startCol": 10, "endLine": 8, "endCol": 20 }));
+
+    //A procedure has its own span and the span of its name
+    let procedure = &json["ast"]["data"][1]["data"][0][1];
+    assert_eq!(procedure["kind"], "ProcDecl");
+    let spans = spansOf(procedure);
+    assert_eq!((spans[0]["startLine"].as_u64(), spans[0]["endLine"].as_u64()), (Some(3), Some(6)));
+    assert_eq!(*spans[1], serde_json::json!({ "startLine": 3, "startCol": 10, "endLine": 3, "endCol": 11 }));
+}
+
+#[test]
+fn astDotLabelsTheBranches() {
+    let source = "program Dot is\nvariable x : integer;\nbegin\nif (x < 2) then x := 1; else x := \"a\"; end if;\nend program.\n";
+    let mut myLexer = Lexer::newFromString("dot.src", source.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    let programAst = myParser.startParse().unwrap().1.unwrap();
+    let dot = astDot("dot.src", &programAst);
+    assert!(dot.starts_with("digraph \"dot.src\" {"));
+    assert!(dot.trim_end().ends_with('}'));
+    for label in ["[label=\"cond\"]", "[label=\"then\"]", "[label=\"else\"]", "label=\"If\\nline 4\"", "label=\"<\""] {
+        assert!(dot.contains(label), "Missing {} in\n{}", label, dot);
+    }
+    //The quotes of the string literal are escaped and the lexer's padding is left off
+    assert!(dot.contains("label=\"\\\"a\\\"\""), "{}", dot);
+}
+
+#[test]
+fn emitWritesTheOutputFile() {
+    let dir = env::temp_dir().join(format!("emit-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("prog.src");
+    fs::write(&input, SOURCE).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("--emit=ast-json").arg(&input).output().unwrap().status;
+    assert!(status.success());
+    let json: Value = serde_json::from_str(&fs::read_to_string(dir.join("prog.ast.json")).unwrap()).unwrap();
+    assert_eq!(json["ast"]["kind"], "Program");
+
+    let output = dir.join("tokens.json");
+    let status = Command::new(env!("CARGO_BIN_EXE_compiler")).args(["--emit", "tokens-json", "-o"]).arg(&output).arg(&input).output().unwrap().status;
+    assert!(status.success());
+    let json: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(json["kind"], "tokens");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn anExplicitOutputIsAlwaysUsed() {
+    let parse = |args: &[&str]| CompilerOptions::parseArgs(args.iter().map(|arg| arg.to_string()).collect()).unwrap();
+    let options = parse(&["--emit=ast-json", "dir/prog.src"]);
+    assert_eq!(options.emitPath(&EmitKind::AstJson), "dir/prog.ast.json");
+    assert_eq!(options.executablePath(), "a.out");
+    //-o a.out is the default executable name, but it was asked for
+    let options = parse(&["--emit=ast-json", "-o", "a.out", "dir/prog.src"]);
+    assert_eq!(options.emitPath(&EmitKind::AstJson), "a.out");
+    let options = parse(&["-o", "prog", "dir/prog.src"]);
+    assert_eq!(options.executablePath(), "prog");
+}
+
+#[test]
+fn cfgDotNeedsTheLlvmBackend() {
+    let args = ["--backend=interp", "--emit=cfg-dot", "prog.src"].iter().map(|arg| arg.to_string()).collect();
+    let errMsg = CompilerOptions::parseArgs(args).unwrap_err();
+    assert!(errMsg.starts_with("--emit=cfg-dot shows the generated LLVM code and can't be used with --backend=interp"), "{}", errMsg);
+
+    let dir = env::temp_dir().join(format!("cfg-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("prog.src");
+    fs::write(&input, SOURCE).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).args(["--emit=cfg-dot", "--backend=interp"]).arg(&input).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("can't be used with --backend=interp"));
+    assert!(!dir.join("prog.cfg.dot").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,156 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for external procedure declarations
+
+//package imports
+use {
+    compiler::models::{formatter::formatSource, lexer::Lexer, parser::{Parser, Stmt, VarType}, typechecker::{SymbolTable, SyntaxChecker}},
+    std::{env, fs, path::{Path, PathBuf}, process::Command},
+};
+
+const PROGRAM: &str = "program Ext is\nexternal procedure sqrtf : float(variable x : float);\nexternal procedure puts : integer(variable s : string);\nvariable y : float;\nbegin\ny := sqrtf(2.0);\nputfloat(y);\nend program.\n";
+
+fn parse(source: &str) -> Stmt {
+    let mut myLexer = Lexer::newFromString("ext.src", source.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    return myParser.startParse().unwrap().1.unwrap();
+}
+
+fn check(source: &str) -> bool {
+    let mut globalTable = SymbolTable::new();
+    let mut myChecker = SyntaxChecker::new(parse(source), &mut globalTable, "Main".to_string());
+    return myChecker.checkProgram();
+}
+
+#[test]
+fn parsesIntoADeclarationWithoutABody() {
+    let Stmt::Program(_, header, _, _, ..) = parse(PROGRAM) else { panic!("Not a program") };
+    let Stmt::Block(decls, _, ..) = *header else { panic!("Header is not a block") };
+    match &decls[0] {
+        Stmt::ExternProcDecl(retType, name, params, lineNum, ..) => {
+            assert_eq!(*retType, VarType::Float);
+            assert_eq!(name, "sqrtf");
+            assert_eq!(lineNum, "2");
+            let Stmt::Block(params, _, ..) = &**params else { panic!("Params are not a block") };
+            assert!(matches!(&params[0], Stmt::VarDecl(name, VarType::Float, _, ..) if name == "x"));
+        }
+        other => panic!("Expected an external procedure, found {:?}", other),
+    }
+    assert!(matches!(decls[2], Stmt::GlobVarDecl(..)));
+}
+
+#[test]
+fn callsAreCheckedLikeProcedures() {
+    assert!(check(PROGRAM));
+    //Wrong argument type
+    assert!(!check(&PROGRAM.replace("putfloat(y)", "putfloat(sqrtf(\"two\"))")));
+    //C can't return a string or take an array
+    assert!(!check("program Ext is\nexternal procedure getenv : string(variable name : string);\nbegin\nend program.\n"));
+    assert!(!check("program Ext is\nexternal procedure sum : integer(variable values : integer[4]);\nbegin\nend program.\n"));
+}
+
+#[test]
+fn formatterKeepsTheDeclaration() {
+    let formatted = formatSource("ext.src", PROGRAM).unwrap();
+    assert!(formatted.contains("    external procedure sqrtf : float(variable x : float);\n"), "{}", formatted);
+    assert_eq!(formatSource("ext.src", &formatted).unwrap(), formatted);
+}
+
+#[test]
+fn interpreterCantCallC() {
+    let dir = env::temp_dir().join(format!("external-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("ext.src");
+    fs::write(&input, PROGRAM).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("--backend=interp").arg(&input).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("External procedure sqrtf can only be called from compiled code"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+//The runtime archive cargo built for the tests, copied where -L can find it under its own name
+#[cfg(feature = "llvm")]
+fn runtimeLibDir(dir: &Path) -> PathBuf {
+    let depsDir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let archive = fs::read_dir(&depsDir).unwrap().filter_map(|entry| entry.ok()).map(|entry| entry.path())
+        .find(|path| {
+            let fileName = path.file_name().unwrap().to_string_lossy().to_string();
+            return fileName.starts_with("libfuncLib") && fileName.ends_with(".a");
+        })
+        .unwrap_or_else(|| panic!("No libfuncLib archive in {}", depsDir.display()));
+    let runtimeDir = dir.join("runtime");
+    fs::create_dir_all(&runtimeDir).unwrap();
+    fs::copy(&archive, runtimeDir.join("libfuncLib.a")).unwrap();
+    return runtimeDir;
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn runFindsTheCLibraries() {
+    let dir = env::temp_dir().join(format!("external-run-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("ext.src");
+    fs::write(&input, PROGRAM).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").arg(&input).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1.4142135\n");
+
+    //A name nothing has is an error before the program runs
+    fs::write(&input, "program Ext is\nexternal procedure noSuchFunction : integer();\nvariable i : integer;\nbegin\ni := noSuchFunction();\nend program.\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").arg(&input).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Function 'nosuchfunction' is declared but wasn't found"), "{}", String::from_utf8_lossy(&output.stderr));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn cGetsTheBytesOfAString() {
+    let dir = env::temp_dir().join(format!("external-string-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("str.src");
+    //C reads a string as a char*, up to its first NUL
+    fs::write(&input, "program Str is\nexternal procedure strlen : integer(variable s : string);\nvariable i : integer;\nbegin\n\
+        i := strlen(concat(\"hel\", \"lo\"));\nputinteger(i);\ni := strlen(\"a\\u{0}b\");\nputinteger(i);\nend program.\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").arg(&input).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "5\n1\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn runLoadsTheLibrariesGivenWithL() {
+    let dir = env::temp_dir().join(format!("external-lib-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("triple.c"), "int triple(int x) { return 3 * x; }\n").unwrap();
+    assert!(Command::new("cc").args(["-shared", "-fPIC", "-o"]).arg(dir.join("libtriple.so")).arg(dir.join("triple.c")).status().unwrap().success());
+    let input = dir.join("lib.src");
+    fs::write(&input, "program Lib is\nexternal procedure triple : integer(variable x : integer);\nvariable i : integer;\nbegin\ni := triple(14);\nputinteger(i);\nend program.\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").arg("-L").arg(&dir).arg("-ltriple").arg(&input).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn linkedExecutablesCallC() {
+    let dir = env::temp_dir().join(format!("external-build-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("ext.src");
+    fs::write(&input, PROGRAM).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("build").arg("-L").arg(runtimeLibDir(&dir)).arg("-o").arg(dir.join("ext")).arg(&input).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(dir.join("ext")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1.4142135\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,51 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Checks that the crate builds with each backend on its own, so a missing #[cfg] is caught
+//The llvm12 and llvm13 builds need their own LLVM installed, docs/building.md has the full matrix
+//Each build is a full cargo check, so the test only runs when asked for: cargo test --test features -- --ignored
+
+//package imports
+use {
+    std::{env, path::Path, process::Command},
+};
+
+//The LLVM version the tests were built with, the only one that is sure to be installed
+fn enabledLlvm() -> Option<&'static str> {
+    if cfg!(feature = "llvm12") {
+        return Some("llvm12");
+    }
+    if cfg!(feature = "llvm13") {
+        return Some("llvm13");
+    }
+    if cfg!(feature = "llvm14") {
+        return Some("llvm14");
+    }
+    return None;
+}
+
+#[test]
+#[ignore = "runs a cargo check for each backend, use --ignored"]
+fn everyBackendBuildsOnItsOwn() {
+    let manifestDir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut combinations = vec!["interp".to_string(), "".to_string()];
+    if let Some(llvm) = enabledLlvm() {
+        combinations.push(llvm.to_string());
+        combinations.push(format!("interp,{}", llvm));
+    }
+    for features in combinations {
+        //Its own target directory, the one running the tests is locked, kept in the temp dir so the checkout stays clean
+        let output = Command::new(env::var("CARGO").unwrap_or("cargo".to_string()))
+            .args(["check", "--quiet", "--all-targets", "--no-default-features", "--features", &features, "--manifest-path"])
+            .arg(manifestDir.join("Cargo.toml"))
+            .env("CARGO_TARGET_DIR", env::temp_dir().join("compiler-feature-check"))
+            .output().unwrap();
+        assert!(output.status.success(), "The build with features '{}' failed:\n{}", features, String::from_utf8_lossy(&output.stderr));
+    }
+}
@@ -0,0 +1,94 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for the source formatter behind 'compiler fmt'
+
+//package imports
+use {
+    compiler::models::formatter::formatSource,
+    std::{env, fs, process::Command},
+};
+
+#[test]
+fn formatsTheCanonicalLayout() {
+    let source = "PROGRAM Edge IS
+variable x : integer; variable longName : float;   /* two on a line */
+begin
+x := 1 +
+  // split here
+  2;
+IF (x < 2) THEN x := x - 1; ELSE x := 0; END IF; // tail
+end program.
+";
+    let expected = "program Edge is
+    variable x        : integer;
+    variable longName : float; /* two on a line */
+begin
+    x := 1 +
+        // split here
+        2;
+    if (x < 2) then
+        x := x - 1;
+    else
+        x := 0;
+    end if; // tail
+end program.
+";
+    assert_eq!(formatSource("edge.src", source).unwrap(), expected);
+}
+
+#[test]
+fn formattingIsIdempotentOnTestPrograms() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/testPgms/correct");
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map(|ext| ext != "src").unwrap_or(true) {
+            continue;
+        }
+        let source = fs::read_to_string(&path).unwrap();
+        let fileName = path.display().to_string();
+        let formatted = formatSource(&fileName, &source).unwrap_or_else(|errMsg| panic!("{}", errMsg));
+        let again = formatSource(&fileName, &formatted).unwrap_or_else(|errMsg| panic!("{}", errMsg));
+        assert_eq!(formatted, again, "Formatting {} twice changed it", fileName);
+    }
+}
+
+#[test]
+fn refusesProgramsThatDontParse() {
+    let source = "program broken is\nvariable x : integer;\nbegin\nx := -(x);\nend program.\n";
+    assert!(formatSource("broken.src", source).is_err());
+}
+
+#[test]
+fn namesKeepTheirSpelling() {
+    let source = "program Mixed is\nvariable totalCount : integer;\nbegin\ntotalCount := getInteger();\nputInteger(totalCount);\nend program.\n";
+    let formatted = formatSource("mixed.src", source).unwrap();
+    assert!(formatted.contains("    totalCount := getInteger();\n    putInteger(totalCount);\n"), "{}", formatted);
+}
+
+#[test]
+fn checkExitsWithOneWhenTheFileWouldChange() {
+    let dir = env::temp_dir().join(format!("fmt-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("check.src");
+    let source = "PROGRAM Check IS\nbegin\nend program.\n";
+    fs::write(&path, source).unwrap();
+    let check = || Command::new(env!("CARGO_BIN_EXE_compiler")).arg("fmt").arg("--check").arg(&path).output().unwrap();
+
+    let output = check();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not formatted, first difference on line 1:"));
+    //--check doesn't write the file
+    assert_eq!(fs::read_to_string(&path).unwrap(), source);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("fmt").arg(&path).output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(check().status.code(), Some(0));
+    fs::remove_dir_all(&dir).unwrap();
+}
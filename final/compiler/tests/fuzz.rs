@@ -0,0 +1,127 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Runs the fuzz targets' checks on pseudo random inputs, so a panic the fuzzer found once stays fixed
+//The fuzz targets themselves are in fuzz/, run them with cargo fuzz
+
+//package imports
+use {
+    compiler::models::{frontend::Stage, fuzzing::{fuzzStage, generateProgram}},
+    std::fs,
+};
+
+//How many inputs each test runs
+const RUNS: usize = 500;
+
+//A small xorshift generator, so every run tries the same inputs
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Random {
+        return Random { state: seed | 1 };
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+
+    fn bytes(&mut self, maxLen: usize) -> Vec<u8> {
+        let len = (self.next() as usize) % maxLen;
+        return (0..len).map(|_| self.next() as u8).collect();
+    }
+}
+
+//Inputs that once panicked or hung
+const REGRESSIONS: &[&str] = &[
+    "",
+    "program",
+    "program p is begin end program",
+    "program p is begin x := 1 /* never closed",
+    "program p is begin x := \"never closed",
+    "program p is begin x := 1 =",
+    "program p is begin x := 1 !",
+    "-",
+    "program p is begin x := 1 <",
+    "end",
+    "(",
+    "variable",
+    "program p is variable a : integer[99999999999999999999]; begin end program.",
+    "program p is begin x := 99999999999999999999; end program.",
+];
+
+#[test]
+fn regressionsDontPanic() {
+    for source in REGRESSIONS {
+        let mut data = vec![0u8];
+        data.extend(source.as_bytes());
+        for stage in [Stage::Lex, Stage::Parse, Stage::Check] {
+            fuzzStage(&data, stage);
+        }
+    }
+}
+
+#[test]
+fn randomBytesDontPanic() {
+    let mut random = Random::new(0x5EED);
+    for _ in 0..RUNS {
+        let mut data = vec![0u8];
+        data.extend(random.bytes(200));
+        fuzzStage(&data, Stage::Check);
+    }
+}
+
+#[test]
+fn generatedProgramsDontPanic() {
+    let mut random = Random::new(0xFACE);
+    for _ in 0..RUNS {
+        let mut data = vec![1u8];
+        data.extend(random.bytes(400));
+        fuzzStage(&data, Stage::Check);
+    }
+}
+
+#[test]
+fn mutatedTestProgramsDontPanic() {
+    let mut random = Random::new(0xBEEF);
+    let mut sources: Vec<Vec<u8>> = Vec::new();
+    for entry in fs::read_dir("testPgms/correct").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map(|ext| ext == "src").unwrap_or(false) {
+            sources.push(fs::read(&path).unwrap());
+        }
+    }
+    for i in 0..RUNS {
+        let mut data = vec![0u8];
+        data.extend(&sources[i % sources.len()]);
+        //Cuts the program off somewhere, then changes a few bytes
+        let cut = 1 + (random.next() as usize) % (data.len() - 1);
+        data.truncate(cut);
+        for _ in 0..(random.next() % 4) {
+            let at = 1 + (random.next() as usize) % data.len().max(2).saturating_sub(1);
+            if at < data.len() {
+                data[at] = random.next() as u8;
+            }
+        }
+        fuzzStage(&data, Stage::Check);
+    }
+}
+
+#[test]
+fn generatedProgramsAreMostlyValid() {
+    //Without stray tokens a generated program follows the grammar, so an empty input (all choices 0) has to parse
+    let program = generateProgram(&[]);
+    let mut data = vec![1u8];
+    let frontEnd = fuzzStage(&data, Stage::Parse);
+    assert!(frontEnd.ast.is_some(), "{}\n{:?}", program, frontEnd.errors);
+}
@@ -0,0 +1,296 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//The grammar conformance suite, each production in docs/grammar.md has a program the parser accepts and one it rejects
+
+//package imports
+use {
+    compiler::models::{lexer::Lexer, parser::Parser},
+    std::fs,
+};
+
+//A production of the grammar, a program that uses it and a program that gets it wrong
+struct Case {
+    production: &'static str,       //The name of the production in docs/grammar.md
+    accepted: &'static str,         //A program the parser has to accept
+    rejected: &'static str,         //A program the parser has to reject
+}
+
+const CASES: &[Case] = &[
+    //Units
+    Case {
+        production: "unit",
+        accepted: "module m is end module.",
+        rejected: "procedure p is begin end program.",
+    },
+    Case {
+        production: "program",
+        accepted: "program p is variable x : integer; begin x := 1; end program\n",
+        rejected: "program is begin end program.",
+    },
+    Case {
+        production: "module",
+        accepted: "module m is variable x : integer; end module.",
+        rejected: "module m is variable x : integer; end module\n",
+    },
+
+    //Declarations
+    Case {
+        production: "declarations",
+        accepted: "program p is variable x : integer; variable y : float; procedure f : bool() begin return true; end procedure; begin end program.",
+        rejected: "program p is x := 1; begin end program.",
+    },
+    Case {
+        production: "declaration",
+        accepted: "program p is import m; global variable g : integer; external procedure e : integer(); begin end program.",
+        rejected: "program p is if (true) then end if; begin end program.",
+    },
+    Case {
+        production: "import",
+        accepted: "program p is import m; import other; begin end program.",
+        rejected: "program p is procedure f : integer() import m; begin return 1; end procedure; begin end program.",
+    },
+    Case {
+        production: "variable",
+        accepted: "program p is procedure f : integer() variable x : integer; begin return x; end procedure; begin end program.",
+        rejected: "program p is variable x : integer begin end program.",
+    },
+    Case {
+        production: "globalVariable",
+        accepted: "program p is procedure f : integer() global variable g : integer; begin return 1; end procedure; begin end program.",
+        rejected: "program p is global g : integer; begin end program.",
+    },
+    Case {
+        production: "variableSpec",
+        accepted: "program p is variable x_1 : string; begin end program.",
+        rejected: "program p is variable x integer; begin end program.",
+    },
+    Case {
+        production: "typeMark",
+        accepted: "program p is variable a : integer[3]; variable b : bool; begin end program.",
+        rejected: "program p is variable x : ; begin end program.",
+    },
+    Case {
+        production: "scalarType",
+        accepted: "program p is variable i : integer; variable f : float; variable b : bool; variable s : string; begin end program.",
+        rejected: "program p is variable c : char; begin end program.",
+    },
+    Case {
+        production: "arrayType",
+        accepted: "program p is variable a : integer[10]; begin a[0] := 1; end program.",
+        rejected: "program p is variable a : float[10]; begin end program.",
+    },
+    Case {
+        production: "procedure",
+        accepted: "program p is
+            procedure f : integer(variable x : integer)
+                variable y : integer;
+                procedure g : float() begin return 1.5; end procedure;
+            begin
+                y := x + 1;
+                return y;
+            end procedure;
+            begin end program.",
+        rejected: "program p is procedure f : integer() begin return 1; end procedure begin end program.",
+    },
+    Case {
+        production: "external",
+        accepted: "program p is external procedure puts : integer(variable s : string); begin end program.",
+        rejected: "program p is external procedure puts : integer(variable s : string) begin return 1; end procedure; begin end program.",
+    },
+    Case {
+        production: "parameters",
+        accepted: "program p is procedure f : integer(variable a : integer, variable b : integer[4], variable c : string) begin return a; end procedure; begin end program.",
+        rejected: "program p is procedure f : integer(variable a : integer,) begin return a; end procedure; begin end program.",
+    },
+
+    //Statements
+    Case {
+        production: "statements",
+        accepted: "program p is variable x : integer; begin x := 1; x := 2; putinteger(x); end program.",
+        rejected: "program p is begin variable x : integer; end program.",
+    },
+    Case {
+        production: "statement",
+        accepted: "program p is variable x : integer; begin if (true) then x := 1; end if; for (x := 0; x < 3) x := x + 1; end for; return; end program.",
+        rejected: "program p is begin then; end program.",
+    },
+    Case {
+        production: "assignment",
+        accepted: "program p is variable x : integer; begin x := 1 + 2; end program.",
+        rejected: "program p is variable x : integer; begin x := ; end program.",
+    },
+    Case {
+        production: "destination",
+        accepted: "program p is variable a : integer[4]; begin a[1 + 1] := 2; end program.",
+        rejected: "program p is variable a : integer[4]; begin a[1 := 2; end program.",
+    },
+    Case {
+        production: "ifStatement",
+        accepted: "program p is variable x : integer; begin if (x < 1) then x := 1; else x := 2; end if; if (true) then end if; end program.",
+        rejected: "program p is variable x : integer; begin if x < 1 then x := 1; end if; end program.",
+    },
+    Case {
+        production: "forStatement",
+        accepted: "program p is variable i : integer; begin for (i := 0; i < 10) putinteger(i); i := i + 1; end for; end program.",
+        rejected: "program p is variable i : integer; begin for (i < 10) i := i + 1; end for; end program.",
+    },
+    Case {
+        production: "returnStatement",
+        accepted: "program p is procedure f : integer() begin return (1 + 2); end procedure; procedure g : bool() begin return; end procedure; begin end program.",
+        rejected: "program p is procedure f : integer() begin return 1 end procedure; begin end program.",
+    },
+    Case {
+        production: "expressionStatement",
+        accepted: "program p is begin putinteger(1); f(); end program.",
+        rejected: "program p is begin putinteger(1) end program.",
+    },
+
+    //Expressions
+    Case {
+        production: "expression",
+        accepted: "program p is variable x : integer; begin x := 1 + 2 * (3 - x) / 4; end program.",
+        rejected: "program p is variable x : integer; begin x := 1 +; end program.",
+    },
+    Case {
+        production: "operator",
+        accepted: "program p is variable b : bool; begin b := 1 < 2 & 3 <= 4 | 5 > 6 & 7 >= 8 & 9 == 9 & 1 != 2 & 1 + 2 - 3 * 4 / 5 > 0; end program.",
+        rejected: "program p is variable x : integer; begin x := 1 % 2; end program.",
+    },
+    Case {
+        production: "operand",
+        accepted: "program p is variable x : integer; variable a : integer[2]; begin x := (x) + f(x) + a[0] + 1; end program.",
+        rejected: "program p is variable x : integer; begin x := ); end program.",
+    },
+    Case {
+        production: "name",
+        accepted: "program p is variable x : integer; variable a : integer[2]; begin x := a[x - 1] + x; end program.",
+        rejected: "program p is variable x : integer; variable a : integer[2]; begin x := a[]; end program.",
+    },
+    Case {
+        production: "procedureCall",
+        accepted: "program p is variable x : integer; begin x := f(1, g(2), h()); end program.",
+        rejected: "program p is variable x : integer; begin x := f(1; end program.",
+    },
+    Case {
+        production: "arguments",
+        accepted: "program p is variable x : integer; begin x := f(x + 1, (2), \"three\", 4.0); end program.",
+        rejected: "program p is variable x : integer; begin x := f(1 2); end program.",
+    },
+    Case {
+        production: "literal",
+        accepted: "program p is variable x : integer; variable y : float; variable s : string; variable b : bool;
+            begin x := 1; y := 2.5; s := \"str\"; b := true; b := false; end program.",
+        rejected: "program p is variable x : integer; begin x := begin; end program.",
+    },
+
+    //Tokens
+    Case {
+        production: "identifier",
+        accepted: "program Mixed_Case2 is variable my_var2 : integer; begin MY_VAR2 := 1; end program.",
+        rejected: "program p is variable 2x : integer; begin end program.",
+    },
+    Case {
+        production: "keyword",
+        accepted: "program p is variable iffy : integer; variable ends : integer; begin iffy := ends; end program.",
+        rejected: "program p is variable if : integer; begin end program.",
+    },
+    Case {
+        production: "integerLiteral",
+        accepted: "program p is variable x : integer; variable a : integer[0x10]; begin x := 42; x := -7; x := x - -3; x := 0xFF + 0b1010 + 1_000_000; x := -2147483648; end program.",
+        rejected: "program p is variable a : integer[-1]; begin end program.",
+    },
+    Case {
+        production: "floatLiteral",
+        accepted: "program p is variable y : float; begin y := 2.5; y := 3.; y := -0.25; y := 1_000.5; y := 2e3; end program.",
+        rejected: "program p is variable y : float; begin y := 2.5.1; end program.",
+    },
+    Case {
+        production: "exponent",
+        accepted: "program p is variable y : float; begin y := 1.5e-3; y := 2E+10; y := 3.e2; end program.",
+        rejected: "program p is variable y : float; begin y := 1e39; end program.",
+    },
+    Case {
+        production: "stringLiteral",
+        accepted: "program p is variable s : string; begin s := \"hello, world; := end program.\"; s := \"say \\\"hi\\\"\\n\"; end program.",
+        rejected: "program p is variable s : string; begin s := \"never closed; end program.",
+    },
+    Case {
+        production: "comment",
+        accepted: "program p is // a line comment
+            /* a /* nested */ block comment */
+            begin /* between */ end program.",
+        rejected: "program p is / / not a comment begin end program.",
+    },
+];
+
+//Lexes and parses a program, returns the first error if it isn't accepted
+fn parse(source: &str) -> Result<(), String> {
+    let mut lexer = Lexer::newFromString("grammar.src", source.to_string());
+    lexer.scanThrough();
+    let mut parser = Parser::new(&lexer);
+    match parser.startParse() {
+        Ok((_, Some(_))) => return Ok(()),
+        Ok((_, None)) => return Err("no AST".to_string()),
+        Err(reports) => return Err(reports.errors.join("\n")),
+    }
+}
+
+//The names of the productions in docs/grammar.md
+fn grammarProductions() -> Vec<String> {
+    let grammar = fs::read_to_string("docs/grammar.md").unwrap();
+    let mut productions: Vec<String> = Vec::new();
+    let mut inGrammar = false;
+    for line in grammar.lines() {
+        if line.starts_with("```") {
+            inGrammar = line == "```ebnf";
+            continue;
+        }
+        if !inGrammar {
+            continue;
+        }
+        if let Some((name, _)) = line.split_once('=') {
+            let name = name.trim();
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()) {
+                productions.push(name.to_string());
+            }
+        }
+    }
+    return productions;
+}
+
+#[test]
+fn acceptsEveryProduction() {
+    for case in CASES {
+        if let Err(err) = parse(case.accepted) {
+            panic!("'{}' was not accepted: {}\n{}", case.production, err, case.accepted);
+        }
+    }
+}
+
+#[test]
+fn rejectsEveryProduction() {
+    for case in CASES {
+        if parse(case.rejected).is_ok() {
+            panic!("'{}' was not rejected:\n{}", case.production, case.rejected);
+        }
+    }
+}
+
+#[test]
+fn everyProductionHasACase() {
+    let productions = grammarProductions();
+    assert!(productions.len() > 30, "Only found {:?} in docs/grammar.md", productions);
+    for production in &productions {
+        assert!(CASES.iter().any(|case| &case.production == production), "'{}' has no case in tests/grammar.rs", production);
+    }
+    for case in CASES {
+        assert!(productions.contains(&case.production.to_string()), "'{}' is not a production in docs/grammar.md", case.production);
+    }
+}
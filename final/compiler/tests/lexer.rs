@@ -0,0 +1,136 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for the errors the lexer reports
+
+//package imports
+use {
+    compiler::{models::lexer::Lexer, tokenTypeEnum},
+};
+
+//Lexes the source, returns the lexer with its tokens and reports
+fn lex(source: &str) -> Lexer {
+    let mut lexer = Lexer::newFromString("test.src", source.to_string());
+    lexer.scanThrough();
+    return lexer;
+}
+
+#[test]
+fn validSourceHasNoErrors() {
+    let lexer = lex("program p is /* a /* nested */ comment */ variable s : string; begin s := \"text\"; end program.");
+    assert!(!lexer.reports.status, "{:?}", lexer.reports.errors);
+}
+
+#[test]
+fn reportsWhereAnUnclosedCommentStarts() {
+    let lexer = lex("program p is\nbegin\n  /* outer /* inner */\nend program.");
+    assert_eq!(lexer.reports.errors, vec!["Error on line 3, column 3: this comment is never closed, the file ends inside 1 comment".to_string()]);
+
+    let lexer = lex("program p is\n/* one /* two /* three\nbegin end program.");
+    assert_eq!(lexer.reports.errors, vec!["Error on line 2, column 1: this comment is never closed, the file ends inside 3 nested comments".to_string()]);
+}
+
+#[test]
+fn reportsAnUnclosedString() {
+    let lexer = lex("program p is variable s : string;\nbegin\ns := \"no end;\nend program.");
+    assert_eq!(lexer.reports.errors.len(), 1, "{:?}", lexer.reports.errors);
+    assert!(lexer.reports.errors[0].starts_with("Error on line 3, column 6: this string is never closed"), "{:?}", lexer.reports.errors);
+
+    //The string stops at the end of its line, so the next line is still lexed
+    assert!(lexer.tokenList.iter().any(|token| token.tt == tokenTypeEnum::END_PROGRAM));
+}
+
+#[test]
+fn reportsInvalidCharactersInAString() {
+    let lexer = lex("program p is variable s : string; begin s := \"bell\u{7}\"; end program.");
+    assert_eq!(lexer.reports.errors, vec!["Error on line 1, column 51: invalid character '\\u{7}' in a string".to_string()]);
+}
+
+#[test]
+fn reportsUnknownCharactersAndKeepsLexing() {
+    let lexer = lex("program p is\nvariable x : integer;\nbegin\nx := 1 @ 2;\nx := 3 $;\nend program.");
+    assert_eq!(lexer.reports.errors, vec![
+        "Error on line 4, column 8: unknown character '@'".to_string(),
+        "Error on line 5, column 8: unknown character '$'".to_string(),
+    ]);
+
+    //The unknown characters are left out, everything after them is still there
+    assert!(!lexer.tokenList.iter().any(|token| token.tt == tokenTypeEnum::UNACCOUNTED));
+    let ints: Vec<&str> = lexer.tokenList.iter().filter(|token| token.tt == tokenTypeEnum::INT).map(|token| token.tokenString.as_str()).collect();
+    assert_eq!(ints, vec!["1", "2", "3"]);
+    assert!(lexer.tokenList.last().map(|token| token.tt == tokenTypeEnum::EOF).unwrap_or(false));
+}
+
+#[test]
+fn reportsOperatorsThatDontExist() {
+    let lexer = lex("program p is variable b : bool; begin b := 1 ! 2; b := 1 =2; end program.");
+    assert_eq!(lexer.reports.errors.len(), 2, "{:?}", lexer.reports.errors);
+    assert!(lexer.reports.errors[0].contains("'!' is not an operator"));
+    assert!(lexer.reports.errors[1].contains("'=' is not an operator"));
+}
+
+//The string in the first string token
+fn firstString(lexer: &Lexer) -> String {
+    return lexer.tokenList.iter().find(|token| token.tt == tokenTypeEnum::STRING).unwrap().tokenString.clone();
+}
+
+#[test]
+fn decodesStringEscapes() {
+    let lexer = lex("program p is variable s : string; begin s := \"a\\nb\\tc \\\"q\\\" \\\\ \\u{e9}\\u{1F600}\"; end program.");
+    assert!(!lexer.reports.status, "{:?}", lexer.reports.errors);
+    assert_eq!(firstString(&lexer), "a\nb\tc \"q\" \\ \u{e9}\u{1F600}");
+
+    //The string keeps its real length, trailing spaces included
+    let lexer = lex("program p is variable s : string; begin s := \"h\u{e9} \"; end program.");
+    assert_eq!(firstString(&lexer), "h\u{e9} ");
+
+    //\u{0} is a NUL like any other character
+    let lexer = lex("program p is variable s : string; begin s := \"a\\u{0}b\"; end program.");
+    assert!(!lexer.reports.status, "{:?}", lexer.reports.errors);
+    assert_eq!(firstString(&lexer), "a\0b");
+}
+
+#[test]
+fn reportsInvalidEscapes() {
+    let lexer = lex("program p is variable s : string;\nbegin\ns := \"a\\qb\";\ns := \"\\u{110000}\";\ns := \"\\u{0}\\u41\";\nend program.");
+    assert_eq!(lexer.reports.errors.len(), 3, "{:?}", lexer.reports.errors);
+    assert!(lexer.reports.errors[0].starts_with("Error on line 3, column 8: '\\q' is not an escape"), "{:?}", lexer.reports.errors);
+    assert!(lexer.reports.errors[1].starts_with("Error on line 4, column 7: invalid unicode escape"), "{:?}", lexer.reports.errors);
+    assert!(lexer.reports.errors[2].starts_with("Error on line 5, column 12: invalid unicode escape"), "{:?}", lexer.reports.errors);
+
+    //A bad escape is left out and the rest of the string is kept
+    let lexer = lex("program p is variable s : string; begin s := \"a\\qb\"; end program.");
+    assert_eq!(firstString(&lexer), "ab");
+}
+
+#[test]
+fn keepsLongStringsWhole() {
+    let text = format!("{}x", "\u{e9}".repeat(200));
+    let lexer = lex(&format!("program p is variable s : string; begin s := \"{}\"; end program.", text));
+    assert!(!lexer.reports.status, "{:?}", lexer.reports.errors);
+    assert_eq!(firstString(&lexer), text);
+}
+
+#[test]
+fn reportsNumbersThatArentValid() {
+    let lexer = lex("program p is variable x : integer;\nbegin\nx := 0x;\nx := 0b102;\nx := 1__0;\nx := 12ab;\nx := 1e;\nx := 1_;\nend program.");
+    assert_eq!(lexer.reports.errors, vec![
+        "Error on line 3, column 6: '0x' is not a valid number, a hex number is 0x and the digits 0-9 and a-f".to_string(),
+        "Error on line 4, column 6: '0b102' is not a valid number, a binary number is 0b and the digits 0 and 1".to_string(),
+        "Error on line 5, column 6: '1__0' is not a valid number, a '_' has to be between two digits".to_string(),
+        "Error on line 6, column 6: '12ab' is not a valid number, a number can't have letters in it".to_string(),
+        "Error on line 7, column 6: '1e' is not a valid number, an exponent needs digits after the e".to_string(),
+        "Error on line 8, column 6: '1_' is not a valid number, a '_' has to be between two digits".to_string(),
+    ]);
+
+    //The literals keep how they are written, the parser works out the values
+    let lexer = lex("x := 0xFf + 1_000 + 2.5e-3 + 3.");
+    let numbers: Vec<&str> = lexer.tokenList.iter().filter(|token| token.tt == tokenTypeEnum::INT || token.tt == tokenTypeEnum::FLOAT).map(|token| token.tokenString.as_str()).collect();
+    assert_eq!(numbers, vec!["0xFf", "1_000", "2.5e-3", "3."]);
+}
@@ -0,0 +1,145 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for the arguments given to the system linker
+
+//package imports
+use {
+    compiler::models::linker::Linker,
+    std::{env, fs, path::{Path, PathBuf}, process::Command},
+};
+
+fn position(args: &Vec<String>, arg: &str) -> usize {
+    return args.iter().position(|found| found == arg).unwrap_or_else(|| panic!("{} is not in {:?}", arg, args));
+}
+
+#[test]
+fn userLibrariesComeBeforeTheRuntime() {
+    let myLinker = Linker::new(None, vec!["/opt/helpers".to_string()], vec!["helper".to_string()]);
+    let args = myLinker.driverArgs(Path::new("prog.o"), Path::new("/rt/libfuncLib.a"), Path::new("prog"));
+    assert_eq!(&args[..3], &["-o".to_string(), "prog".to_string(), "prog.o".to_string()]);
+    assert!(position(&args, "-L/opt/helpers") < position(&args, "-lhelper"));
+    assert!(position(&args, "-lhelper") < position(&args, "/rt/libfuncLib.a"));
+    //The system libraries the runtime needs come after it
+    assert!(position(&args, "/rt/libfuncLib.a") < position(&args, "-lpthread"));
+    assert_eq!(args.last().unwrap(), "-lc");
+}
+
+#[test]
+fn ldGetsTheSameOrderBetweenTheStartFiles() {
+    let mut myLinker = Linker::new(Some("ld".to_string()), vec![], vec!["helper".to_string()]);
+    //Without the crt files there is nothing to check
+    let Some(args) = myLinker.ldArgs(Path::new("prog.o"), Path::new("/rt/libfuncLib.a"), Path::new("prog")) else { return };
+    let crt1 = args.iter().position(|arg| arg.ends_with("crt1.o")).unwrap();
+    assert!(crt1 < position(&args, "prog.o"));
+    assert!(position(&args, "prog.o") < position(&args, "-lhelper"));
+    assert!(position(&args, "-lhelper") < position(&args, "/rt/libfuncLib.a"));
+    assert!(position(&args, "/rt/libfuncLib.a") < position(&args, "-lgcc_s"));
+    //ld is told where libgcc_s is
+    let gccDir = Linker::findGccLibDir().unwrap();
+    assert!(position(&args, &format!("-L{}", gccDir.display())) < position(&args, "-lgcc_s"));
+    assert!(args.last().unwrap().ends_with("crtn.o"));
+    assert!(position(&args, "-dynamic-linker") < crt1);
+}
+
+#[test]
+fn findsBareLd() {
+    assert!(Linker::isBareLd("ld"));
+    assert!(Linker::isBareLd("/usr/bin/ld"));
+    assert!(Linker::isBareLd("ld.lld"));
+    assert!(Linker::isBareLd("/usr/bin/ld.gold"));
+    assert!(!Linker::isBareLd("cc"));
+    assert!(!Linker::isBareLd("/usr/bin/clang"));
+    assert!(!Linker::isBareLd("gold"));
+    assert!(!Linker::isBareLd("/opt/ld/cc"));
+}
+
+//The runtime archive cargo built for the tests, copied where -L can find it under its own name
+#[cfg(feature = "llvm")]
+fn runtimeLibDir(dir: &Path) -> PathBuf {
+    let depsDir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let archive = fs::read_dir(&depsDir).unwrap().filter_map(|entry| entry.ok()).map(|entry| entry.path())
+        .find(|path| {
+            let fileName = path.file_name().unwrap().to_string_lossy().to_string();
+            return fileName.starts_with("libfuncLib") && fileName.ends_with(".a");
+        })
+        .unwrap_or_else(|| panic!("No libfuncLib archive in {}", depsDir.display()));
+    let runtimeDir = dir.join("runtime");
+    fs::create_dir_all(&runtimeDir).unwrap();
+    fs::copy(&archive, runtimeDir.join("libfuncLib.a")).unwrap();
+    return runtimeDir;
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn aCHelperCanCallTheRuntime() {
+    let dir = env::temp_dir().join(format!("linker-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    //A static library whose function prints through the runtime
+    fs::write(dir.join("helper.c"), "#include <stdbool.h>\nextern bool putstring(const char *val);\nint helper(void) { putstring(\"from C\"); return 7; }\n").unwrap();
+    assert!(Command::new("cc").arg("-c").arg("-o").arg(dir.join("helper.o")).arg(dir.join("helper.c")).status().unwrap().success());
+    assert!(Command::new("ar").arg("rcs").arg(dir.join("libhelper.a")).arg(dir.join("helper.o")).status().unwrap().success());
+    fs::write(dir.join("prog.src"), "program Prog is\nexternal procedure helper : integer();\nvariable i : integer;\nbegin\ni := helper();\nputinteger(i);\nend program.\n").unwrap();
+
+    let runtimeDir = runtimeLibDir(&dir);
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("build").arg("-L").arg(&runtimeDir).arg("-L").arg(&dir).arg("-lhelper")
+        .arg("-o").arg(dir.join("prog")).arg(dir.join("prog.src")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(dir.join("prog")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "from C\n7\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn linkedExecutablesFlushTheirOutput() {
+    let dir = env::temp_dir().join(format!("linker-flush-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    //More output than funcLib buffers, the rest is written by the rt_flush main calls before it returns
+    fs::write(dir.join("prog.src"), "program Prog is\nvariable i : integer;\nbegin\nfor (i := 0; i < 20000)\nputinteger(i);\ni := i + 1;\nend for;\nputstring(\"last\");\nreturn 4;\nend program.\n").unwrap();
+
+    let runtimeDir = runtimeLibDir(&dir);
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("build").arg("-L").arg(&runtimeDir)
+        .arg("-o").arg(dir.join("prog")).arg(dir.join("prog.src")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(dir.join("prog")).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(lines.len(), 20001);
+    assert_eq!(&lines[19999..], &["19999", "last"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn theBuildPutsTheRuntimeNextToTheCompiler() {
+    let exeDir = Path::new(env!("CARGO_BIN_EXE_compiler")).parent().unwrap();
+    assert!(exeDir.join("libfuncLib.a").is_file(), "No libfuncLib.a in {}", exeDir.display());
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn ldLinksARunnableProgram() {
+    //ld needs the C start files, without them only a compiler driver can link
+    if Linker::findCrtDir().is_none() {
+        return;
+    }
+    let dir = env::temp_dir().join(format!("linker-ld-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("prog.src"), "program Prog is\nbegin\nputstring(\"linked by ld\");\nputinteger(7);\nreturn 2;\nend program.\n").unwrap();
+
+    //The runtime is the one the build put next to the compiler
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("build").arg("--linker").arg("ld")
+        .arg("-o").arg(dir.join("prog")).arg(dir.join("prog.src")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(dir.join("prog")).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "linked by ld\n7\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
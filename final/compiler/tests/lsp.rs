@@ -0,0 +1,281 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Drives the language server binary over stdio like an editor would
+
+//package imports
+use {
+    compiler::models::lsp::{readMessage, writeMessage},
+    serde_json::{json, Value},
+    std::{
+        io::BufReader,
+        process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    },
+};
+
+const URI: &str = "file:///tmp/scripted.src";
+
+//The program the client edits, the line numbers in the tests are 0 based lines of this text
+const PROGRAM: &str = "program scripted is
+global variable total : integer;
+procedure addup : integer(variable val : integer)
+	variable extra : integer;
+	begin
+		extra := val + 1;
+		return extra;
+end procedure;
+begin
+total := addup(2);
+total := putinteger(total);
+end program.
+";
+
+//A scripted client for the server binary
+struct Client {
+    server: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    nextId: i64,
+}
+
+impl Client {
+    fn start() -> Client {
+        let mut server = Command::new(env!("CARGO_BIN_EXE_lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Error starting the language server");
+        let input = server.stdin.take().unwrap();
+        let output = BufReader::new(server.stdout.take().unwrap());
+        Client { server, input, output, nextId: 1 }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        writeMessage(&mut self.input, &json!({ "jsonrpc": "2.0", "method": method, "params": params })).unwrap();
+    }
+
+    //Sends a request and returns its response, skipping any notifications in between
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.nextId;
+        self.nextId += 1;
+        writeMessage(&mut self.input, &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).unwrap();
+        loop {
+            let message = self.receive();
+            if message["id"] == id {
+                return message;
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Value {
+        return readMessage(&mut self.output).unwrap().expect("The server closed its output");
+    }
+
+    //Waits for the next diagnostics of the document
+    fn diagnostics(&mut self) -> Vec<Value> {
+        loop {
+            let message = self.receive();
+            if message["method"] == "textDocument/publishDiagnostics" {
+                assert_eq!(message["params"]["uri"], URI);
+                return message["params"]["diagnostics"].as_array().unwrap().clone();
+            }
+        }
+    }
+
+    fn open(&mut self, text: &str) -> Vec<Value> {
+        self.notify("textDocument/didOpen", json!({
+            "textDocument": { "uri": URI, "languageId": "src", "version": 1, "text": text }
+        }));
+        return self.diagnostics();
+    }
+
+    fn change(&mut self, version: i64, text: &str) -> Vec<Value> {
+        self.notify("textDocument/didChange", json!({
+            "textDocument": { "uri": URI, "version": version },
+            "contentChanges": [{ "text": text }]
+        }));
+        return self.diagnostics();
+    }
+
+    fn at(&mut self, method: &str, line: u64, character: u64) -> Value {
+        return self.request(method, json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": true }
+        }))["result"].clone();
+    }
+
+    //Shuts the server down and returns its exit status
+    fn stop(mut self) -> i32 {
+        let response = self.request("shutdown", Value::Null);
+        assert_eq!(response["result"], Value::Null);
+        self.notify("exit", Value::Null);
+        return self.server.wait().unwrap().code().unwrap_or(-1);
+    }
+}
+
+fn started() -> Client {
+    let mut client = Client::start();
+    let response = client.request("initialize", json!({ "processId": null, "rootUri": null, "capabilities": {} }));
+    let capabilities = &response["result"]["capabilities"];
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(capabilities["hoverProvider"], true);
+    client.notify("initialized", json!({}));
+    return client;
+}
+
+#[test]
+fn diagnosticsFollowChanges() {
+    let mut client = started();
+    assert_eq!(client.open(PROGRAM), Vec::<Value>::new());
+
+    //Assigning a string to the integer total is a type error on line 10 of the source
+    let broken = PROGRAM.replace("total := addup(2);", "total := \"text\";");
+    let diagnostics = client.change(2, &broken);
+    assert!(diagnostics.len() > 0, "Expected a diagnostic for {}", broken);
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 9);
+
+    //Fixing it clears the diagnostics
+    assert_eq!(client.change(3, PROGRAM), Vec::<Value>::new());
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn diagnosticsPointAtTheError() {
+    let mut client = started();
+    client.open(PROGRAM);
+
+    //A syntax error points at the token the parser didn't expect, not the whole line
+    let broken = PROGRAM.replace("total := addup(2);", "total := addup(2) 3;");
+    let diagnostics = client.change(2, &broken);
+    assert_eq!(diagnostics[0]["range"], json!({ "start": { "line": 9, "character": 18 }, "end": { "line": 9, "character": 19 } }));
+
+    //A lexer error points at the character
+    let broken = PROGRAM.replace("return extra;", "return extra ! 1;");
+    let diagnostics = client.change(3, &broken);
+    assert_eq!(diagnostics[0]["range"], json!({ "start": { "line": 6, "character": 15 }, "end": { "line": 6, "character": 16 } }));
+
+    //A type error in a procedure points at the statement it is in
+    let broken = PROGRAM.replace("extra := val + 1;", "extra := \"one\";");
+    let diagnostics = client.change(4, &broken);
+    assert_eq!(diagnostics[0]["range"], json!({ "start": { "line": 5, "character": 2 }, "end": { "line": 5, "character": 17 } }));
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn localsShadowGlobals() {
+    let mut client = started();
+    //The total in addup is its own local, the checker looks in the procedure's table before the global one
+    let shadowed = PROGRAM.replace("\tvariable extra : integer;", "\tvariable extra : integer;\n\tvariable total : integer;")
+        .replace("return extra;", "total := extra;\n\t\treturn total;");
+    assert_eq!(client.open(&shadowed), Vec::<Value>::new());
+
+    let local = client.at("textDocument/definition", 7, 2);
+    assert_eq!(local["range"]["start"], json!({ "line": 4, "character": 10 }));
+    let references = client.at("textDocument/references", 1, 17);
+    let lines: Vec<u64> = references.as_array().unwrap().iter().map(|r| r["range"]["start"]["line"].as_u64().unwrap()).collect();
+    assert_eq!(lines, vec![1, 11, 12, 12]);
+
+    let hover = client.at("textDocument/hover", 8, 10);
+    let hoverText = hover["contents"]["value"].as_str().unwrap().to_string();
+    assert!(hoverText.contains("variable total : integer") && !hoverText.contains("global"), "{}", hoverText);
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn definitionAndReferences() {
+    let mut client = started();
+    client.open(PROGRAM);
+
+    //addup in "total := addup(2);"
+    let definition = client.at("textDocument/definition", 9, 10);
+    assert_eq!(definition["uri"], URI);
+    assert_eq!(definition["range"]["start"], json!({ "line": 2, "character": 10 }));
+
+    //extra is declared on line 3 and used twice
+    let references = client.at("textDocument/references", 5, 3);
+    let lines: Vec<u64> = references.as_array().unwrap().iter().map(|r| r["range"]["start"]["line"].as_u64().unwrap()).collect();
+    assert_eq!(lines, vec![3, 5, 6]);
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn hoverShowsTypesAndSignatures() {
+    let mut client = started();
+    client.open(PROGRAM);
+
+    let procHover = client.at("textDocument/hover", 9, 10);
+    let procText = procHover["contents"]["value"].as_str().unwrap().to_string();
+    assert!(procText.contains("procedure addup : integer(variable val : integer)"), "{}", procText);
+
+    let varHover = client.at("textDocument/hover", 9, 1);
+    let varText = varHover["contents"]["value"].as_str().unwrap().to_string();
+    assert!(varText.contains("global variable total : integer"), "{}", varText);
+
+    let builtinHover = client.at("textDocument/hover", 10, 12);
+    let builtinText = builtinHover["contents"]["value"].as_str().unwrap().to_string();
+    assert!(builtinText.contains("putinteger"), "{}", builtinText);
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn completionAndSymbols() {
+    let mut client = started();
+    client.open(PROGRAM);
+
+    //Inside addup the locals, the globals, the procedure itself and the builtins are in scope
+    let completion = client.at("textDocument/completion", 5, 2);
+    let labels: Vec<String> = completion.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap().to_string()).collect();
+    for expected in ["extra", "val", "total", "addup", "putinteger", "getfloat"] {
+        assert!(labels.contains(&expected.to_string()), "Missing {} in {:?}", expected, labels);
+    }
+
+    //In the program body the locals of addup are not
+    let completion = client.at("textDocument/completion", 9, 0);
+    let labels: Vec<String> = completion.as_array().unwrap().iter().map(|item| item["label"].as_str().unwrap().to_string()).collect();
+    assert!(!labels.contains(&"extra".to_string()), "{:?}", labels);
+
+    let symbols = client.request("textDocument/documentSymbol", json!({ "textDocument": { "uri": URI } }))["result"].clone();
+    assert_eq!(symbols.as_array().unwrap().len(), 1);
+    assert_eq!(symbols[0]["name"], "addup");
+    assert_eq!(symbols[0]["range"]["start"]["line"], 2);
+    assert_eq!(symbols[0]["range"]["end"]["line"], 7);
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn nestedProceduresAndUnknownMethods() {
+    let mut client = started();
+    let program = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/testPgms/correct/multipleProcs.src")).unwrap();
+    assert_eq!(client.open(&program), Vec::<Value>::new());
+
+    let symbols = client.request("textDocument/documentSymbol", json!({ "textDocument": { "uri": URI } }))["result"].clone();
+    assert_eq!(symbols[0]["name"], "proc1");
+    assert_eq!(symbols[0]["children"][0]["name"], "proc2");
+    assert_eq!(symbols[0]["children"][0]["children"][0]["name"], "proc1");
+
+    //The inner Proc1 call in Proc2 goes to the Proc1 nested in Proc2
+    let definition = client.at("textDocument/definition", 14, 12);
+    assert_eq!(definition["range"]["start"]["line"], 7);
+
+    let response = client.request("textDocument/rename", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn unfinishedInputDoesNotStopTheServer() {
+    let mut client = started();
+    let diagnostics = client.open("program broken is\nbegin\n/* never closed");
+    assert!(diagnostics.len() > 0);
+    //The server still answers
+    assert_eq!(client.at("textDocument/hover", 0, 0), Value::Null);
+    assert_eq!(client.stop(), 0);
+}
@@ -0,0 +1,159 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for modules, import clauses and the resolver
+
+//package imports
+use {
+    compiler::models::{lexer::Lexer, parser::{Parser, Stmt}, resolver::Resolver},
+    std::{env, fs, path::PathBuf, process::Command},
+};
+
+const MATH_MODULE: &str = "module MathLib is\nglobal variable counter : integer;\nprocedure double : integer(variable x : integer)\nbegin\ncounter := counter + 1;\nreturn x * 2;\nend procedure;\nend module.\n";
+const PROGRAM: &str = "program Main is\nimport mathlib;\nvariable y : integer;\nbegin\ny := double(21);\nputinteger(y);\nputinteger(counter);\nend program.\n";
+
+//Writes the files into a new temp directory for the test
+fn writeFiles(testName: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = env::temp_dir().join(format!("modules-{}-{}", testName, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+    return dir;
+}
+
+fn parse(path: &PathBuf) -> Stmt {
+    let mut myLexer = Lexer::newFromString(&path.display().to_string(), fs::read_to_string(path).unwrap());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    return myParser.startParse().unwrap().1.unwrap();
+}
+
+#[test]
+fn modulesComeBeforeTheirImporters() {
+    let dir = writeFiles("order", &[("mathlib.src", MATH_MODULE), ("prog.src", PROGRAM)]);
+    let programPath = dir.join("prog.src");
+    let mut myResolver = Resolver::new(Vec::new());
+    assert!(myResolver.resolve(&programPath.display().to_string(), parse(&programPath)), "{:?}", myResolver.reports);
+    let names: Vec<String> = myResolver.units.iter().map(|unit| unit.name.clone()).collect();
+    assert_eq!(names, vec!["mathlib", "main"]);
+    assert!(myResolver.checkUnits(), "{:?}", myResolver.reports);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn importCyclesAreReported() {
+    let dir = writeFiles("cycle", &[
+        ("a.src", "module a is\nimport b;\nend module.\n"),
+        ("b.src", "module b is\nimport a;\nend module.\n"),
+        ("prog.src", "program Cycle is\nimport a;\nbegin\nend program.\n"),
+    ]);
+    let programPath = dir.join("prog.src");
+    let mut myResolver = Resolver::new(Vec::new());
+    assert!(!myResolver.resolve(&programPath.display().to_string(), parse(&programPath)));
+    assert!(myResolver.reports.errors.iter().any(|err| err.contains("Import cycle: a -> b -> a")), "{:?}", myResolver.reports);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn importsAreNotPassedOn() {
+    //main imports outer, only outer imports mathlib, so double is not visible in main
+    let dir = writeFiles("export", &[
+        ("mathlib.src", MATH_MODULE),
+        ("outer.src", "module outer is\nimport mathlib;\nprocedure quad : integer(variable x : integer)\nbegin\nreturn double(double(x));\nend procedure;\nend module.\n"),
+        ("prog.src", "program Main is\nimport outer;\nvariable y : integer;\nbegin\ny := double(1);\nend program.\n"),
+    ]);
+    let programPath = dir.join("prog.src");
+    let mut myResolver = Resolver::new(Vec::new());
+    assert!(myResolver.resolve(&programPath.display().to_string(), parse(&programPath)), "{:?}", myResolver.reports);
+    assert!(!myResolver.checkUnits());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missingModulesAndDuplicateNamesAreErrors() {
+    let dir = writeFiles("missing", &[
+        ("mathlib.src", MATH_MODULE),
+        ("missing.src", "program Missing is\nimport nowhere;\nbegin\nend program.\n"),
+        ("dup.src", "program Dup is\nimport mathlib;\nglobal variable counter : integer;\nbegin\nend program.\n"),
+    ]);
+    let missingPath = dir.join("missing.src");
+    let mut myResolver = Resolver::new(Vec::new());
+    assert!(!myResolver.resolve(&missingPath.display().to_string(), parse(&missingPath)));
+    assert!(myResolver.reports.errors[0].contains("nowhere.src"), "{:?}", myResolver.reports);
+
+    let dupPath = dir.join("dup.src");
+    let mut myResolver = Resolver::new(Vec::new());
+    assert!(!myResolver.resolve(&dupPath.display().to_string(), parse(&dupPath)));
+    assert!(myResolver.reports.errors[0].contains("counter is declared in both mathlib and dup"), "{:?}", myResolver.reports);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn interpreterRunsImportedProcedures() {
+    //The module is found through -I
+    let dir = writeFiles("run", &[("prog.src", PROGRAM)]);
+    let libDir = dir.join("lib");
+    fs::create_dir_all(&libDir).unwrap();
+    fs::write(libDir.join("mathlib.src"), MATH_MODULE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("--backend=interp").arg("-I").arg(&libDir).arg(dir.join("prog.src")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n1\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+//Runs the program with the LLVM backend, each module is compiled into its own module and linked into the program's
+#[cfg(feature = "llvm")]
+//Returns if it worked, what the program printed, all of stdout and stderr, which has the compiler's progress from --verbose
+fn runCompiled(dir: &PathBuf, libDir: &PathBuf) -> (bool, Vec<String>, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").arg("-v").arg("-I").arg(libDir).arg(dir.join("prog.src")).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let printed = stdout.lines().map(|line| line.to_string()).collect();
+    return (output.status.success(), printed, stdout, String::from_utf8_lossy(&output.stderr).to_string());
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn llvmLinksSeparatelyCompiledModules() {
+    let dir = writeFiles("llvm", &[("prog.src", PROGRAM)]);
+    let libDir = dir.join("lib");
+    fs::create_dir_all(&libDir).unwrap();
+    fs::write(libDir.join("mathlib.src"), MATH_MODULE).unwrap();
+
+    let (success, printed, stdout, stderr) = runCompiled(&dir, &libDir);
+    assert!(success, "{}", stderr);
+    //The module is compiled on its own and linked in, the counter it keeps is shared with the program
+    assert!(stderr.contains("Module mathlib generated\n") && stderr.contains("Linked module mathlib\n"), "{}", stderr);
+    assert_eq!(printed, vec!["42".to_string(), "1".to_string()]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "llvm")]
+fn llvmPassesStringsButRefusesArraysAcrossModules() {
+    let textModule = "module text is\nprocedure shout : string(variable words : string)\nbegin\nreturn concat(words, \"!\");\nend procedure;\nend module.\n";
+    let program = "program Main is\nimport text;\nbegin\nputstring(shout(\"hi\"));\nend program.\n";
+    let dir = writeFiles("llvmstr", &[("prog.src", program), ("text.src", textModule)]);
+    let (success, printed, stdout, stderr) = runCompiled(&dir, &dir);
+    assert!(success, "{}\n{}", stdout, stderr);
+    assert_eq!(printed, vec!["hi!".to_string()]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let arrayModule = "module arrays is\nprocedure first : integer(variable values : integer[4])\nbegin\nreturn values[0];\nend procedure;\nend module.\n";
+    let program = "program Main is\nimport arrays;\nvariable a : integer[4];\nvariable n : integer;\nbegin\na[0] := 2;\nn := first(a);\nputinteger(n);\nend program.\n";
+    let dir = writeFiles("llvmarray", &[("prog.src", program), ("arrays.src", arrayModule)]);
+    let (success, printed, stdout, stderr) = runCompiled(&dir, &dir);
+    assert!(!success);
+    assert!(stderr.contains("Procedure first can't be imported, only integer, float, bool and string parameters and return values can be passed between modules"), "{}", stderr);
+    //The interpreter doesn't lower the call, so it still runs it
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("--backend=interp").arg(dir.join("prog.src")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n", "{}", String::from_utf8_lossy(&output.stderr));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,102 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+//The optimizer only exists in the LLVM backend
+#![cfg(feature = "llvm")]
+
+//Tests for the pass pipeline of each optimization level
+
+//package imports
+use {
+    compiler::models::{compiler::Compiler, lexer::Lexer, optimizer::Optimizer, options::OptLevel, parser::{Parser, Stmt}, typechecker::{SymbolTable, SyntaxChecker}},
+    inkwell::{attributes::{Attribute, AttributeLoc}, context::Context, values::{AnyValue, PointerValue}},
+    std::collections::HashMap,
+};
+
+const PROGRAM: &str = "program Opt is\nvariable total : integer;\nvariable i : integer;\n\
+    procedure square : integer(variable x : integer)\nbegin\nreturn x * x;\nend procedure;\nbegin\n\
+    total := 0;\nfor (i := 0; i < 10)\ntotal := total + square(i);\ni := i + 1;\nend for;\nputinteger(total);\nend program.\n";
+
+const LEVELS: [OptLevel; 5] = [OptLevel::O0, OptLevel::O1, OptLevel::O2, OptLevel::O3, OptLevel::Os];
+
+fn parse(source: &str) -> Stmt {
+    let mut myLexer = Lexer::newFromString("opt.src", source.to_string());
+    myLexer.scanThrough();
+    let mut myParser = Parser::new(&mut myLexer);
+    let programAst = myParser.startParse().unwrap().1.unwrap();
+    let mut globalTable = SymbolTable::new();
+    let mut myChecker = SyntaxChecker::new(programAst.clone(), &mut globalTable, "Main".to_string());
+    assert!(myChecker.checkProgram());
+    return programAst;
+}
+
+#[test]
+fn everyLevelRunsItsOwnPasses() {
+    let pipelines: Vec<(Vec<&str>, Vec<&str>)> = LEVELS.iter().map(|level| {
+        let myOptimizer = Optimizer::new(level.clone(), false, false);
+        return (myOptimizer.functionPasses(), myOptimizer.modulePasses());
+    }).collect();
+    assert_eq!(pipelines[0], (vec![], vec![]));
+    for first in 0..pipelines.len() {
+        for second in first + 1..pipelines.len() {
+            assert_ne!(pipelines[first], pipelines[second], "{} and {} run the same passes", LEVELS[first], LEVELS[second]);
+        }
+    }
+    //-O3 unrolls loops, -Os doesn't inline or unroll
+    assert!(pipelines[3].1.contains(&"loop-unroll"));
+    assert!(!pipelines[4].1.contains(&"inline") && !pipelines[4].1.contains(&"loop-unroll"));
+    assert!(pipelines[4].1.contains(&"merge-functions"));
+}
+
+#[test]
+fn optimizedModulesStillVerify() {
+    for level in LEVELS {
+        let mut globalTable: HashMap<String, PointerValue> = HashMap::new();
+        let context = Context::create();
+        let mut myGen = Compiler::new(parse(PROGRAM), &context, &mut globalTable, "test".to_string(), "Program".to_string());
+        let module = myGen.compileProgram().unwrap().clone();
+        let myOptimizer = Optimizer::new(level.clone(), false, false);
+        let timings = myOptimizer.optimize(&module).unwrap_or_else(|err| panic!("{}: {}", level, err));
+        let passCount = myOptimizer.functionPasses().len() + myOptimizer.modulePasses().len();
+        assert_eq!(timings.len(), passCount, "{}", level);
+        assert!(module.verify().is_ok(), "{}", level);
+
+        //Only -Os asks for small code
+        let optSize = Attribute::get_named_enum_kind_id("optsize");
+        let square = module.get_function("square").unwrap();
+        assert_eq!(square.get_enum_attribute(AttributeLoc::Function, optSize).is_some(), level == OptLevel::Os, "{}", level);
+    }
+}
+
+//Compiles the program at the level and returns the optimized main function
+fn optimizedMain(level: &OptLevel) -> String {
+    let mut globalTable: HashMap<String, PointerValue> = HashMap::new();
+    let context = Context::create();
+    let mut myGen = Compiler::new(parse(PROGRAM), &context, &mut globalTable, "test".to_string(), "Program".to_string());
+    let module = myGen.compileProgram().unwrap().clone();
+    Optimizer::new(level.clone(), false, false).optimize(&module).unwrap();
+    return module.get_function("main").unwrap().print_to_string().to_string();
+}
+
+#[test]
+fn levelsChangeTheGeneratedCode() {
+    //-O2 and -O3 inline square, -Os keeps the call
+    for level in [OptLevel::O2, OptLevel::O3] {
+        let main = optimizedMain(&level);
+        assert!(!main.contains("@square"), "{} didn't inline square:\n{}", level, main);
+    }
+    let main = optimizedMain(&OptLevel::Os);
+    assert!(main.contains("call i32 @square"), "-Os inlined square:\n{}", main);
+
+    //-O2 keeps the loop, -O3 unrolls it and folds the sum
+    let main = optimizedMain(&OptLevel::O2);
+    assert!(main.contains("br i1"), "-O2 removed the loop:\n{}", main);
+    let main = optimizedMain(&OptLevel::O3);
+    assert!(!main.contains("br i1"), "-O3 didn't unroll the loop:\n{}", main);
+    assert!(main.contains("store i32 285, i32* @total"), "-O3 didn't fold the sum:\n{}", main);
+}
@@ -0,0 +1,231 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+
+//Tests for the recursive descent parser
+
+//package imports
+use {
+    compiler::models::{lexer::{Lexer, Token}, parser::{Expr, NumberWidth, Operator, Parser, Stmt}, reporting::{Reporting, Span}},
+    compiler::tokenTypeEnum,
+    std::fs,
+};
+
+//Lexes and parses a program, returns the AST or the first error
+fn parseSource(source: &str) -> Result<Stmt, String> {
+    let mut lexer = Lexer::newFromString("test.src", source.to_string());
+    lexer.scanThrough();
+    let mut parser = Parser::new(&lexer);
+    match parser.startParse() {
+        Ok((_, Some(program))) => return Ok(program),
+        Ok((_, None)) => return Err("no program".to_string()),
+        Err(reports) => return Err(reports.errors.join("\n")),
+    }
+}
+
+//The statements in the body of a program
+fn programBody(program: Stmt) -> Vec<Stmt> {
+    match program {
+        Stmt::Program(_, _, body, _, ..) => match *body {
+            Stmt::Block(stmts, _, ..) => return stmts,
+            _ => panic!("The program body is not a block"),
+        },
+        _ => panic!("Not a program"),
+    }
+}
+
+#[test]
+fn parsesEveryCorrectTestProgram() {
+    for entry in fs::read_dir("testPgms/correct").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map(|ext| ext == "src").unwrap_or(false) {
+            let source = fs::read_to_string(&path).unwrap();
+            if let Err(err) = parseSource(&source) {
+                panic!("{} did not parse: {}", path.display(), err);
+            }
+        }
+    }
+}
+
+#[test]
+fn operatorsTakeEverythingToTheirRight() {
+    let program = parseSource("program p is variable x : integer; begin x := 1 - 2 * 3; end program.").unwrap();
+    let expected = Expr::newOp(
+        Box::new(Expr::IntLiteral(1, Span::default())),
+        Operator::Sub,
+        Box::new(Expr::newOp(Box::new(Expr::IntLiteral(2, Span::default())), Operator::Mul, Box::new(Expr::IntLiteral(3, Span::default())))),
+    );
+    match &programBody(program)[0] {
+        Stmt::Assign(Expr::VarRef(name, ..), value, _, ..) => {
+            assert_eq!(name, "x");
+            assert_eq!(*value, expected);
+        }
+        other => panic!("Expected an assignment, got {:?}", other),
+    }
+}
+
+#[test]
+fn parsesNestedCallsWithSeveralArguments() {
+    let program = parseSource("program p is variable x : integer; variable a : integer[4];
+begin
+x := f(g(1, a[2]), (x + 1), h()) + 1;
+end program.").unwrap();
+    let call = Expr::ProcRef("f".to_string(), Some(vec![
+        Expr::ProcRef("g".to_string(), Some(vec![Expr::IntLiteral(1, Span::default()), Expr::ArrayRef("a".to_string(), Box::new(Expr::IntLiteral(2, Span::default())), Span::default())]), Span::default()),
+        Expr::newOp(Box::new(Expr::VarRef("x".to_string(), Span::default())), Operator::Add, Box::new(Expr::IntLiteral(1, Span::default()))),
+        Expr::ProcRef("h".to_string(), None, Span::default()),
+    ]), Span::default());
+    let expected = Expr::newOp(Box::new(call), Operator::Add, Box::new(Expr::IntLiteral(1, Span::default())));
+    match &programBody(program)[0] {
+        Stmt::Assign(_, value, _, ..) => assert_eq!(*value, expected),
+        other => panic!("Expected an assignment, got {:?}", other),
+    }
+}
+
+#[test]
+fn reportsTheLineOfASyntaxError() {
+    let err = parseSource("program p is
+variable x : integer;
+begin
+x := 1;
+x := (2 + ;
+end program.").unwrap_err();
+    assert!(err.contains("line 5"), "{}", err);
+}
+
+#[test]
+fn rejectsTokensAfterTheEnd() {
+    let err = parseSource("program p is begin end program. x := 1;").unwrap_err();
+    assert!(err.contains("end of the file"), "{}", err);
+}
+
+#[test]
+fn readsEveryFormOfNumberLiteral() {
+    let program = parseSource("program p is variable x : integer; variable y : float;
+begin
+x := 0xFF; x := 0B1010; x := 1_000_000; x := -0x10; x := -2147483648;
+y := 1.5e3; y := 2E-2; y := 3.; y := 1_0.2_5;
+end program.").unwrap();
+    let values: Vec<Expr> = programBody(program).into_iter().map(|stmt| match stmt {
+        Stmt::Assign(_, value, _, ..) => value,
+        other => panic!("Expected an assignment, got {:?}", other),
+    }).collect();
+    assert_eq!(values, vec![
+        Expr::IntLiteral(255, Span::default()), Expr::IntLiteral(10, Span::default()), Expr::IntLiteral(1000000, Span::default()), Expr::IntLiteral(-16, Span::default()), Expr::IntLiteral(-2147483648, Span::default()),
+        Expr::FloatLiteral(1500.0, Span::default()), Expr::FloatLiteral(0.02, Span::default()), Expr::FloatLiteral(3.0, Span::default()), Expr::FloatLiteral(10.25, Span::default()),
+    ]);
+}
+
+#[test]
+fn reportsLiteralsOutOfRange() {
+    for (literal, message) in [
+        ("2147483648", "the integer 2147483648 is out of range"),
+        ("-2147483649", "the integer -2147483649 is out of range"),
+        ("0x1_0000_0000", "the integer 0x1_0000_0000 is out of range"),
+        ("99999999999999999999999999999999999999999", "is out of range"),
+        ("1e39", "the float 1e39 is out of range"),
+    ] {
+        let source = format!("program p is variable x : integer;\nbegin\nx := 1 + {};\nend program.", literal);
+        let err = parseSource(&source).unwrap_err();
+        assert!(err.contains("line 3") && err.contains(message), "{}: {}", literal, err);
+    }
+}
+
+#[test]
+fn wideNumbersTakeWiderLiterals() {
+    let source = "program p is variable x : integer; variable y : float;\nbegin\nx := 9223372036854775807; x := -0x8000_0000_0000_0000; y := 1e300;\nend program.";
+    let mut lexer = Lexer::newFromString("test.src", source.to_string());
+    lexer.scanThrough();
+    let mut parser = Parser::new(&lexer);
+    parser.numberWidth = NumberWidth::Bits64;
+    let program = match parser.startParse() {
+        Ok((_, Some(program))) => program,
+        other => panic!("Wide literals didn't parse: {:?}", other.err().map(|reports| reports.errors)),
+    };
+    let values: Vec<Expr> = programBody(program).into_iter().map(|stmt| match stmt {
+        Stmt::Assign(_, value, _, ..) => value,
+        other => panic!("Expected an assignment, got {:?}", other),
+    }).collect();
+    assert_eq!(values, vec![Expr::IntLiteral(i64::MAX, Span::default()), Expr::IntLiteral(i64::MIN, Span::default()), Expr::FloatLiteral(1e300, Span::default())]);
+
+    //A wide literal is still out of range for the default width, and the wide range has an end too
+    let err = parseSource("program p is variable x : integer;\nbegin\nx := 9223372036854775807;\nend program.").unwrap_err();
+    assert!(err.contains("the integer 9223372036854775807 is out of range"), "{}", err);
+    let mut lexer = Lexer::newFromString("test.src", "program p is variable x : integer;\nbegin\nx := 9223372036854775808;\nend program.".to_string());
+    lexer.scanThrough();
+    let mut parser = Parser::new(&lexer);
+    parser.numberWidth = NumberWidth::Bits64;
+    let err = parser.startParse().err().map(|reports| reports.errors.join("\n")).unwrap_or_default();
+    assert!(err.contains("an integer is from -9223372036854775808 to 9223372036854775807"), "{}", err);
+}
+
+//Lexes a small program and repeats the tokens from the first open token to the first close token (both included), the open part before the rest
+//Building big inputs from tokens keeps the lexer out of the test
+fn repeatTokens(source: &str, open: tokenTypeEnum, close: tokenTypeEnum, times: usize) -> Vec<Token> {
+    let mut lexer = Lexer::newFromString("test.src", source.to_string());
+    lexer.scanThrough();
+    let tokens = lexer.tokenList;
+    let openAt = tokens.iter().position(|token| token.tt == open).unwrap();
+    let closeAt = tokens.iter().position(|token| token.tt == close).unwrap();
+    let mut repeated = tokens[..openAt].to_vec();
+    for _ in 0..times {
+        repeated.push(tokens[openAt].clone());
+    }
+    repeated.extend(tokens[openAt + 1..closeAt].iter().cloned());
+    for _ in 0..times {
+        repeated.push(tokens[closeAt].clone());
+    }
+    repeated.extend(tokens[closeAt + 1..].iter().cloned());
+    return repeated;
+}
+
+//Parses a list of tokens, returns the first error
+fn parseTokens(tokens: &[Token]) -> Result<Stmt, String> {
+    let mut parser = Parser::fromTokens(tokens);
+    match parser.startParse() {
+        Ok((_, Some(program))) => return Ok(program),
+        Ok((_, None)) => return Err("no program".to_string()),
+        Err(reports) => return Err(reports.errors.join("\n")),
+    }
+}
+
+#[test]
+fn deepNestingIsAnErrorInsteadOfAStackOverflow() {
+    let source = "program p is variable x : integer; begin x := (1); end program.";
+    let tokens = repeatTokens(source, tokenTypeEnum::L_PAREN, tokenTypeEnum::R_PAREN, 100000);
+    let err = parseTokens(&tokens).unwrap_err();
+    assert!(err.contains("nested"), "{}", err);
+
+    //Nesting that stays under the limit still parses
+    let tokens = repeatTokens(source, tokenTypeEnum::L_PAREN, tokenTypeEnum::R_PAREN, 200);
+    assert!(parseTokens(&tokens).is_ok());
+
+    let source = "program p is variable x : integer; begin x := f(1); end program.";
+    let tokens = repeatTokens(source, tokenTypeEnum::PROCEDURE_CALL, tokenTypeEnum::R_PAREN, 100000);
+    let err = parseTokens(&tokens).unwrap_err();
+    assert!(err.contains("nested"), "{}", err);
+}
+
+#[test]
+fn longExpressionsNestLikeParentheses() {
+    let source = "program p is variable x : integer; begin x := x + x; end program.";
+    let mut lexer = Lexer::newFromString("test.src", source.to_string());
+    lexer.scanThrough();
+    let plusAt = lexer.tokenList.iter().position(|token| token.tt == tokenTypeEnum::PLUS).unwrap();
+    let withTerms = |count: usize| {
+        let mut tokens = lexer.tokenList[..plusAt + 2].to_vec();
+        for _ in 1..count {
+            tokens.extend(lexer.tokenList[plusAt..plusAt + 2].iter().cloned());
+        }
+        tokens.extend(lexer.tokenList[plusAt + 2..].iter().cloned());
+        return tokens;
+    };
+    assert!(parseTokens(&withTerms(200)).is_ok());
+    let err = parseTokens(&withTerms(100000)).unwrap_err();
+    assert!(err.contains("nested"), "{}", err);
+}
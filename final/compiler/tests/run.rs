@@ -0,0 +1,127 @@
+//Rules
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(unused_parens)]
+#![allow(unused_mut)]
+#![allow(unused_variables)]
+//The run command JIT compiles the program, it only exists in the LLVM backend
+#![cfg(feature = "llvm")]
+
+//Tests for the run command
+
+//package imports
+use {
+    std::{env, fs, io::Write, path::Path, process::{Command, Stdio}},
+};
+
+//Runs a program with the run command, returns its exit code and everything it printed
+//The compiler only prints its progress with --verbose, so stdout is the program's output
+fn runJit(name: &str, source: &str, flags: &[&str]) -> (i32, String) {
+    let dir = env::temp_dir().join(format!("run-test-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let srcPath = dir.join(format!("{}.src", name));
+    fs::write(&srcPath, source).unwrap();
+    let result = runFile(&srcPath, flags, "");
+    fs::remove_dir_all(&dir).unwrap();
+    return result;
+}
+
+//Runs a source file with the input on stdin
+fn runFile(srcPath: &Path, flags: &[&str], input: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").args(flags).arg(srcPath)
+        .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    return (output.status.code().unwrap(), String::from_utf8_lossy(&output.stdout).to_string());
+}
+
+#[test]
+fn runsHelloWorld() {
+    let source = "program Hello is\nvariable ok : bool;\nbegin\nok := putstring(\"Hello, world\");\nend program.\n";
+    let (status, printed) = runJit("hello", source, &[]);
+    assert_eq!((status, printed.as_str()), (0, "Hello, world\n"));
+}
+
+#[test]
+fn stringsAreNotCutOff() {
+    //A literal and results past the 64 bytes strings used to be cut to, through a procedure and a global
+    let long = "a long string ".repeat(10);
+    let source = format!("program Long is\nvariable s : string;\n\
+        procedure twice : string(variable text : string)\nbegin\nreturn concat(text, text);\nend procedure;\n\
+        begin\ns := twice(\"{}\");\nputinteger(stringlength(s));\nputstring(substring(s, 270, 10));\nend program.\n", long);
+    let (status, printed) = runJit("long", &source, &[]);
+    assert_eq!((status, printed.as_str()), (0, "280\nng string \n"));
+}
+
+#[test]
+fn exitsWithTheProgramsStatus() {
+    let source = "program Status is\nvariable i : integer;\nbegin\ni := 6;\nputinteger(i);\nreturn i * 7;\nend program.\n";
+    assert_eq!(runJit("status", source, &[]), (42, "6\n".to_string()));
+    //exit ends the program where it is called
+    let source = "program Exit is\nbegin\nputinteger(1);\nexit(3);\nputinteger(2);\nend program.\n";
+    assert_eq!(runJit("exit", source, &[]), (3, "1\n".to_string()));
+    //The optimized program gives the same result
+    assert_eq!(runJit("exitO2", source, &["-O2"]), (3, "1\n".to_string()));
+}
+
+#[test]
+fn frontEndErrorsFailTheCommand() {
+    //A lexer, parser and type error each stop run and build with a status that isn't 0 and nothing on stdout
+    let sources = [
+        ("lexbad", "program Bad is\nbegin\n/* never closed\nend program.\n"),
+        ("parsebad", "program Bad is\nbegin\nputinteger(1\nend program.\n"),
+        ("typebad", "program Bad is\nvariable b : bool;\nbegin\nb := putinteger(\"one\");\nend program.\n"),
+    ];
+    for (name, source) in sources {
+        let dir = env::temp_dir().join(format!("run-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let srcPath = dir.join(format!("{}.src", name));
+        fs::write(&srcPath, source).unwrap();
+        for command in ["run", "build"] {
+            let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg(command).arg(&srcPath).arg("-o").arg(dir.join("a.out")).output().unwrap();
+            assert_eq!(output.status.code(), Some(1), "{} {}", command, name);
+            assert_eq!(String::from_utf8_lossy(&output.stdout), "", "{} {}", command, name);
+            assert!(String::from_utf8_lossy(&output.stderr).contains("Error"), "{} {}", command, name);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[test]
+fn verboseProgressGoesToStderr() {
+    let source = "program Hello is\nbegin\nputinteger(7);\nend program.\n";
+    let dir = env::temp_dir().join(format!("run-test-verbose-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let srcPath = dir.join("verbose.src");
+    fs::write(&srcPath, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compiler")).arg("run").arg("-v").arg(&srcPath).output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "7\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Program is valid") && stderr.contains("define i32 @main()"), "{}", stderr);
+}
+
+#[test]
+fn runsTheCorrectTestPrograms() {
+    //Each program in testPgms/correct, the input it reads and what it prints
+    let programs = [
+        ("arrays.src", "", "30\n130\n0\n5\n6\n6\n"),
+        ("conversions.src", "", "3628800\n3.5\n3\n3\ntrue\nfalse\n3.5\n-3\n"),
+        ("math.src", "", "1346269\n"),
+        ("multipleProcs.src", "", "3\n"),
+        ("recursiveFib.src", "5\n", "0\n1\n3\n6\n10\n"),
+        ("source.src", "", ""),
+        ("test1.src", "", ""),
+        ("test1b.src", "", ""),
+        ("test2.src", "", "144\n"),
+        ("test_heap.src", "5\nhello\n", "Enter a string:\nEnter a string:\nhello\n5\n"),
+        ("test_program_minimal.src", "", "15\n"),
+    ];
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testPgms").join("correct");
+    for (fileName, input, expected) in programs {
+        let (status, printed) = runFile(&dir.join(fileName), &[], input);
+        assert_eq!((status, printed.as_str()), (0, expected), "{}", fileName);
+    }
+}